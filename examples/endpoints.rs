@@ -1,17 +1,12 @@
 use crate::binance::Binance;
 use anyhow::Result;
 use binance_async as binance;
-use std::env::var;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    dotenv::dotenv()?;
     env_logger::init();
 
-    let api_key = var("BINANCE_KEY")?;
-    let secret_key = var("BINANCE_SECRET")?;
-
-    let bn = Binance::with_credential(&api_key, &secret_key);
+    let bn = Binance::from_env()?;
 
     // TODO broken due to a decoding issue.
     // General
@@ -25,8 +20,8 @@ async fn main() -> Result<()> {
         Err(e) => println!("Error: {}", e),
     }
 
-    match bn.get_exchange_info().await {
-        Ok(answer) => println!("ExchangeInfo: {:#?}", answer),
+    match bn.exchange_info().await {
+        Ok(answer) => println!("ExchangeInformation: {:#?}", answer),
         Err(e) => println!("Error: {}", e),
     }
 
@@ -22,6 +22,10 @@ async fn main() -> Result<()> {
         // Subscription::UserData(listen_key),
         // Subscription::MiniTickerAll,
         // Subscription::TickerAll,
+        // Subscription::BookTicker("btcusdt".to_string()),
+        // Subscription::BookTickerAll,
+        // Subscription::AveragePrice("btcusdt".to_string()),
+        // Subscription::RollingWindowTicker("btcusdt".to_string(), "1h".to_string()),
     ] {
         ws.subscribe(&sub).await?;
     }
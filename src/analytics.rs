@@ -0,0 +1,370 @@
+//! Realized and unrealized profit and loss of every asset, from the trade history.
+//!
+//! A `PnlCalculator` values everything in one quote asset and takes the trades of pairs quoted
+//! in it, plus deposits and withdrawals, in chronological order. Buying raises the average
+//! cost of an asset, selling realizes the difference to it. Fees count against the asset they
+//! were paid in. `pnl_report` walks the whole history of the account for a list of symbols.
+
+use crate::{
+    client::Binance,
+    error::Error,
+    model::{timestamp, DepositRecord, KlineSummaries, TradeHistory, WithdrawRecord},
+};
+use anyhow::Result;
+use chrono::NaiveDateTime;
+use futures::TryStreamExt;
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryFrom,
+    io::Write,
+};
+
+// `status` of a deposit credited to the account
+const DEPOSIT_SUCCESS: u8 = 1;
+// `status` of a withdrawal that left the account
+const WITHDRAW_COMPLETED: u8 = 6;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetPnl {
+    pub asset: String,
+    pub qty: f64,
+    // Average price paid for `qty`, in the quote asset
+    pub avg_cost: f64,
+    pub realized_pnl: f64,
+    // Fees paid in this asset, valued at the average cost when paid
+    pub fees: f64,
+}
+
+impl AssetPnl {
+    fn new(asset: &str) -> Self {
+        Self {
+            asset: asset.into(),
+            qty: 0.0,
+            avg_cost: 0.0,
+            realized_pnl: 0.0,
+            fees: 0.0,
+        }
+    }
+
+    #[must_use]
+    pub fn unrealized_pnl(&self, price: f64) -> f64 {
+        (price - self.avg_cost) * self.qty
+    }
+
+    fn add(&mut self, qty: f64, cost: f64) {
+        let total = self.qty + qty;
+        if total > 0.0 {
+            self.avg_cost = self.avg_cost.mul_add(self.qty, cost) / total;
+        }
+        self.qty = total;
+    }
+
+    // Takes `qty` out at the average cost, what it was sold for above that is realized
+    fn remove(&mut self, qty: f64, proceeds: f64) {
+        self.realized_pnl += self.avg_cost.mul_add(-qty, proceeds);
+        self.qty -= qty;
+        if self.qty <= 0.0 {
+            self.qty = 0.0;
+            self.avg_cost = 0.0;
+        }
+    }
+
+    fn pay_fee(&mut self, qty: f64) {
+        self.fees += qty * self.avg_cost;
+        self.remove(qty, 0.0);
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PnlCalculator {
+    quote: String,
+    assets: BTreeMap<String, AssetPnl>,
+}
+
+impl PnlCalculator {
+    #[must_use]
+    pub fn new(quote: &str) -> Self {
+        Self {
+            quote: quote.to_uppercase(),
+            assets: BTreeMap::new(),
+        }
+    }
+
+    // Every asset seen so far, sorted by name. The quote asset is left out.
+    pub fn assets(&self) -> impl Iterator<Item = &AssetPnl> {
+        self.assets.values()
+    }
+
+    #[must_use]
+    pub fn asset(&self, asset: &str) -> Option<&AssetPnl> {
+        self.assets.get(&asset.to_uppercase())
+    }
+
+    // A trade of the pair `base_asset`/`quote_asset`, which must be quoted in the calculator's
+    // quote asset
+    pub fn apply_trade(
+        &mut self,
+        trade: &TradeHistory,
+        base_asset: &str,
+        quote_asset: &str,
+    ) -> Result<()> {
+        let base_asset = base_asset.to_uppercase();
+        if !quote_asset.eq_ignore_ascii_case(&self.quote) {
+            return Err(Error::InvalidParams(format!(
+                "{} is not quoted in {}",
+                trade.symbol, self.quote
+            ))
+            .into());
+        }
+
+        let notional = trade.price * trade.qty;
+        let commission = trade.commission;
+        let commission_asset = trade.commission_asset.to_uppercase();
+        let position = self.entry(&base_asset);
+        match (trade.is_buyer, commission_asset == base_asset) {
+            (true, true) => {
+                position.add(trade.qty - commission, notional);
+                position.fees += commission * trade.price;
+            }
+            (true, false) => position.add(trade.qty, notional),
+            (false, true) => {
+                position.remove(trade.qty, notional);
+                position.pay_fee(commission);
+            }
+            (false, false) => position.remove(trade.qty, notional),
+        }
+
+        if commission_asset == self.quote {
+            let position = self.entry(&base_asset);
+            position.fees += commission;
+            position.realized_pnl -= commission;
+        } else if commission_asset != base_asset && commission > 0.0 {
+            self.entry(&commission_asset).pay_fee(commission);
+        }
+        Ok(())
+    }
+
+    // Coins credited at `price` each, e.g. the market price at the time of the deposit
+    pub fn apply_deposit(&mut self, asset: &str, amount: f64, price: f64) {
+        let asset = asset.to_uppercase();
+        if asset != self.quote {
+            self.entry(&asset).add(amount, amount * price);
+        }
+    }
+
+    // Coins that left the account, at their average cost
+    pub fn apply_withdrawal(&mut self, asset: &str, amount: f64) {
+        let asset = asset.to_uppercase();
+        if asset != self.quote {
+            let position = self.entry(&asset);
+            let cost = position.avg_cost * amount;
+            position.remove(amount, cost);
+        }
+    }
+
+    // One row per asset. Unrealized PnL is left empty for assets without a price in `prices`.
+    pub fn write_csv<W: Write>(&self, mut writer: W, prices: &HashMap<String, f64>) -> Result<()> {
+        writeln!(
+            writer,
+            "asset,qty,avg_cost,realized_pnl,unrealized_pnl,fees,quote"
+        )?;
+        for position in self.assets.values() {
+            let unrealized = prices
+                .get(&position.asset)
+                .map(|price| position.unrealized_pnl(*price).to_string())
+                .unwrap_or_default();
+            writeln!(
+                writer,
+                "{},{},{},{},{},{},{}",
+                position.asset,
+                position.qty,
+                position.avg_cost,
+                position.realized_pnl,
+                unrealized,
+                position.fees,
+                self.quote
+            )?;
+        }
+        Ok(())
+    }
+
+    fn entry(&mut self, asset: &str) -> &mut AssetPnl {
+        self.assets
+            .entry(asset.into())
+            .or_insert_with(|| AssetPnl::new(asset))
+    }
+}
+
+enum Event {
+    Trade(TradeHistory, String),
+    Deposit(DepositRecord),
+    Withdrawal(WithdrawRecord),
+}
+
+// Walks the trades of `symbols` (all quoted in `quote`) and the deposit and withdraw history.
+// Deposits are valued at the 1m kline close of their pair with `quote`, deposits without one
+// count at zero cost.
+pub async fn pnl_report(client: &Binance, quote: &str, symbols: &[&str]) -> Result<PnlCalculator> {
+    let quote = quote.to_uppercase();
+    let info = client.exchange_info().await?;
+    let mut events: Vec<(u64, Event)> = Vec::new();
+    for symbol in symbols {
+        let symbol = symbol.to_uppercase();
+        let pair = info
+            .symbols
+            .iter()
+            .find(|s| s.symbol == symbol)
+            .ok_or_else(|| Error::InvalidParams(format!("unknown symbol {symbol}")))?;
+        if pair.quote_asset != quote {
+            return Err(Error::InvalidParams(format!("{symbol} is not quoted in {quote}")).into());
+        }
+        let trades: Vec<TradeHistory> = client.my_trades_stream(&symbol).try_collect().await?;
+        events.extend(trades.into_iter().map(|trade| {
+            let time = timestamp::to_millis(&trade.time);
+            (time, Event::Trade(trade, pair.base_asset.clone()))
+        }));
+    }
+
+    let deposits: Vec<DepositRecord> = client.deposit_history_stream(None).try_collect().await?;
+    events.extend(
+        deposits
+            .into_iter()
+            .filter(|deposit| deposit.status == DEPOSIT_SUCCESS)
+            .map(|deposit| {
+                (
+                    timestamp::to_millis(&deposit.insert_time),
+                    Event::Deposit(deposit),
+                )
+            }),
+    );
+    let withdrawals: Vec<WithdrawRecord> =
+        client.withdraw_history_stream(None).try_collect().await?;
+    events.extend(
+        withdrawals
+            .into_iter()
+            .filter(|withdrawal| withdrawal.status == WITHDRAW_COMPLETED)
+            .filter_map(|withdrawal| {
+                Some((
+                    parse_apply_time(&withdrawal.apply_time)?,
+                    Event::Withdrawal(withdrawal),
+                ))
+            }),
+    );
+    events.sort_by_key(|(time, _)| *time);
+
+    let mut calculator = PnlCalculator::new(&quote);
+    for (time, event) in events {
+        match event {
+            Event::Trade(trade, base_asset) => {
+                calculator.apply_trade(&trade, &base_asset, &quote)?;
+            }
+            Event::Deposit(deposit) => {
+                let price = price_at(client, &deposit.coin, &quote, time).await?;
+                calculator.apply_deposit(&deposit.coin, deposit.amount, price);
+            }
+            Event::Withdrawal(withdrawal) => {
+                calculator.apply_withdrawal(
+                    &withdrawal.coin,
+                    withdrawal.amount + withdrawal.transaction_fee,
+                );
+            }
+        }
+    }
+    Ok(calculator)
+}
+
+// Close of the 1m kline of `asset`/`quote` containing `time`, zero without such a pair
+async fn price_at(client: &Binance, asset: &str, quote: &str, time: u64) -> Result<f64> {
+    if asset.eq_ignore_ascii_case(quote) {
+        return Ok(1.0);
+    }
+    let symbol = format!("{asset}{quote}");
+    match client
+        .get_klines(&symbol, "1m", 1, time - time % 60_000, None)
+        .await
+    {
+        Ok(KlineSummaries::AllKlineSummaries(klines)) => {
+            Ok(klines.first().map_or(0.0, |kline| kline.close))
+        }
+        Err(e) => match e.downcast_ref::<Error>() {
+            Some(Error::BinanceError { .. }) => Ok(0.0),
+            _ => Err(e),
+        },
+    }
+}
+
+// Withdrawals carry their time as "2021-04-29 16:08:00" (UTC)
+fn parse_apply_time(apply_time: &str) -> Option<u64> {
+    let time = NaiveDateTime::parse_from_str(apply_time, "%Y-%m-%d %H:%M:%S").ok()?;
+    u64::try_from(time.and_utc().timestamp_millis()).ok()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_apply_time, PnlCalculator};
+    use crate::model::TradeHistory;
+    use anyhow::Result;
+    use serde_json::from_str;
+    use std::collections::HashMap;
+
+    fn trade(
+        is_buyer: bool,
+        price: &str,
+        qty: &str,
+        commission: &str,
+        asset: &str,
+    ) -> TradeHistory {
+        from_str(&format!(
+            r#"{{"symbol":"BTCUSDT","id":1,"orderId":1,"price":"{price}","qty":"{qty}","commission":"{commission}","commissionAsset":"{asset}","time":1499865549590,"isBuyer":{is_buyer},"isMaker":false,"isBestMatch":true}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn average_cost() -> Result<()> {
+        let mut calculator = PnlCalculator::new("usdt");
+        calculator.apply_trade(&trade(true, "100", "1", "0", "USDT"), "BTC", "USDT")?;
+        calculator.apply_trade(&trade(true, "200", "1", "1", "USDT"), "BTC", "USDT")?;
+        calculator.apply_deposit("BNB", 2.0, 300.0);
+        calculator.apply_trade(&trade(false, "250", "1", "0.01", "BNB"), "BTC", "USDT")?;
+
+        let btc = calculator.asset("btc").unwrap();
+        assert!((btc.avg_cost - 150.0).abs() < 1e-9);
+        assert!((btc.qty - 1.0).abs() < 1e-9);
+        // 100 on the sale minus the USDT fee
+        assert!((btc.realized_pnl - 99.0).abs() < 1e-9);
+        assert!((btc.unrealized_pnl(170.0) - 20.0).abs() < 1e-9);
+
+        let bnb = calculator.asset("BNB").unwrap();
+        assert!((bnb.qty - 1.99).abs() < 1e-9);
+        assert!((bnb.fees - 3.0).abs() < 1e-9);
+
+        calculator.apply_withdrawal("BTC", 1.0);
+        assert!(calculator.asset("BTC").unwrap().qty.abs() < 1e-9);
+        assert!(calculator
+            .apply_trade(&trade(true, "1", "1", "0", "BTC"), "ETH", "BTC")
+            .is_err());
+
+        let mut csv = Vec::new();
+        let prices = HashMap::from([("BNB".to_string(), 310.0)]);
+        calculator.write_csv(&mut csv, &prices)?;
+        let csv = String::from_utf8(csv)?;
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("asset,qty,avg_cost,realized_pnl,unrealized_pnl,fees,quote")
+        );
+        assert!(lines.next().unwrap().starts_with("BNB,1.99,300,-3,"));
+        assert!(lines.next().unwrap().starts_with("BTC,0,0,99,,"));
+        Ok(())
+    }
+
+    #[test]
+    fn withdraw_apply_time() {
+        assert_eq!(
+            parse_apply_time("2021-04-29 16:08:00"),
+            Some(1_619_712_480_000)
+        );
+        assert_eq!(parse_apply_time("yesterday"), None);
+    }
+}
@@ -0,0 +1,120 @@
+//! Short-lived cache for hot public endpoints, shared by all clones of a client.
+
+use anyhow::Result;
+use chrono::Utc;
+use std::{
+    any::Any,
+    collections::HashMap,
+    convert::TryFrom,
+    fmt,
+    future::Future,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+struct Entry {
+    fetched_ms: i64,
+    value: Arc<dyn Any + Send + Sync>,
+}
+
+#[derive(Default)]
+pub struct ResponseCache {
+    // `None` disables the cache
+    ttl: Option<Duration>,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+impl ResponseCache {
+    pub fn new(ttl: Option<Duration>) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::default(),
+        }
+    }
+
+    // `None` if the cache is disabled
+    pub const fn ttl(&self) -> Option<Duration> {
+        self.ttl
+    }
+
+    // The value cached under `key` if it is younger than the TTL, otherwise the result of `fetch`
+    pub async fn get_or_fetch<T, F>(&self, key: String, fetch: F) -> Result<T>
+    where
+        T: Clone + Send + Sync + 'static,
+        F: Future<Output = Result<T>>,
+    {
+        let Some(ttl) = self.ttl else {
+            return fetch.await;
+        };
+        if let Some(value) = self.get(&key, ttl, Utc::now().timestamp_millis()) {
+            return Ok(value);
+        }
+        let value = fetch.await?;
+        self.insert(key, value.clone(), Utc::now().timestamp_millis());
+        Ok(value)
+    }
+
+    fn get<T: Clone + 'static>(&self, key: &str, ttl: Duration, now_ms: i64) -> Option<T> {
+        let entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        let value = entries
+            .get(key)
+            .filter(|entry| is_fresh(entry.fetched_ms, ttl, now_ms))
+            .map(|entry| Arc::clone(&entry.value));
+        drop(entries);
+        value?.downcast_ref::<T>().cloned()
+    }
+
+    fn insert<T: Send + Sync + 'static>(&self, key: String, value: T, now_ms: i64) {
+        let entry = Entry {
+            fetched_ms: now_ms,
+            value: Arc::new(value),
+        };
+        self.entries
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(key, entry);
+    }
+}
+
+// Whether a value fetched at `fetched_ms` is younger than `ttl` at `now_ms`
+pub fn is_fresh(fetched_ms: i64, ttl: Duration, now_ms: i64) -> bool {
+    let ttl_ms = i64::try_from(ttl.as_millis()).unwrap_or(i64::MAX);
+    now_ms.saturating_sub(fetched_ms) < ttl_ms
+}
+
+impl fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let entries = self.entries.lock().map_or(0, |entries| entries.len());
+        f.debug_struct("ResponseCache")
+            .field("ttl", &self.ttl)
+            .field("entries", &entries)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ResponseCache;
+    use std::time::Duration;
+
+    #[test]
+    fn expires_after_ttl() {
+        let ttl = Duration::from_secs(1);
+        let cache = ResponseCache::new(Some(ttl));
+        cache.insert("/ticker/price?symbol=BTCUSDT".into(), 42_u32, 10_000);
+        assert_eq!(
+            cache.get::<u32>("/ticker/price?symbol=BTCUSDT", ttl, 10_999),
+            Some(42)
+        );
+        assert_eq!(
+            cache.get::<u32>("/ticker/price?symbol=BTCUSDT", ttl, 11_000),
+            None
+        );
+        assert_eq!(cache.get::<u32>("/ticker/price", ttl, 10_500), None);
+        // A value of another type under the same key is a miss, not a panic
+        assert_eq!(
+            cache.get::<String>("/ticker/price?symbol=BTCUSDT", ttl, 10_500),
+            None
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! OHLCV candles built locally from trade streams, at any interval including sub-minute ones.
+//!
+//! Feed a `CandleAggregator` the `@trade` or `@aggTrade` messages of one or more symbols. Every
+//! trade returns the candle it updated, and the previous candle of the symbol once a trade
+//! opens the next interval. `close_expired` closes candles no trade followed up on.
+
+use crate::{error::Error, model::websocket::BinanceWebsocketMessage};
+use anyhow::Result;
+use std::{collections::HashMap, convert::TryFrom, time::Duration};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candle {
+    pub symbol: String,
+    // Start of the interval, milliseconds
+    pub open_time: u64,
+    // Last millisecond of the interval
+    pub close_time: u64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub quote_volume: f64,
+    pub trades: u64,
+    // No further trades go into this candle
+    pub closed: bool,
+}
+
+impl Candle {
+    fn open(symbol: &str, open_time: u64, interval_ms: u64, price: f64, qty: f64) -> Self {
+        Self {
+            symbol: symbol.into(),
+            open_time,
+            close_time: open_time + interval_ms - 1,
+            open: price,
+            high: price,
+            low: price,
+            close: price,
+            volume: qty,
+            quote_volume: price * qty,
+            trades: 1,
+            closed: false,
+        }
+    }
+
+    fn add(&mut self, price: f64, qty: f64) {
+        self.high = self.high.max(price);
+        self.low = self.low.min(price);
+        self.close = price;
+        self.volume += qty;
+        self.quote_volume += price * qty;
+        self.trades += 1;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CandleAggregator {
+    interval_ms: u64,
+    // The candle in progress of every symbol
+    candles: HashMap<String, Candle>,
+}
+
+impl CandleAggregator {
+    pub fn new(interval: Duration) -> Result<Self> {
+        let interval_ms = u64::try_from(interval.as_millis()).unwrap_or_default();
+        if interval_ms == 0 {
+            return Err(Error::InvalidCandleInterval(interval).into());
+        }
+        Ok(Self {
+            interval_ms,
+            candles: HashMap::new(),
+        })
+    }
+
+    // Takes trades and aggregate trades, other messages return nothing
+    pub fn apply(&mut self, message: &BinanceWebsocketMessage) -> Vec<Candle> {
+        match message {
+            BinanceWebsocketMessage::Trade(trade) => self.apply_trade(
+                &trade.symbol,
+                trade.price,
+                trade.qty,
+                u64::try_from(trade.trade_order_time).unwrap_or_default(),
+            ),
+            BinanceWebsocketMessage::AggregateTrade(trade) => self.apply_trade(
+                &trade.symbol,
+                trade.price,
+                trade.qty,
+                trade.trade_order_time,
+            ),
+            _ => Vec::new(),
+        }
+    }
+
+    // The closed previous candle if the trade opened a new interval, then the candle in progress.
+    // Trades older than the candle in progress are dropped.
+    pub fn apply_trade(&mut self, symbol: &str, price: f64, qty: f64, time: u64) -> Vec<Candle> {
+        let open_time = time - time % self.interval_ms;
+        let mut updates = Vec::new();
+        match self.candles.get_mut(symbol) {
+            Some(current) if current.open_time == open_time => current.add(price, qty),
+            Some(current) if current.open_time > open_time => return updates,
+            Some(current) => {
+                let candle = Candle::open(symbol, open_time, self.interval_ms, price, qty);
+                let mut previous = std::mem::replace(current, candle);
+                previous.closed = true;
+                updates.push(previous);
+            }
+            None => {
+                let candle = Candle::open(symbol, open_time, self.interval_ms, price, qty);
+                self.candles.insert(symbol.into(), candle);
+            }
+        }
+        updates.extend(self.candles.get(symbol).cloned());
+        updates
+    }
+
+    // Closes the candles whose interval ended before `now` (milliseconds) without another trade
+    pub fn close_expired(&mut self, now: u64) -> Vec<Candle> {
+        let (expired, open): (HashMap<_, _>, HashMap<_, _>) = std::mem::take(&mut self.candles)
+            .into_iter()
+            .partition(|(_, candle)| candle.close_time < now);
+        self.candles = open;
+        expired
+            .into_values()
+            .map(|mut candle| {
+                candle.closed = true;
+                candle
+            })
+            .collect()
+    }
+
+    // The candle in progress of `symbol`
+    #[must_use]
+    pub fn current(&self, symbol: &str) -> Option<&Candle> {
+        self.candles.get(symbol)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CandleAggregator;
+    use crate::model::websocket::parse_event;
+    use anyhow::Result;
+    use std::time::Duration;
+
+    #[test]
+    fn sub_minute_candles() -> Result<()> {
+        let mut aggregator = CandleAggregator::new(Duration::from_secs(15))?;
+        let trade = parse_event(
+            r#"{"e":"trade","E":1672515782136,"s":"BNBBTC","t":12345,"p":"0.001","q":"100","T":1672515782136,"m":true,"M":true}"#,
+        )?;
+        let updates = aggregator.apply(&trade);
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].open_time, 1_672_515_780_000);
+        assert_eq!(updates[0].close_time, 1_672_515_794_999);
+        assert!(!updates[0].closed);
+
+        aggregator.apply_trade("BNBBTC", 0.003, 50.0, 1_672_515_790_000);
+        aggregator.apply_trade("BNBBTC", 0.0005, 10.0, 1_672_515_791_000);
+        // Late trade of an interval already closed
+        assert!(aggregator
+            .apply_trade("BNBBTC", 0.1, 1.0, 1_672_515_770_000)
+            .is_empty());
+
+        let updates = aggregator.apply_trade("BNBBTC", 0.002, 1.0, 1_672_515_795_000);
+        assert_eq!(updates.len(), 2);
+        let closed = &updates[0];
+        assert!(closed.closed);
+        assert_eq!(closed.trades, 3);
+        assert!((closed.high - 0.003).abs() < f64::EPSILON);
+        assert!((closed.low - 0.0005).abs() < f64::EPSILON);
+        assert!((closed.close - 0.0005).abs() < f64::EPSILON);
+        assert!((closed.volume - 160.0).abs() < f64::EPSILON);
+        assert_eq!(updates[1].open_time, 1_672_515_795_000);
+
+        let expired = aggregator.close_expired(1_672_515_810_000);
+        assert_eq!(expired.len(), 1);
+        assert!(expired[0].closed);
+        assert!(aggregator.current("BNBBTC").is_none());
+        assert!(CandleAggregator::new(Duration::from_micros(10)).is_err());
+        Ok(())
+    }
+}
@@ -1,27 +1,24 @@
-use crate::error::Error;
+use crate::error::{ClientOrderId, Error};
 use crate::transport::Version;
 use crate::{
     client::Binance,
-    model::{AccountInformation, Balance, Order, OrderCanceled, TradeHistory, Transaction},
+    model::{
+        pair::IntoTradingPair,
+        request::{
+            AllOrdersRequest, BnbBurnRequest, MyTradesRequest, NewOrderRequest, OpenOrdersRequest,
+            OrderQueryRequest, SymbolRequest,
+        },
+        timestamp, AccountInformation, AccountStatus, ApiKeyPermissions, ApiTradingStatus, Balance,
+        BnbBurnStatus, Order, OrderCanceled, OrderResponse, OrderType, RequestTiming, Side,
+        TimedOrder, TradeHistory, Transaction,
+    },
+    signing,
 };
-use anyhow::Result;
-use serde_json::json;
-use std::collections::HashMap;
-
-const ORDER_TYPE_LIMIT: &str = "LIMIT";
-const ORDER_TYPE_MARKET: &str = "MARKET";
-const ORDER_SIDE_BUY: &str = "BUY";
-const ORDER_SIDE_SELL: &str = "SELL";
-const TIME_IN_FORCE_GTC: &str = "GTC";
-
-struct OrderRequest {
-    pub symbol: String,
-    pub qty: f64,
-    pub price: f64,
-    pub order_side: String,
-    pub order_type: String,
-    pub time_in_force: String,
-}
+use anyhow::{Context, Result};
+use serde::de::DeserializeOwned;
+use uuid::Uuid;
+
+const ORDER_DOES_NOT_EXIST: i64 = -2013;
 
 impl Binance {
     // Account Information
@@ -34,18 +31,23 @@ impl Binance {
 
     // Balance for ONE Asset
     pub async fn get_balance(&self, asset: &str) -> Result<Balance> {
-        let asset = asset.to_string().to_uppercase();
         self.get_account()
             .await?
             .balances
             .into_iter()
-            .find(|balance| balance.asset == asset)
+            .find(|balance| balance.asset.eq_ignore_ascii_case(asset))
             .ok_or_else(|| Error::AssetsNotFound.into())
     }
 
     // Current open orders for ONE symbol
-    pub async fn get_open_orders(&self, symbol: &str) -> Result<Vec<Order>> {
-        let params = json! {{"symbol": symbol.to_uppercase()}};
+    pub async fn get_open_orders<S>(&self, symbol: S) -> Result<Vec<Order>>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = OpenOrdersRequest {
+            symbol: Some(symbol.into()),
+        };
         let orders = self
             .transport
             .signed_get(Version::V3, "/openOrders", Some(params))
@@ -62,9 +64,71 @@ impl Binance {
         Ok(orders)
     }
 
+    // All orders for ONE symbol: active, canceled, or filled
+    pub async fn get_all_orders<S, O, L>(
+        &self,
+        symbol: S,
+        order_id: O,
+        limit: L,
+    ) -> Result<Vec<Order>>
+    where
+        S: IntoTradingPair,
+        O: Into<Option<u64>>,
+        L: Into<Option<u16>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = AllOrdersRequest {
+            symbol: symbol.into(),
+            order_id: order_id.into(),
+            limit: limit.into(),
+        };
+
+        let orders = self
+            .transport
+            .signed_get(Version::V3, "/allOrders", Some(params))
+            .await?;
+        Ok(orders)
+    }
+
+    // Look up an order by the client order id it was placed with, `None` if it never reached the book
+    pub async fn find_order_by_client_id<S>(
+        &self,
+        symbol: S,
+        client_order_id: &str,
+    ) -> Result<Option<Order>>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = OrderQueryRequest {
+            symbol: symbol.into(),
+            orig_client_order_id: Some(client_order_id.into()),
+            ..OrderQueryRequest::default()
+        };
+        let order = self
+            .transport
+            .signed_get(Version::V3, "/order", Some(params))
+            .await;
+        match order {
+            Ok(order) => Ok(Some(order)),
+            Err(e) => match e.downcast_ref::<Error>() {
+                Some(Error::BinanceError { code, .. }) if *code == ORDER_DOES_NOT_EXIST => Ok(None),
+                _ => Err(e),
+            },
+        }
+    }
+
     // Check an order's status
-    pub async fn order_status(&self, symbol: &str, order_id: u64) -> Result<Order> {
-        let params = json! {{"symbol": symbol.to_uppercase(), "orderId": order_id}};
+    pub async fn order_status<S>(&self, symbol: S, order_id: u64) -> Result<Order>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = OrderQueryRequest {
+            symbol: symbol.into(),
+            order_id: Some(order_id),
+            ..OrderQueryRequest::default()
+        };
         Ok(self
             .transport
             .signed_get(Version::V3, "/order", Some(params))
@@ -72,84 +136,326 @@ impl Binance {
     }
 
     // Place a LIMIT order - BUY
-    pub async fn limit_buy(&self, symbol: &str, qty: f64, price: f64) -> Result<Transaction> {
-        let order = OrderRequest {
-            symbol: symbol.into(),
+    pub async fn limit_buy<S>(&self, symbol: S, qty: f64, price: f64) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = NewOrderRequest::limit(&symbol, Side::Buy, qty, price);
+        self.place_order(order).await
+    }
+
+    // Place a LIMIT order - SELL
+    pub async fn limit_sell<S>(&self, symbol: S, qty: f64, price: f64) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = NewOrderRequest::limit(&symbol, Side::Sell, qty, price);
+        self.place_order(order).await
+    }
+
+    // Place a MARKET order - BUY
+    pub async fn market_buy<S>(&self, symbol: S, qty: f64) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = NewOrderRequest::market(&symbol, Side::Buy, qty);
+        self.place_order(order).await
+    }
+
+    // Place a MARKET order - SELL
+    pub async fn market_sell<S>(&self, symbol: S, qty: f64) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = NewOrderRequest::market(&symbol, Side::Sell, qty);
+        self.place_order(order).await
+    }
+
+    // Place a MARKET order - BUY, spending exactly `quote_qty` of the quote asset
+    pub async fn market_buy_with_quote_qty<S>(
+        &self,
+        symbol: S,
+        quote_qty: f64,
+    ) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = NewOrderRequest::market_quote(&symbol, Side::Buy, quote_qty);
+        self.place_order(order).await
+    }
+
+    // Place a MARKET order - SELL, receiving exactly `quote_qty` of the quote asset
+    pub async fn market_sell_with_quote_qty<S>(
+        &self,
+        symbol: S,
+        quote_qty: f64,
+    ) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = NewOrderRequest::market_quote(&symbol, Side::Sell, quote_qty);
+        self.place_order(order).await
+    }
+
+    // Place a STOP_LOSS order - SELL, selling at market once the price falls to `stop_price`
+    pub async fn stop_loss_sell<S>(
+        &self,
+        symbol: S,
+        qty: f64,
+        stop_price: f64,
+    ) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order =
+            NewOrderRequest::triggered(&symbol, Side::Sell, OrderType::StopLoss, qty, stop_price);
+        self.place_order(order).await
+    }
+
+    // Place a STOP_LOSS order - BUY, buying at market once the price rises to `stop_price`
+    pub async fn stop_loss_buy<S>(
+        &self,
+        symbol: S,
+        qty: f64,
+        stop_price: f64,
+    ) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order =
+            NewOrderRequest::triggered(&symbol, Side::Buy, OrderType::StopLoss, qty, stop_price);
+        self.place_order(order).await
+    }
+
+    // Place a STOP_LOSS_LIMIT order - SELL at `price` once the price falls to `stop_price`
+    pub async fn stop_loss_limit_sell<S>(
+        &self,
+        symbol: S,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+    ) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = NewOrderRequest::triggered_limit(
+            &symbol,
+            Side::Sell,
+            OrderType::StopLossLimit,
             qty,
             price,
-            order_side: ORDER_SIDE_BUY.to_string(),
-            order_type: ORDER_TYPE_LIMIT.to_string(),
-            time_in_force: TIME_IN_FORCE_GTC.to_string(),
-        };
-        let params = Self::build_order(order);
+            stop_price,
+        );
+        self.place_order(order).await
+    }
 
-        let transaction = self
-            .transport
-            .signed_post(Version::V3, "/order", Some(params))
-            .await?;
+    // Place a STOP_LOSS_LIMIT order - BUY at `price` once the price rises to `stop_price`
+    pub async fn stop_loss_limit_buy<S>(
+        &self,
+        symbol: S,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+    ) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = NewOrderRequest::triggered_limit(
+            &symbol,
+            Side::Buy,
+            OrderType::StopLossLimit,
+            qty,
+            price,
+            stop_price,
+        );
+        self.place_order(order).await
+    }
 
-        Ok(transaction)
+    // Place a TAKE_PROFIT order - SELL, selling at market once the price rises to `stop_price`
+    pub async fn take_profit_sell<S>(
+        &self,
+        symbol: S,
+        qty: f64,
+        stop_price: f64,
+    ) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order =
+            NewOrderRequest::triggered(&symbol, Side::Sell, OrderType::TakeProfit, qty, stop_price);
+        self.place_order(order).await
     }
 
-    // Place a LIMIT order - SELL
-    pub async fn limit_sell(&self, symbol: &str, qty: f64, price: f64) -> Result<Transaction> {
-        let order = OrderRequest {
-            symbol: symbol.into(),
+    // Place a TAKE_PROFIT order - BUY, buying at market once the price falls to `stop_price`
+    pub async fn take_profit_buy<S>(
+        &self,
+        symbol: S,
+        qty: f64,
+        stop_price: f64,
+    ) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order =
+            NewOrderRequest::triggered(&symbol, Side::Buy, OrderType::TakeProfit, qty, stop_price);
+        self.place_order(order).await
+    }
+
+    // Place a TAKE_PROFIT_LIMIT order - SELL at `price` once the price rises to `stop_price`
+    pub async fn take_profit_limit_sell<S>(
+        &self,
+        symbol: S,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+    ) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = NewOrderRequest::triggered_limit(
+            &symbol,
+            Side::Sell,
+            OrderType::TakeProfitLimit,
             qty,
             price,
-            order_side: ORDER_SIDE_SELL.to_string(),
-            order_type: ORDER_TYPE_LIMIT.to_string(),
-            time_in_force: TIME_IN_FORCE_GTC.to_string(),
-        };
-        let params = Self::build_order(order);
-        let transaction = self
-            .transport
-            .signed_post(Version::V3, "/order", Some(params))
-            .await?;
-
-        Ok(transaction)
+            stop_price,
+        );
+        self.place_order(order).await
     }
 
-    // Place a MARKET order - BUY
-    pub async fn market_buy(&self, symbol: &str, qty: f64) -> Result<Transaction> {
-        let order = OrderRequest {
-            symbol: symbol.into(),
+    // Place a TAKE_PROFIT_LIMIT order - BUY at `price` once the price falls to `stop_price`
+    pub async fn take_profit_limit_buy<S>(
+        &self,
+        symbol: S,
+        qty: f64,
+        price: f64,
+        stop_price: f64,
+    ) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = NewOrderRequest::triggered_limit(
+            &symbol,
+            Side::Buy,
+            OrderType::TakeProfitLimit,
             qty,
-            price: 0.0,
-            order_side: ORDER_SIDE_BUY.to_string(),
-            order_type: ORDER_TYPE_MARKET.to_string(),
-            time_in_force: TIME_IN_FORCE_GTC.to_string(),
-        };
-        let params = Self::build_order(order);
-        let transaction = self
-            .transport
-            .signed_post(Version::V3, "/order", Some(params))
-            .await?;
+            price,
+            stop_price,
+        );
+        self.place_order(order).await
+    }
 
-        Ok(transaction)
+    // Place a trailing STOP_LOSS order - SELL, selling at market once the price falls
+    // `trailing_delta` basis points below its high. With `activation_price` the trailing only
+    // starts once the price reaches it.
+    pub async fn trailing_stop_sell<S, A>(
+        &self,
+        symbol: S,
+        qty: f64,
+        trailing_delta: u32,
+        activation_price: A,
+    ) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+        A: Into<Option<f64>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = trailing_stop(&symbol, Side::Sell, qty, trailing_delta, activation_price);
+        self.place_order(order).await
     }
 
-    // Place a MARKET order - SELL
-    pub async fn market_sell(&self, symbol: &str, qty: f64) -> Result<Transaction> {
-        let order = OrderRequest {
-            symbol: symbol.into(),
-            qty,
-            price: 0.0,
-            order_side: ORDER_SIDE_SELL.to_string(),
-            order_type: ORDER_TYPE_MARKET.to_string(),
-            time_in_force: TIME_IN_FORCE_GTC.to_string(),
-        };
-        let params = Self::build_order(order);
-        let transaction = self
-            .transport
-            .signed_post(Version::V3, "/order", Some(params))
+    // Place a trailing STOP_LOSS order - BUY, buying at market once the price rises
+    // `trailing_delta` basis points above its low
+    pub async fn trailing_stop_buy<S, A>(
+        &self,
+        symbol: S,
+        qty: f64,
+        trailing_delta: u32,
+        activation_price: A,
+    ) -> Result<Transaction>
+    where
+        S: IntoTradingPair,
+        A: Into<Option<f64>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = trailing_stop(&symbol, Side::Buy, qty, trailing_delta, activation_price);
+        self.place_order(order).await
+    }
+
+    // Place a LIMIT order at `price`, or a MARKET order without one. The response type picks
+    // how much the exchange reports back: `OrderAck` returns fastest, `OrderFull` has the fills.
+    pub async fn new_order<S, R, P>(&self, symbol: S, side: Side, qty: f64, price: P) -> Result<R>
+    where
+        S: IntoTradingPair,
+        R: OrderResponse,
+        P: Into<Option<f64>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = price.into().map_or_else(
+            || NewOrderRequest::market(&symbol, side.clone(), qty),
+            |price| NewOrderRequest::limit(&symbol, side.clone(), qty, price),
+        );
+        self.place_order(order.response_type(R::RESPONSE_TYPE))
+            .await
+    }
+
+    // `new_order` with the timing of the request, for telling the exchange's processing time
+    // apart from the network latency. The rounding to the symbol's filters happens before.
+    pub async fn new_order_timed<S, R, P>(
+        &self,
+        symbol: S,
+        side: Side,
+        qty: f64,
+        price: P,
+    ) -> Result<TimedOrder<R>>
+    where
+        S: IntoTradingPair,
+        R: OrderResponse,
+        P: Into<Option<f64>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let order = price.into().map_or_else(
+            || NewOrderRequest::market(&symbol, side.clone(), qty),
+            |price| NewOrderRequest::limit(&symbol, side.clone(), qty, price),
+        );
+        let order = self
+            .prepare_order(order.response_type(R::RESPONSE_TYPE))
             .await?;
-        Ok(transaction)
+        let sent_at = signing::timestamp();
+        let order: R = self.post_order(order).await?;
+        let timing = RequestTiming {
+            sent_at,
+            received_at: signing::timestamp(),
+            server_transact_time: timestamp::to_millis(order.transact_time()),
+        };
+        Ok(TimedOrder { order, timing })
     }
 
     // Check an order's status
-    pub async fn cancel_order(&self, symbol: &str, order_id: u64) -> Result<OrderCanceled> {
-        let params = json! {{"symbol":symbol, "orderId":order_id}};
+    pub async fn cancel_order<S>(&self, symbol: S, order_id: u64) -> Result<OrderCanceled>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = OrderQueryRequest {
+            symbol: symbol.into(),
+            order_id: Some(order_id),
+            ..OrderQueryRequest::default()
+        };
         let order_canceled = self
             .transport
             .signed_delete(Version::V3, "/order", Some(params))
@@ -158,8 +464,14 @@ impl Binance {
     }
 
     // Trade history
-    pub async fn trade_history(&self, symbol: &str) -> Result<Vec<TradeHistory>> {
-        let params = json! {{"symbol":symbol.to_uppercase()}};
+    pub async fn trade_history<S>(&self, symbol: S) -> Result<Vec<TradeHistory>>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = SymbolRequest {
+            symbol: symbol.into(),
+        };
         let trade_history = self
             .transport
             .signed_get(Version::V3, "/myTrades", Some(params))
@@ -167,19 +479,136 @@ impl Binance {
         Ok(trade_history)
     }
 
-    fn build_order(order: OrderRequest) -> HashMap<&'static str, String> {
-        let mut params: HashMap<&str, String> = maplit::hashmap! {
-            "symbol" => order.symbol,
-            "side" => order.order_side,
-            "type" => order.order_type,
-            "quantity" => order.qty.to_string(),
+    // Trades for ONE symbol starting at trade id `from_id`
+    pub async fn get_my_trades<S, F, L>(
+        &self,
+        symbol: S,
+        from_id: F,
+        limit: L,
+    ) -> Result<Vec<TradeHistory>>
+    where
+        S: IntoTradingPair,
+        F: Into<Option<u64>>,
+        L: Into<Option<u16>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = MyTradesRequest {
+            symbol: symbol.into(),
+            from_id: from_id.into(),
+            limit: limit.into(),
         };
 
-        if order.price != 0.0 {
-            params.insert("price", order.price.to_string());
-            params.insert("timeInForce", order.time_in_force.to_string());
+        let trades = self
+            .transport
+            .signed_get(Version::V3, "/myTrades", Some(params))
+            .await?;
+        Ok(trades)
+    }
+
+    // Whether BNB is used to pay spot trading fees and margin interest
+    pub async fn get_bnb_burn_status(&self) -> Result<BnbBurnStatus> {
+        let status = self
+            .transport
+            .signed_get::<_, ()>(Version::SapiV1, "/bnbBurn", None)
+            .await?;
+        Ok(status)
+    }
+
+    // Toggle BNB burn for spot trading fees and/or margin interest
+    pub async fn set_bnb_burn<S, I>(
+        &self,
+        spot_bnb_burn: S,
+        interest_bnb_burn: I,
+    ) -> Result<BnbBurnStatus>
+    where
+        S: Into<Option<bool>>,
+        I: Into<Option<bool>>,
+    {
+        let params = BnbBurnRequest {
+            spot_bnb_burn: spot_bnb_burn.into(),
+            interest_bnb_burn: interest_bnb_burn.into(),
+        };
+        let status = self
+            .transport
+            .signed_post(Version::SapiV1, "/bnbBurn", Some(params))
+            .await?;
+        Ok(status)
+    }
+
+    // Account status ("Normal" unless the account is restricted)
+    pub async fn get_account_status(&self) -> Result<AccountStatus> {
+        let status = self
+            .transport
+            .signed_get::<_, ()>(Version::SapiV1, "/account/status", None)
+            .await?;
+        Ok(status)
+    }
+
+    // API trading status, including whether trading is locked by the quantitative rules
+    pub async fn get_api_trading_status(&self) -> Result<ApiTradingStatus> {
+        let status = self
+            .transport
+            .signed_get::<_, ()>(Version::SapiV1, "/account/apiTradingStatus", None)
+            .await?;
+        Ok(status)
+    }
+
+    // Permissions granted to the API key in use
+    pub async fn get_api_key_permissions(&self) -> Result<ApiKeyPermissions> {
+        let permissions = self
+            .transport
+            .signed_get::<_, ()>(Version::SapiV1, "/account/apiRestrictions", None)
+            .await?;
+        Ok(permissions)
+    }
+
+    // Place any spot order, e.g. a `NewOrderRequest::limit_maker` or a limit order with
+    // `time_in_force(TimeInForce::IOC)`. Prices and quantities are rounded to the symbol's
+    // filters first. `R` has to match the order's response type, `Transaction` without one.
+    pub async fn place_order<R: DeserializeOwned>(&self, order: NewOrderRequest) -> Result<R> {
+        let order = self.prepare_order(order).await?;
+        self.post_order(order).await
+    }
+
+    // Client order id and rounding to the symbol's filters
+    async fn prepare_order(&self, mut order: NewOrderRequest) -> Result<NewOrderRequest> {
+        if self.auto_client_order_id && order.new_client_order_id.is_none() {
+            order.new_client_order_id = Some(Uuid::new_v4().simple().to_string());
+        }
+        let symbol = self.symbol_info(&order.symbol).await?;
+        Ok(order.rounded_to(&symbol))
+    }
+
+    async fn post_order<R: DeserializeOwned>(&self, order: NewOrderRequest) -> Result<R> {
+        let client_order_id = order.new_client_order_id.clone();
+        let transaction = self
+            .transport
+            .signed_post(Version::V3, "/order", Some(order))
+            .await;
+
+        match client_order_id {
+            Some(id) => transaction.context(ClientOrderId(id)),
+            None => transaction,
         }
-        params
+    }
+}
+
+// STOP_LOSS at market, trailing from `activation_price` or right away
+fn trailing_stop<A>(
+    symbol: &str,
+    side: Side,
+    qty: f64,
+    trailing_delta: u32,
+    activation_price: A,
+) -> NewOrderRequest
+where
+    A: Into<Option<f64>>,
+{
+    let mut order = NewOrderRequest::market(symbol, side, qty).trailing_delta(trailing_delta);
+    order.order_type = OrderType::StopLoss;
+    match activation_price.into() {
+        Some(activation_price) => order.stop_price(activation_price),
+        None => order,
     }
 }
 
@@ -230,4 +659,46 @@ mod test {
         b.trade_history("btcusdt").await?;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_get_all_orders() -> Result<()> {
+        let b = setup()?;
+        b.get_all_orders("btcusdt", None, 10).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_my_trades() -> Result<()> {
+        let b = setup()?;
+        b.get_my_trades("btcusdt", 0, 10).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_bnb_burn_status() -> Result<()> {
+        let b = setup()?;
+        b.get_bnb_burn_status().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_account_status() -> Result<()> {
+        let b = setup()?;
+        b.get_account_status().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_api_trading_status() -> Result<()> {
+        let b = setup()?;
+        b.get_api_trading_status().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_api_key_permissions() -> Result<()> {
+        let b = setup()?;
+        b.get_api_key_permissions().await?;
+        Ok(())
+    }
 }
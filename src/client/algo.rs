@@ -0,0 +1,170 @@
+use crate::transport::Version;
+use crate::{
+    client::Binance,
+    model::{
+        algo::{
+            AlgoOrders, AlgoSubOrders, CanceledAlgoOrder, FuturesAlgoOrderRequest, NewAlgoOrder,
+            TwapOrderRequest,
+        },
+        request::{AlgoIdRequest, AlgoOrdersRequest, AlgoSubOrdersRequest},
+    },
+};
+use anyhow::Result;
+
+// Spot Algo endpoints
+impl Binance {
+    // Place a spot TWAP order
+    pub async fn spot_twap_order(&self, order: TwapOrderRequest) -> Result<NewAlgoOrder> {
+        let algo = self
+            .transport
+            .signed_post(Version::SapiV1, "/algo/spot/newOrderTwap", Some(order))
+            .await?;
+        Ok(algo)
+    }
+
+    // Cancel an open spot algo order
+    pub async fn cancel_spot_algo_order(&self, algo_id: u64) -> Result<CanceledAlgoOrder> {
+        let params = AlgoIdRequest { algo_id };
+        let canceled = self
+            .transport
+            .signed_delete(Version::SapiV1, "/algo/spot/order", Some(params))
+            .await?;
+        Ok(canceled)
+    }
+
+    // Spot algo orders still working
+    pub async fn get_spot_algo_open_orders(&self) -> Result<AlgoOrders> {
+        let orders = self
+            .transport
+            .signed_get::<_, ()>(Version::SapiV1, "/algo/spot/openOrders", None)
+            .await?;
+        Ok(orders)
+    }
+
+    // Finished spot algo orders, filtered by the fields set in `request`
+    pub async fn get_spot_algo_historical_orders(
+        &self,
+        request: AlgoOrdersRequest,
+    ) -> Result<AlgoOrders> {
+        let orders = self
+            .transport
+            .signed_get(
+                Version::SapiV1,
+                "/algo/spot/historicalOrders",
+                Some(request),
+            )
+            .await?;
+        Ok(orders)
+    }
+
+    // Sub-orders a spot algo order was sliced into
+    pub async fn get_spot_algo_sub_orders<P, S>(
+        &self,
+        algo_id: u64,
+        page: P,
+        page_size: S,
+    ) -> Result<AlgoSubOrders>
+    where
+        P: Into<Option<u32>>,
+        S: Into<Option<u16>>,
+    {
+        let params = AlgoSubOrdersRequest {
+            algo_id,
+            page: page.into(),
+            page_size: page_size.into(),
+        };
+        let sub_orders = self
+            .transport
+            .signed_get(Version::SapiV1, "/algo/spot/subOrders", Some(params))
+            .await?;
+        Ok(sub_orders)
+    }
+}
+
+// Futures Algo endpoints
+impl Binance {
+    // Place a futures volume participation (VP) or TWAP order, depending on how `order` was built
+    pub async fn futures_algo_order(&self, order: FuturesAlgoOrderRequest) -> Result<NewAlgoOrder> {
+        let endpoint = if order.urgency.is_some() {
+            "/algo/futures/newOrderVp"
+        } else {
+            "/algo/futures/newOrderTwap"
+        };
+        let algo = self
+            .transport
+            .signed_post(Version::SapiV1, endpoint, Some(order))
+            .await?;
+        Ok(algo)
+    }
+
+    // Cancel an open futures algo order
+    pub async fn cancel_futures_algo_order(&self, algo_id: u64) -> Result<CanceledAlgoOrder> {
+        let params = AlgoIdRequest { algo_id };
+        let canceled = self
+            .transport
+            .signed_delete(Version::SapiV1, "/algo/futures/order", Some(params))
+            .await?;
+        Ok(canceled)
+    }
+
+    // Futures algo orders still working
+    pub async fn get_futures_algo_open_orders(&self) -> Result<AlgoOrders> {
+        let orders = self
+            .transport
+            .signed_get::<_, ()>(Version::SapiV1, "/algo/futures/openOrders", None)
+            .await?;
+        Ok(orders)
+    }
+
+    // Finished futures algo orders, filtered by the fields set in `request`
+    pub async fn get_futures_algo_historical_orders(
+        &self,
+        request: AlgoOrdersRequest,
+    ) -> Result<AlgoOrders> {
+        let orders = self
+            .transport
+            .signed_get(
+                Version::SapiV1,
+                "/algo/futures/historicalOrders",
+                Some(request),
+            )
+            .await?;
+        Ok(orders)
+    }
+
+    // Sub-orders a futures algo order was sliced into
+    pub async fn get_futures_algo_sub_orders<P, S>(
+        &self,
+        algo_id: u64,
+        page: P,
+        page_size: S,
+    ) -> Result<AlgoSubOrders>
+    where
+        P: Into<Option<u32>>,
+        S: Into<Option<u16>>,
+    {
+        let params = AlgoSubOrdersRequest {
+            algo_id,
+            page: page.into(),
+            page_size: page_size.into(),
+        };
+        let sub_orders = self
+            .transport
+            .signed_get(Version::SapiV1, "/algo/futures/subOrders", Some(params))
+            .await?;
+        Ok(sub_orders)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tests::test::setup;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_get_spot_algo_open_orders() -> Result<()> {
+        let b = setup()?;
+        b.get_spot_algo_open_orders().await?;
+        Ok(())
+    }
+}
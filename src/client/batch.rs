@@ -0,0 +1,118 @@
+use crate::{client::Binance, transport::Host};
+use futures::future::join_all;
+use std::{future::Future, time::Duration};
+use tokio::time::{sleep_until, Instant};
+
+// Batch endpoints
+impl Binance {
+    // Runs `requests`, each paired with its request weight, concurrently, but starts no more
+    // weight per second than the market data budget allows, 80 with the default limit of 6000
+    // per minute. Weight already used in the current minute counts, requests past what is left
+    // of it start in the next minute. The results come back in the order of `requests`.
+    //
+    // client.join_weighted(symbols.iter().map(|s| (limit.weight(), client.get_depth(s, limit))))
+    pub async fn join_weighted<I, F>(&self, requests: I) -> Vec<F::Output>
+    where
+        I: IntoIterator<Item = (u32, F)>,
+        F: Future,
+    {
+        let (weights, requests): (Vec<u32>, Vec<F>) = requests.into_iter().unzip();
+        let per_second = self.transport.weight_per_second(&Host::Api);
+        let (left, next_minute) = self.transport.budget_left(&Host::Api);
+        let delays = start_delays(&weights, per_second, left, next_minute);
+        let start = Instant::now();
+        join_all(
+            requests
+                .into_iter()
+                .zip(delays)
+                .map(|(request, delay)| async move {
+                    sleep_until(start + delay).await;
+                    request.await
+                }),
+        )
+        .await
+    }
+}
+
+// When each request may start, in order and with at most `per_second` weight per second.
+// Once the weight `left` in the current minute is spent, the following requests start from
+// `next_minute` on with the budget of a whole minute. A request heavier than `per_second`
+// gets a second of its own.
+fn start_delays(
+    weights: &[u32],
+    per_second: u32,
+    left: u32,
+    next_minute: Duration,
+) -> Vec<Duration> {
+    let minute_budget = per_second * 60;
+    let (mut minute_start, mut left) = (Duration::ZERO, left);
+    let (mut second, mut used, mut spent) = (0, 0, 0);
+    weights
+        .iter()
+        .map(|weight| {
+            if spent + weight > left && (spent > 0 || left < minute_budget) {
+                minute_start = if minute_start.is_zero() {
+                    next_minute
+                } else {
+                    minute_start + Duration::from_mins(1)
+                };
+                left = minute_budget;
+                (second, used, spent) = (0, 0, 0);
+            } else if used > 0 && used + weight > per_second {
+                second += 1;
+                used = 0;
+            }
+            used += weight;
+            spent += weight;
+            minute_start + Duration::from_secs(second)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::start_delays;
+    use crate::Binance;
+    use std::time::Duration;
+    use tokio::time::Instant;
+
+    fn seconds(delays: &[Duration]) -> Vec<u64> {
+        delays.iter().map(Duration::as_secs).collect()
+    }
+
+    #[test]
+    fn weight_slots() {
+        let minute = Duration::from_secs(30);
+        let delays = start_delays(&[20, 20, 20, 20, 5], 80, 4800, minute);
+        assert_eq!(seconds(&delays), vec![0, 0, 0, 0, 1]);
+        let delays = start_delays(&[50, 100, 10, 1], 80, 4800, minute);
+        assert_eq!(seconds(&delays), vec![0, 1, 2, 2]);
+        assert!(start_delays(&[], 80, 4800, minute).is_empty());
+    }
+
+    #[test]
+    fn used_weight_slots() {
+        let minute = Duration::from_secs(30);
+        // 50 left in this minute, the rest waits for the next one
+        let delays = start_delays(&[20, 20, 20, 20, 100, 5], 80, 50, minute);
+        assert_eq!(seconds(&delays), vec![0, 0, 30, 30, 31, 32]);
+        // Nothing left
+        assert_eq!(seconds(&start_delays(&[1], 80, 0, minute)), vec![30]);
+        // A minute's budget for every following minute
+        let delays = start_delays(&[4800, 4800, 1], 80, 4800, minute);
+        assert_eq!(seconds(&delays), vec![0, 30, 90]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn join_weighted() -> anyhow::Result<()> {
+        let client = Binance::builder().weight_limit(600).build()?;
+        let start = Instant::now();
+        let results = client
+            .join_weighted((0..3).map(|i| (4, async move { i * 2 })))
+            .await;
+        assert_eq!(results, vec![0, 2, 4]);
+        // 8 per second, the third request waits for the next second
+        assert_eq!(start.elapsed().as_secs(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+use crate::transport::Version;
+use crate::{
+    client::Binance,
+    model::{
+        blvt::{BlvtInfo, BlvtRedemption, BlvtSubscription, BlvtUserLimit},
+        request::{BlvtRedeemRequest, BlvtSubscribeRequest, BlvtTokenRequest},
+    },
+};
+use anyhow::Result;
+
+// Leveraged token (BLVT) endpoints
+impl Binance {
+    // Basket, NAV and limits of ALL leveraged tokens, or of ONE if `token_name` is set
+    pub async fn get_blvt_info<'a, T>(&self, token_name: T) -> Result<Vec<BlvtInfo>>
+    where
+        T: Into<Option<&'a str>>,
+    {
+        let params = BlvtTokenRequest {
+            token_name: token_name.into().map(String::from),
+        };
+        let info = self
+            .transport
+            .get(Version::SapiV1, "/blvt/tokenInfo", Some(params))
+            .await?;
+        Ok(info)
+    }
+
+    // Subscribe to `token_name` spending `cost` USDT
+    pub async fn subscribe_blvt(&self, token_name: &str, cost: f64) -> Result<BlvtSubscription> {
+        let params = BlvtSubscribeRequest {
+            token_name: token_name.into(),
+            cost,
+        };
+        let subscription = self
+            .transport
+            .signed_post(Version::SapiV1, "/blvt/subscribe", Some(params))
+            .await?;
+        Ok(subscription)
+    }
+
+    // Redeem `amount` of `token_name`
+    pub async fn redeem_blvt(&self, token_name: &str, amount: f64) -> Result<BlvtRedemption> {
+        let params = BlvtRedeemRequest {
+            token_name: token_name.into(),
+            amount,
+        };
+        let redemption = self
+            .transport
+            .signed_post(Version::SapiV1, "/blvt/redeem", Some(params))
+            .await?;
+        Ok(redemption)
+    }
+
+    // Daily subscription and redemption limits of the user
+    pub async fn get_blvt_user_limits<'a, T>(&self, token_name: T) -> Result<Vec<BlvtUserLimit>>
+    where
+        T: Into<Option<&'a str>>,
+    {
+        let params = BlvtTokenRequest {
+            token_name: token_name.into().map(String::from),
+        };
+        let limits = self
+            .transport
+            .signed_get(Version::SapiV1, "/blvt/userLimit", Some(params))
+            .await?;
+        Ok(limits)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tests::test::setup;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_get_blvt_info() -> Result<()> {
+        let b = setup()?;
+        b.get_blvt_info(None).await?;
+        Ok(())
+    }
+}
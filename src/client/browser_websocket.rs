@@ -0,0 +1,144 @@
+//! Market and user data streams in the browser, for `wasm32-unknown-unknown` builds with the
+//! `browser-websocket` feature.
+//!
+//! `BrowserWebsocket` opens one browser `WebSocket` per subscription and yields the messages of
+//! all of them as one stream, parsed like those of `BinanceWebsocket`. The browser answers the
+//! pings itself. A connection the exchange closes ends with an error item and is not reopened,
+//! `resubscribe` opens it again.
+
+use crate::model::websocket::{parse_stream_text, BinanceWebsocketMessage, Subscription};
+use anyhow::{anyhow, Result};
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    prelude::*,
+};
+use std::{
+    collections::HashMap,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{CloseEvent, Event, MessageEvent, WebSocket};
+
+const SPOT_WS_URL: &str = "wss://stream.binance.com:9443/ws";
+
+type Message = Result<BinanceWebsocketMessage>;
+
+// A browser socket with the handlers it calls, which have to live as long as it does
+struct Connection {
+    socket: WebSocket,
+    _on_message: Closure<dyn FnMut(MessageEvent)>,
+    _on_error: Closure<dyn FnMut(Event)>,
+    _on_close: Closure<dyn FnMut(CloseEvent)>,
+}
+
+impl Connection {
+    fn open(
+        url: &str,
+        subscription: &Subscription,
+        sender: &UnboundedSender<Message>,
+    ) -> Result<Self> {
+        let socket = WebSocket::new(url).map_err(|e| anyhow!("opening {url}: {e:?}"))?;
+
+        let (sub, tx) = (subscription.clone(), sender.clone());
+        let on_message = Closure::<dyn FnMut(MessageEvent)>::new(move |event: MessageEvent| {
+            let message = match event.data().as_string() {
+                Some(text) => parse_stream_text(&sub, &text),
+                None => Err(anyhow!("{sub}: unexpected binary frame")),
+            };
+            let _ = tx.unbounded_send(message);
+        });
+        let (sub, tx) = (subscription.clone(), sender.clone());
+        let on_error = Closure::<dyn FnMut(Event)>::new(move |_: Event| {
+            let _ = tx.unbounded_send(Err(anyhow!("{sub}: websocket error")));
+        });
+        let (sub, tx) = (subscription.clone(), sender.clone());
+        let on_close = Closure::<dyn FnMut(CloseEvent)>::new(move |event: CloseEvent| {
+            let _ = tx.unbounded_send(Err(anyhow!(
+                "{sub}: socket closed with code {}",
+                event.code()
+            )));
+        });
+
+        socket.set_onmessage(Some(on_message.as_ref().unchecked_ref()));
+        socket.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+        socket.set_onclose(Some(on_close.as_ref().unchecked_ref()));
+        Ok(Self {
+            socket,
+            _on_message: on_message,
+            _on_error: on_error,
+            _on_close: on_close,
+        })
+    }
+}
+
+// Closing on purpose is no error, the handlers go before the socket
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.socket.set_onmessage(None);
+        self.socket.set_onerror(None);
+        self.socket.set_onclose(None);
+        let _ = self.socket.close();
+    }
+}
+
+pub struct BrowserWebsocket {
+    base_url: String,
+    connections: HashMap<Subscription, Connection>,
+    sender: UnboundedSender<Message>,
+    receiver: UnboundedReceiver<Message>,
+}
+
+impl Default for BrowserWebsocket {
+    fn default() -> Self {
+        let (sender, receiver) = unbounded();
+        Self {
+            base_url: SPOT_WS_URL.into(),
+            connections: HashMap::new(),
+            sender,
+            receiver,
+        }
+    }
+}
+
+impl BrowserWebsocket {
+    // Spot streams from `base_url` instead of `wss://stream.binance.com:9443/ws`, e.g.
+    // `wss://data-stream.binance.vision/ws`. Futures and NAV streams keep their own hosts.
+    #[must_use]
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').into();
+        self
+    }
+
+    // Opens the connection of `subscription`, its messages follow once the browser connected
+    pub fn subscribe(&mut self, subscription: &Subscription) -> Result<()> {
+        let base = subscription.dedicated_base().unwrap_or(&self.base_url);
+        let url = format!("{base}/{subscription}");
+        let connection = Connection::open(&url, subscription, &self.sender)?;
+        self.connections.insert(subscription.clone(), connection);
+        Ok(())
+    }
+
+    // Closes the connection of `subscription` and opens a new one
+    pub fn resubscribe(&mut self, subscription: &Subscription) -> Result<()> {
+        self.unsubscribe(subscription);
+        self.subscribe(subscription)
+    }
+
+    // `false` if `subscription` was not subscribed
+    pub fn unsubscribe(&mut self, subscription: &Subscription) -> bool {
+        self.connections.remove(subscription).is_some()
+    }
+
+    pub fn subscriptions(&self) -> impl Iterator<Item = &Subscription> {
+        self.connections.keys()
+    }
+}
+
+impl Stream for BrowserWebsocket {
+    type Item = Result<BinanceWebsocketMessage>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
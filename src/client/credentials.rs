@@ -0,0 +1,77 @@
+use crate::{client::Binance, error::Error};
+use anyhow::Result;
+use serde::Deserialize;
+use std::{fs, path::Path};
+
+const KEY_VAR: &str = "BINANCE_KEY";
+const SECRET_VAR: &str = "BINANCE_SECRET";
+
+#[derive(Debug, Deserialize)]
+struct CredentialFile {
+    api_key: String,
+    api_secret: String,
+}
+
+// Credential loading
+impl Binance {
+    // Credential from `BINANCE_KEY` and `BINANCE_SECRET`, which may also be set in a `.env` file
+    pub fn from_env() -> Result<Self> {
+        dotenv::dotenv().ok();
+        let var = |name: &str| {
+            std::env::var(name).map_err(|_| Error::MissingCredential(format!("{name} not set")))
+        };
+        Ok(Self::with_credential(&var(KEY_VAR)?, &var(SECRET_VAR)?))
+    }
+
+    // Credential from the `api_key` and `api_secret` of a TOML file, or of a JSON file if the
+    // name ends in `.json`
+    pub fn from_config_file<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let is_json = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("json"));
+        let file = parse_credential_file(&fs::read_to_string(path)?, is_json)?;
+        Ok(Self::with_credential(&file.api_key, &file.api_secret))
+    }
+
+    // Credential kept in the OS keyring as the entries `api_key` and `api_secret` of `service`
+    #[cfg(all(feature = "keyring", not(target_arch = "wasm32")))]
+    pub fn from_keyring(service: &str) -> Result<Self> {
+        let entry = |name: &str| -> Result<String> {
+            keyring::Entry::new(service, name)
+                .and_then(|entry| entry.get_password())
+                .map_err(|e| Error::MissingCredential(format!("{service}/{name}: {e}")).into())
+        };
+        Ok(Self::with_credential(
+            &entry("api_key")?,
+            &entry("api_secret")?,
+        ))
+    }
+}
+
+fn parse_credential_file(text: &str, is_json: bool) -> Result<CredentialFile> {
+    if is_json {
+        Ok(serde_json::from_str(text)?)
+    } else {
+        Ok(toml::from_str(text)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::parse_credential_file;
+    use anyhow::Result;
+
+    #[test]
+    fn credential_files() -> Result<()> {
+        let toml = parse_credential_file("api_key = \"key\"\napi_secret = \"secret\"\n", false)?;
+        assert_eq!(
+            (toml.api_key.as_str(), toml.api_secret.as_str()),
+            ("key", "secret")
+        );
+        let json = parse_credential_file(r#"{"api_key":"key","api_secret":"secret"}"#, true)?;
+        assert_eq!(json.api_secret, "secret");
+        assert!(parse_credential_file("api_key = \"key\"", false).is_err());
+        Ok(())
+    }
+}
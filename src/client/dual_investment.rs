@@ -0,0 +1,125 @@
+use crate::transport::Version;
+use crate::{
+    client::Binance,
+    model::{
+        dual_investment::{
+            AutoCompoundPlan, AutoCompoundStatus, DualInvestmentOptionType, DualInvestmentPage,
+            DualInvestmentPosition, DualInvestmentProduct, DualInvestmentSubscription,
+        },
+        request::{
+            AutoCompoundRequest, DualInvestmentPositionsRequest, DualInvestmentProductsRequest,
+            DualInvestmentSubscribeRequest,
+        },
+    },
+};
+use anyhow::Result;
+
+// Dual Investment endpoints
+impl Binance {
+    // Products of ONE page settling `exercised_coin` against `invest_coin`
+    pub async fn get_dual_investment_products<S, I>(
+        &self,
+        option_type: DualInvestmentOptionType,
+        exercised_coin: &str,
+        invest_coin: &str,
+        page_size: S,
+        page_index: I,
+    ) -> Result<DualInvestmentPage<DualInvestmentProduct>>
+    where
+        S: Into<Option<u16>>,
+        I: Into<Option<u32>>,
+    {
+        let params = DualInvestmentProductsRequest {
+            option_type,
+            exercised_coin: exercised_coin.into(),
+            invest_coin: invest_coin.into(),
+            page_size: page_size.into(),
+            page_index: page_index.into(),
+        };
+        let products = self
+            .transport
+            .signed_get(Version::SapiV1, "/dci/product/list", Some(params))
+            .await?;
+        Ok(products)
+    }
+
+    // Subscribe `deposit_amount` to `product`, rolling it over according to `plan`
+    pub async fn subscribe_dual_investment(
+        &self,
+        product: &DualInvestmentProduct,
+        deposit_amount: f64,
+        plan: AutoCompoundPlan,
+    ) -> Result<DualInvestmentSubscription> {
+        let params = DualInvestmentSubscribeRequest {
+            id: product.id.clone(),
+            order_id: product.order_id,
+            deposit_amount,
+            auto_compound_plan: plan,
+        };
+        let subscription = self
+            .transport
+            .signed_post(Version::SapiV1, "/dci/product/subscribe", Some(params))
+            .await?;
+        Ok(subscription)
+    }
+
+    // Dual Investment positions of ONE page, optionally only those in `status`
+    pub async fn get_dual_investment_positions<'a, T, S, I>(
+        &self,
+        status: T,
+        page_size: S,
+        page_index: I,
+    ) -> Result<DualInvestmentPage<DualInvestmentPosition>>
+    where
+        T: Into<Option<&'a str>>,
+        S: Into<Option<u16>>,
+        I: Into<Option<u32>>,
+    {
+        let params = DualInvestmentPositionsRequest {
+            status: status.into().map(String::from),
+            page_size: page_size.into(),
+            page_index: page_index.into(),
+        };
+        let positions = self
+            .transport
+            .signed_get(Version::SapiV1, "/dci/product/positions", Some(params))
+            .await?;
+        Ok(positions)
+    }
+
+    // Change how the position `position_id` rolls over once it settles
+    pub async fn set_dual_investment_auto_compound(
+        &self,
+        position_id: &str,
+        plan: AutoCompoundPlan,
+    ) -> Result<AutoCompoundStatus> {
+        let params = AutoCompoundRequest {
+            position_id: position_id.into(),
+            auto_compound_plan: plan,
+        };
+        let status = self
+            .transport
+            .signed_post(
+                Version::SapiV1,
+                "/dci/product/auto_compound/edit-status",
+                Some(params),
+            )
+            .await?;
+        Ok(status)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::model::dual_investment::DualInvestmentOptionType;
+    use crate::tests::test::setup;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_get_dual_investment_products() -> Result<()> {
+        let b = setup()?;
+        b.get_dual_investment_products(DualInvestmentOptionType::Put, "bnb", "usdt", 10, None)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,459 @@
+use crate::error::{BinanceResponse, Error};
+use crate::transport::{Endpoint, Host, Version};
+use crate::{
+    client::Binance,
+    model::{
+        futures::{
+            FundingRate, FuturesAccount, FuturesOrder, FuturesOrderRequest, IndexConstituents,
+            IndexInfo, Leverage, LongShortRatio, MarginType, OpenInterest, PositionMode,
+            PremiumIndex, TakerLongShortRatio,
+        },
+        pair::{IntoTradingPair, TradingPair},
+        request::{
+            FundingRateRequest, FuturesDataRequest, KlinesRequest, LeverageRequest,
+            MarginTypeRequest, OptionalSymbolRequest, PositionModeRequest, SymbolRequest,
+        },
+        KlineSummary, Success,
+    },
+};
+use anyhow::Result;
+use http::Method;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::ops::Range;
+
+// Orders `/fapi/v1/batchOrders` takes at once
+const MAX_BATCH_ORDERS: usize = 5;
+const NO_NEED_TO_CHANGE_MARGIN_TYPE: i64 = -4046;
+const MARGIN_TYPE_OPEN_ORDERS: i64 = -4047;
+const MARGIN_TYPE_OPEN_POSITION: i64 = -4048;
+const NO_NEED_TO_CHANGE_POSITION_SIDE: i64 = -4059;
+const POSITION_SIDE_OPEN_ORDERS: i64 = -4067;
+const POSITION_SIDE_OPEN_POSITION: i64 = -4068;
+
+// USDⓈ-M futures endpoints
+impl Binance {
+    // Place up to 5 futures orders in one request. Every order succeeds or fails on its own,
+    // the results are in the order of `orders`.
+    pub async fn place_batch_orders(
+        &self,
+        orders: Vec<FuturesOrderRequest>,
+    ) -> Result<Vec<Result<FuturesOrder, Error>>> {
+        if orders.is_empty() {
+            return Ok(Vec::new());
+        }
+        if orders.len() > MAX_BATCH_ORDERS {
+            return Err(Error::InvalidParams(format!(
+                "{} orders, a batch takes at most {MAX_BATCH_ORDERS}",
+                orders.len()
+            ))
+            .into());
+        }
+        let params = [("batchOrders", batch_orders_param(&orders)?)];
+        let results: Vec<BinanceResponse<FuturesOrder>> = self
+            .transport
+            .signed_post(Version::FapiV1, "/batchOrders", Some(params))
+            .await?;
+        Ok(results
+            .into_iter()
+            .map(BinanceResponse::into_result)
+            .collect())
+    }
+
+    // Funding rates of ONE symbol settled within `range` (millisecond timestamps), oldest first
+    // (Default 100; max 1000)
+    pub async fn get_funding_rate_history<S, L>(
+        &self,
+        symbol: S,
+        range: Range<u64>,
+        limit: L,
+    ) -> Result<Vec<FundingRate>>
+    where
+        S: IntoTradingPair,
+        L: Into<Option<u16>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = FundingRateRequest {
+            symbol: symbol.into(),
+            start_time: Some(range.start),
+            end_time: Some(range.end.saturating_sub(1)),
+            limit: limit.into(),
+        };
+        let rates = self
+            .transport
+            .get(Version::FapiV1, "/fundingRate", Some(params))
+            .await?;
+        Ok(rates)
+    }
+
+    // Klines of ONE futures symbol, see `get_klines` (Default 500; max 1500)
+    pub async fn get_futures_klines<S, L, B, E>(
+        &self,
+        symbol: S,
+        interval: &str,
+        limit: L,
+        start_time: B,
+        end_time: E,
+    ) -> Result<Vec<KlineSummary>>
+    where
+        S: IntoTradingPair,
+        L: Into<Option<u16>>,
+        B: Into<Option<u64>>,
+        E: Into<Option<u64>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = KlinesRequest {
+            symbol: symbol.into(),
+            interval: interval.into(),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            limit: limit.into(),
+        };
+        let klines = self
+            .transport
+            .get(Version::FapiV1, "/klines", Some(params))
+            .await?;
+        Ok(klines)
+    }
+
+    // Mark price, index price and next funding of ONE symbol
+    pub async fn get_mark_price<S>(&self, symbol: S) -> Result<PremiumIndex>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = SymbolRequest {
+            symbol: symbol.into(),
+        };
+        let index = self
+            .transport
+            .get(Version::FapiV1, "/premiumIndex", Some(params))
+            .await?;
+        Ok(index)
+    }
+
+    // Mark price, index price and next funding of ALL symbols
+    pub async fn get_all_mark_prices(&self) -> Result<Vec<PremiumIndex>> {
+        let indexes = self
+            .transport
+            .get::<_, ()>(Version::FapiV1, "/premiumIndex", None)
+            .await?;
+        Ok(indexes)
+    }
+
+    // Base asset weights of ONE composite index, or of ALL with `None`
+    pub async fn get_index_info<'a, S>(&self, symbol: S) -> Result<Vec<IndexInfo>>
+    where
+        S: Into<Option<&'a str>>,
+    {
+        let params = OptionalSymbolRequest {
+            symbol: symbol
+                .into()
+                .map(TradingPair::new)
+                .transpose()?
+                .map(String::from),
+        };
+        let info = self
+            .transport
+            .get(Version::FapiV1, "/indexInfo", Some(params))
+            .await?;
+        Ok(info)
+    }
+
+    // Exchanges and markets the index price of ONE symbol is computed from
+    pub async fn get_index_constituents<S>(&self, symbol: S) -> Result<IndexConstituents>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = SymbolRequest {
+            symbol: symbol.into(),
+        };
+        let constituents = self
+            .transport
+            .get(Version::FapiV1, "/constituents", Some(params))
+            .await?;
+        Ok(constituents)
+    }
+
+    // Open interest of ONE symbol per `period` ("5m", "1h", "1d", ...), only the last 30 days
+    // are available (Default 30; max 500)
+    pub async fn get_open_interest_hist<S, L, R>(
+        &self,
+        symbol: S,
+        period: &str,
+        limit: L,
+        range: R,
+    ) -> Result<Vec<OpenInterest>>
+    where
+        S: IntoTradingPair,
+        L: Into<Option<u16>>,
+        R: Into<Option<Range<u64>>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        self.futures_data("/openInterestHist", symbol, period, limit, range)
+            .await
+    }
+
+    // Long/short ratio of the positions of the top 20% traders by margin balance
+    pub async fn get_top_long_short_position_ratio<S, L, R>(
+        &self,
+        symbol: S,
+        period: &str,
+        limit: L,
+        range: R,
+    ) -> Result<Vec<LongShortRatio>>
+    where
+        S: IntoTradingPair,
+        L: Into<Option<u16>>,
+        R: Into<Option<Range<u64>>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        self.futures_data("/topLongShortPositionRatio", symbol, period, limit, range)
+            .await
+    }
+
+    // Long/short ratio of the accounts of the top 20% traders by margin balance
+    pub async fn get_top_long_short_account_ratio<S, L, R>(
+        &self,
+        symbol: S,
+        period: &str,
+        limit: L,
+        range: R,
+    ) -> Result<Vec<LongShortRatio>>
+    where
+        S: IntoTradingPair,
+        L: Into<Option<u16>>,
+        R: Into<Option<Range<u64>>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        self.futures_data("/topLongShortAccountRatio", symbol, period, limit, range)
+            .await
+    }
+
+    // Taker buy and sell volume of ONE symbol per `period`
+    pub async fn get_taker_long_short_ratio<S, L, R>(
+        &self,
+        symbol: S,
+        period: &str,
+        limit: L,
+        range: R,
+    ) -> Result<Vec<TakerLongShortRatio>>
+    where
+        S: IntoTradingPair,
+        L: Into<Option<u16>>,
+        R: Into<Option<Range<u64>>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        self.futures_data("/takerlongshortRatio", symbol, period, limit, range)
+            .await
+    }
+
+    // Wallet balances and positions of the futures account
+    pub async fn get_futures_account(&self) -> Result<FuturesAccount> {
+        let account = self
+            .transport
+            .signed_get::<_, ()>(Version::FapiV2, "/account", None)
+            .await?;
+        Ok(account)
+    }
+
+    // Whether the account is in hedge mode (`true`) or one-way mode
+    pub async fn get_position_mode(&self) -> Result<PositionMode> {
+        let mode = self
+            .transport
+            .signed_get::<_, ()>(Version::FapiV1, "/positionSide/dual", None)
+            .await?;
+        Ok(mode)
+    }
+
+    // Switch between hedge mode (`true`) and one-way mode for ALL symbols. Switching to the
+    // current mode succeeds, with open positions or orders it fails with
+    // `Error::PositionModeLocked`.
+    pub async fn set_position_mode(&self, hedge_mode: bool) -> Result<()> {
+        let params = PositionModeRequest {
+            dual_side_position: hedge_mode,
+        };
+        let result = self
+            .transport
+            .signed_post::<Success, _>(Version::FapiV1, "/positionSide/dual", Some(params))
+            .await;
+        position_mode_set(result)
+    }
+
+    // Initial leverage of ONE symbol
+    pub async fn set_leverage<S>(&self, symbol: S, leverage: u8) -> Result<Leverage>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = LeverageRequest {
+            symbol: symbol.into(),
+            leverage,
+        };
+        let leverage = self
+            .transport
+            .signed_post(Version::FapiV1, "/leverage", Some(params))
+            .await?;
+        Ok(leverage)
+    }
+
+    // Switch ONE symbol between isolated and cross margin. Switching to the current type
+    // succeeds, with open positions or orders it fails with `Error::MarginTypeLocked`.
+    pub async fn set_margin_type<S>(&self, symbol: S, margin_type: MarginType) -> Result<()>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = MarginTypeRequest {
+            symbol: symbol.into(),
+            margin_type,
+        };
+        let result = self
+            .transport
+            .signed_post::<Success, _>(Version::FapiV1, "/marginType", Some(params))
+            .await;
+        margin_type_set(result)
+    }
+
+    // The statistics live below `/futures/data` instead of a versioned path
+    async fn futures_data<O, L, R>(
+        &self,
+        path: &str,
+        symbol: TradingPair,
+        period: &str,
+        limit: L,
+        range: R,
+    ) -> Result<O>
+    where
+        O: DeserializeOwned,
+        L: Into<Option<u16>>,
+        R: Into<Option<Range<u64>>>,
+    {
+        let range = range.into();
+        let params = FuturesDataRequest {
+            symbol: symbol.into(),
+            period: period.into(),
+            limit: limit.into(),
+            start_time: range.as_ref().map(|range| range.start),
+            end_time: range.map(|range| range.end.saturating_sub(1)),
+        };
+        let endpoint = Endpoint::new(Host::Fapi, &format!("/futures/data{path}"));
+        self.transport
+            .request::<_, _, ()>(Method::GET, endpoint, Some(params), None)
+            .await
+    }
+}
+
+// Already being in the requested mode counts as switched
+fn position_mode_set(result: Result<Success>) -> Result<()> {
+    let Err(e) = result else {
+        return Ok(());
+    };
+    match e.downcast_ref::<Error>() {
+        Some(Error::BinanceError { code, .. }) if *code == NO_NEED_TO_CHANGE_POSITION_SIDE => {
+            Ok(())
+        }
+        Some(Error::BinanceError { code, msg })
+            if *code == POSITION_SIDE_OPEN_ORDERS || *code == POSITION_SIDE_OPEN_POSITION =>
+        {
+            Err(Error::PositionModeLocked(msg.clone()).into())
+        }
+        _ => Err(e),
+    }
+}
+
+fn margin_type_set(result: Result<Success>) -> Result<()> {
+    let Err(e) = result else {
+        return Ok(());
+    };
+    match e.downcast_ref::<Error>() {
+        Some(Error::BinanceError { code, .. }) if *code == NO_NEED_TO_CHANGE_MARGIN_TYPE => Ok(()),
+        Some(Error::BinanceError { code, msg })
+            if *code == MARGIN_TYPE_OPEN_ORDERS || *code == MARGIN_TYPE_OPEN_POSITION =>
+        {
+            Err(Error::MarginTypeLocked(msg.clone()).into())
+        }
+        _ => Err(e),
+    }
+}
+
+// The batch is a JSON array of orders with every value as a string
+fn batch_orders_param(orders: &[FuturesOrderRequest]) -> Result<String> {
+    let orders = orders
+        .iter()
+        .map(|order| {
+            let Value::Object(fields) = serde_json::to_value(order)? else {
+                return Err(Error::InvalidParams(format!("{order:?}")).into());
+            };
+            Ok(fields
+                .into_iter()
+                .map(|(key, value)| match value {
+                    Value::String(_) => (key, value),
+                    other => (key, Value::String(other.to_string())),
+                })
+                .collect())
+        })
+        .collect::<Result<Vec<serde_json::Map<String, Value>>>>()?;
+    Ok(serde_json::to_string(&orders)?)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{batch_orders_param, margin_type_set, position_mode_set};
+    use crate::{
+        error::Error,
+        model::{algo::PositionSide, futures::FuturesOrderRequest, Side},
+    };
+    use anyhow::Result;
+
+    #[test]
+    fn mode_switch_errors() {
+        let error = |code, msg: &str| {
+            Err(Error::BinanceError {
+                code,
+                msg: msg.into(),
+            }
+            .into())
+        };
+        assert!(margin_type_set(error(-4046, "No need to change margin type.")).is_ok());
+        assert!(matches!(
+            margin_type_set(error(
+                -4048,
+                "Margin type cannot be changed if there exists position."
+            ))
+            .unwrap_err()
+            .downcast_ref(),
+            Some(Error::MarginTypeLocked(_))
+        ));
+        assert!(position_mode_set(error(-4059, "No need to change position side.")).is_ok());
+        assert!(matches!(
+            position_mode_set(error(
+                -4068,
+                "Position side cannot be changed if there exists position."
+            ))
+            .unwrap_err()
+            .downcast_ref(),
+            Some(Error::PositionModeLocked(_))
+        ));
+        assert!(
+            position_mode_set(error(-1022, "Signature for this request is not valid.")).is_err()
+        );
+    }
+
+    #[test]
+    fn batch_orders_as_strings() -> Result<()> {
+        let mut order = FuturesOrderRequest::limit("btcusdt", Side::Sell, 0.5, 65_000.5);
+        order.reduce_only = Some(true);
+        let param = batch_orders_param(&[
+            order,
+            FuturesOrderRequest::market("ETHUSDT", Side::Buy, 2.0)
+                .position_side(PositionSide::Long),
+        ])?;
+        assert_eq!(
+            param,
+            r#"[{"price":"65000.5","quantity":"0.5","reduceOnly":"true","side":"SELL","symbol":"BTCUSDT","timeInForce":"GTC","type":"LIMIT"},{"positionSide":"LONG","quantity":"2.0","side":"BUY","symbol":"ETHUSDT","type":"MARKET"}]"#
+        );
+        Ok(())
+    }
+}
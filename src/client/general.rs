@@ -1,9 +1,16 @@
+use crate::cache::is_fresh;
+use crate::error::Error;
 use crate::transport::Version;
 use crate::{
     client::Binance,
-    model::{ExchangeInfo, ExchangeInformation, ServerTime},
+    model::{
+        pair::IntoTradingPair, request::ExchangeInfoRequest, DelistSchedule, ExchangeInformation,
+        ServerTime, Symbol, SystemStatus,
+    },
 };
 use anyhow::Result;
+use chrono::Utc;
+use std::sync::PoisonError;
 
 impl Binance {
     // Test connectivity
@@ -22,20 +29,132 @@ impl Binance {
             .await?)
     }
 
-    pub async fn get_exchange_info(&self) -> Result<ExchangeInfo> {
-        Ok(self
+    // Whether the exchange is under system maintenance. The history streams poll it after an
+    // `Error::ExchangeMaintenance` and resume once it is over.
+    pub async fn get_system_status(&self) -> Result<SystemStatus> {
+        let status = self
             .transport
-            .get::<_, ()>(Version::V3, "/exchangeInfo", None)
-            .await?)
+            .get::<_, ()>(Version::SapiV1, "/system/status", None)
+            .await?;
+        Ok(status)
+    }
+
+    #[deprecated(note = "use `exchange_info`, or `exchange_info_fresh` to bypass the cache")]
+    pub async fn get_exchange_info(&self) -> Result<ExchangeInformation> {
+        self.exchange_info_fresh().await
     }
 
-    // Obtain exchange information (rate limits, symbol metadata etc)
+    // Exchange information restricted to `symbols`, much smaller than the full response
+    pub async fn get_exchange_info_for<S: AsRef<str> + Sync>(
+        &self,
+        symbols: &[S],
+    ) -> Result<ExchangeInformation> {
+        let params = ExchangeInfoRequest::for_symbols(symbols);
+        let info = self
+            .transport
+            .get(Version::V3, "/exchangeInfo", Some(params))
+            .await?;
+        Ok(info)
+    }
+
+    // Exchange information of the symbols tradable with any of `permissions` ("SPOT", "MARGIN", ...)
+    pub async fn get_exchange_info_with_permissions<S: AsRef<str> + Sync>(
+        &self,
+        permissions: &[S],
+    ) -> Result<ExchangeInformation> {
+        let params = ExchangeInfoRequest::for_permissions(permissions);
+        let info = self
+            .transport
+            .get(Version::V3, "/exchangeInfo", Some(params))
+            .await?;
+        Ok(info)
+    }
+
+    // Obtain exchange information (rate limits, symbol metadata etc), served from the cache if
+    // `cache_ttl` is set
     pub async fn exchange_info(&self) -> Result<ExchangeInformation> {
+        self.cache
+            .get_or_fetch("/exchangeInfo".into(), self.exchange_info_fresh())
+            .await
+    }
+
+    // Exchange information, bypassing the cache
+    pub async fn exchange_info_fresh(&self) -> Result<ExchangeInformation> {
         Ok(self
             .transport
             .get::<_, ()>(Version::V3, "/exchangeInfo", None)
             .await?)
     }
+
+    // Trading rules of ONE symbol, fetched once per client and again once they are older than
+    // `cache_ttl` if it is set
+    pub async fn symbol_info<S>(&self, symbol: S) -> Result<Symbol>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = String::from(symbol.into_trading_pair()?);
+        let now_ms = Utc::now().timestamp_millis();
+        let known = self
+            .symbols
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&symbol)
+            .filter(|(fetched_ms, _)| {
+                self.cache
+                    .ttl()
+                    .is_none_or(|ttl| is_fresh(*fetched_ms, ttl, now_ms))
+            })
+            .map(|(_, info)| info.clone());
+        match known {
+            Some(info) => Ok(info),
+            None => self.refresh_symbol_info(symbol).await,
+        }
+    }
+
+    // Trading rules of ONE symbol fetched again, e.g. after the exchange changed its filters
+    pub async fn refresh_symbol_info<S>(&self, symbol: S) -> Result<Symbol>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = String::from(symbol.into_trading_pair()?);
+        let info = self
+            .get_exchange_info_for(&[&symbol])
+            .await?
+            .symbols
+            .into_iter()
+            .find(|s| s.symbol == symbol)
+            .ok_or(Error::SymbolNotFound)?;
+        self.symbols
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(symbol, (Utc::now().timestamp_millis(), info.clone()));
+        Ok(info)
+    }
+
+    // `price` rounded to the tick size of ONE symbol, e.g. "0.05433"
+    pub async fn format_price<S>(&self, symbol: S, price: f64) -> Result<String>
+    where
+        S: IntoTradingPair,
+    {
+        Ok(self.symbol_info(symbol).await?.format_price(price))
+    }
+
+    // `qty` rounded down to the step size of ONE symbol, e.g. "1.2345"
+    pub async fn format_qty<S>(&self, symbol: S, qty: f64) -> Result<String>
+    where
+        S: IntoTradingPair,
+    {
+        Ok(self.symbol_info(symbol).await?.format_qty(qty))
+    }
+
+    // Upcoming delistings of spot symbols
+    pub async fn get_delist_schedule(&self) -> Result<Vec<DelistSchedule>> {
+        let schedule = self
+            .transport
+            .signed_get::<_, ()>(Version::SapiV1, "/spot/delist-schedule", None)
+            .await?;
+        Ok(schedule)
+    }
 }
 
 #[cfg(test)]
@@ -59,12 +178,28 @@ mod test {
     }
 
     #[tokio::test]
+    async fn test_get_system_status() -> Result<()> {
+        let b = Binance::new();
+        b.get_system_status().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[allow(deprecated)]
     async fn test_get_exchange_info() -> Result<()> {
         let b = Binance::new();
         b.get_exchange_info().await?;
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_exchange_info_for() -> Result<()> {
+        let b = Binance::new();
+        let info = b.get_exchange_info_for(&["btcusdt", "ethusdt"]).await?;
+        assert_eq!(info.symbols.len(), 2);
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_exchange_info() -> Result<()> {
         let b = Binance::new();
@@ -0,0 +1,121 @@
+use crate::{
+    client::Binance,
+    error::Error,
+    model::{timestamp, Latency, Timed, WarmUp},
+    signing,
+    transport::{Host, Region},
+};
+use anyhow::Result;
+use std::{convert::TryFrom, future::Future, time::Instant};
+use url::Url;
+
+// api.binance.com and its mirrors, which may be closer or less loaded
+const API_HOSTS: [&str; 5] = [
+    "https://api.binance.com",
+    "https://api1.binance.com",
+    "https://api2.binance.com",
+    "https://api3.binance.com",
+    "https://api4.binance.com",
+];
+
+// Latency endpoints
+impl Binance {
+    // Requests the server time `n` times in a row and reports round trip statistics and the
+    // offset between the server clock and the local one
+    pub async fn measure_latency(&self, n: usize) -> Result<Latency> {
+        let mut samples = Vec::with_capacity(n);
+        for _ in 0..n {
+            let sent_at = signing::timestamp();
+            let Timed { value, elapsed } = self.timed(self.get_server_time()).await?;
+            let rtt_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+            let local = i128::from(sent_at) + i128::from(rtt_ms / 2);
+            let server = i128::from(timestamp::to_millis(&value.server_time));
+            let offset = i64::try_from(server - local).unwrap_or_default();
+            samples.push((elapsed, offset));
+        }
+        Ok(Latency::from_samples(&samples).ok_or(Error::InvalidLatencySamples(n))?)
+    }
+
+    // Resolves the spot API host, completes the TLS handshake and leaves the connection open,
+    // so the first order does not pay for the setup. With `pin_fastest` the mirrors api1 to
+    // api4 are warmed up as well, and the one with the fastest warm round trip serves the spot
+    // API of this client and its clones from then on, see `Transport::set_spot_host`. Only
+    // hosts of `Region::Com` are pinned, the other APIs keep their hosts.
+    pub async fn warm_up(&self, pin_fastest: bool) -> Result<WarmUp> {
+        let region = self.transport.region();
+        let hosts = if pin_fastest && region == Region::Com {
+            API_HOSTS.iter().map(|host| (*host).to_string()).collect()
+        } else {
+            vec![region.base_url(&Host::Api).to_string()]
+        };
+
+        let mut fastest: Option<WarmUp> = None;
+        let mut last_error = None;
+        for host in hosts {
+            match self.ping_twice(&host).await {
+                Ok(warm_up) if fastest.as_ref().is_none_or(|best| warm_up.warm < best.warm) => {
+                    fastest = Some(warm_up);
+                }
+                Ok(_) => {}
+                Err(e) => last_error = Some(e),
+            }
+        }
+        let Some(fastest) = fastest else {
+            return Err(last_error.unwrap_or_else(|| anyhow::anyhow!("no host to warm up")));
+        };
+        if pin_fastest && region == Region::Com && fastest.host != API_HOSTS[0] {
+            self.transport
+                .set_spot_host(Some(Url::parse(&fastest.host)?));
+        }
+        Ok(fastest)
+    }
+
+    async fn ping_twice(&self, host: &str) -> Result<WarmUp> {
+        let url = format!("{host}/api/v3/ping");
+        let cold = self.transport.round_trip(&url).await?;
+        let warm = self.transport.round_trip(&url).await?;
+        Ok(WarmUp {
+            host: host.into(),
+            cold,
+            warm,
+        })
+    }
+
+    // Awaits `request`, e.g. `client.timed(client.get_depth("BTCUSDT", None))`, and records how
+    // long it took
+    pub async fn timed<T, F>(&self, request: F) -> Result<Timed<T>>
+    where
+        F: Future<Output = Result<T>> + Send,
+    {
+        let started = Instant::now();
+        let value = request.await?;
+        Ok(Timed {
+            value,
+            elapsed: started.elapsed(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::Binance;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_measure_latency() -> Result<()> {
+        let b = Binance::new();
+        let latency = b.measure_latency(5).await?;
+        assert_eq!(latency.samples, 5);
+        assert!(latency.min <= latency.p99);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_warm_up() -> Result<()> {
+        let b = Binance::new();
+        let warm_up = b.warm_up(true).await?;
+        assert!(warm_up.host.starts_with("https://api"));
+        b.get_server_time().await?;
+        Ok(())
+    }
+}
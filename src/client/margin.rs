@@ -0,0 +1,38 @@
+use crate::transport::Version;
+use crate::{
+    client::Binance,
+    model::margin::{
+        MarginAccount, MarginOcoOrderRequest, MarginOrderRequest, MarginOrderResult, OcoOrder,
+    },
+};
+use anyhow::Result;
+
+// Margin trading endpoints
+impl Binance {
+    // Balances and loans of the cross margin account
+    pub async fn get_margin_account(&self) -> Result<MarginAccount> {
+        let account = self
+            .transport
+            .signed_get::<_, ()>(Version::SapiV1, "/margin/account", None)
+            .await?;
+        Ok(account)
+    }
+
+    // Place an order on the cross margin account, or the isolated one of `order.symbol`
+    pub async fn margin_order(&self, order: MarginOrderRequest) -> Result<MarginOrderResult> {
+        let transaction = self
+            .transport
+            .signed_post(Version::SapiV1, "/margin/order", Some(order))
+            .await?;
+        Ok(transaction)
+    }
+
+    // Place a margin OCO order: a limit order and a stop(-limit) order, one cancels the other
+    pub async fn margin_oco_order(&self, order: MarginOcoOrderRequest) -> Result<OcoOrder> {
+        let oco = self
+            .transport
+            .signed_post(Version::SapiV1, "/margin/order/oco", Some(order))
+            .await?;
+        Ok(oco)
+    }
+}
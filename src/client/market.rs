@@ -1,26 +1,39 @@
 use super::Binance;
 use crate::error::Error;
-use crate::model::{BookTickers, SymbolPrice, Ticker};
-use crate::model::{HistoricalTrade, KlineSummaries, KlineSummary, OrderBook, PriceStats, Prices};
+use crate::model::{
+    pair::IntoTradingPair,
+    request::{
+        AggTradesRequest, DepthRequest, HistoricalTradesRequest, KlinesRequest, SymbolRequest,
+    },
+    BookTickers, SymbolPrice, Ticker,
+};
+use crate::model::{
+    AggTrade, DepthLimit, HistoricalTrade, KlineSummaries, KlineSummary, OrderBook, PriceStats,
+    Prices,
+};
 use crate::transport::Version;
 use anyhow::Result;
 use log::debug;
-use serde_json::{json, Value};
-use std::{collections::HashMap, iter::FromIterator};
 
 // Market Data endpoints
 impl Binance {
-    // Order book (Default 100; max 100)
-    pub async fn get_depth<L>(&self, symbol: &str, limit: L) -> Result<OrderBook>
+    // Order book (Default 100; max 5000). Waits until the weight of `limit`, see
+    // `DepthLimit::weight`, fits into the budget of the current minute.
+    pub async fn get_depth<S, L>(&self, symbol: S, limit: L) -> Result<OrderBook>
     where
-        L: Into<Option<u64>>,
+        S: IntoTradingPair,
+        L: Into<Option<DepthLimit>>,
     {
-        let limit = limit.into().unwrap_or(100);
-        let params = json! {{"symbol": symbol.to_uppercase(), "limit": limit}};
+        let symbol = symbol.into_trading_pair()?;
+        let limit = limit.into().unwrap_or_default();
+        let params = DepthRequest {
+            symbol: symbol.into(),
+            limit: Some(limit.value()),
+        };
 
         Ok(self
             .transport
-            .get(Version::V3, "/depth", Some(params))
+            .weighted_get(Version::V3, "/depth", Some(params), limit.weight())
             .await?)
     }
 
@@ -32,31 +45,51 @@ impl Binance {
             .await?)
     }
 
-    pub async fn get_price(&self, symbol: &str) -> Result<SymbolPrice> {
-        let symbol = symbol.to_uppercase();
-        let params = json! {{"symbol": symbol.to_uppercase()}};
+    // Latest price for ONE symbol, served from the cache if `cache_ttl` is set
+    pub async fn get_price<S>(&self, symbol: S) -> Result<SymbolPrice>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let key = format!("/ticker/price?symbol={symbol}");
+        self.cache
+            .get_or_fetch(key, self.get_price_fresh(symbol))
+            .await
+    }
+
+    // Latest price for ONE symbol, bypassing the cache
+    pub async fn get_price_fresh<S>(&self, symbol: S) -> Result<SymbolPrice>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = SymbolRequest {
+            symbol: symbol.into(),
+        };
         Ok(self
             .transport
             .get(Version::V3, "/ticker/price", Some(params))
             .await?)
     }
 
-    pub async fn get_historical_trades<L, F>(
+    pub async fn get_historical_trades<S, L, F>(
         &self,
-        symbol: &str,
+        symbol: S,
         limit: L,
         from_id: F,
     ) -> Result<Vec<HistoricalTrade>>
     where
+        S: IntoTradingPair,
         L: Into<Option<u16>>,
         F: Into<Option<u64>>,
     {
-        let limit = limit.into().unwrap_or(500);
-        let params = match from_id.into() {
-            Some(v) => json! {{"symbol": symbol.to_uppercase(), "limit": limit, "fromId": v}},
-            None => json! {{"symbol": symbol.to_uppercase(), "limit": limit}},
+        let symbol = symbol.into_trading_pair()?;
+        let params = HistoricalTradesRequest {
+            symbol: symbol.into(),
+            limit: Some(limit.into().unwrap_or(500)),
+            from_id: from_id.into(),
         };
-        debug!("params: {}", params.to_string());
+        debug!("params: {:?}", params);
 
         Ok(self
             .transport
@@ -64,9 +97,46 @@ impl Binance {
             .await?)
     }
 
+    // Compressed, aggregate trades starting at aggregate trade id `from_id`
+    pub async fn get_agg_trades<S, F, L>(
+        &self,
+        symbol: S,
+        from_id: F,
+        limit: L,
+    ) -> Result<Vec<AggTrade>>
+    where
+        S: IntoTradingPair,
+        F: Into<Option<u64>>,
+        L: Into<Option<u16>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = AggTradesRequest {
+            symbol: symbol.into(),
+            from_id: from_id.into(),
+            limit: limit.into(),
+            ..AggTradesRequest::default()
+        };
+
+        let trades = self
+            .transport
+            .get(Version::V3, "/aggTrades", Some(params))
+            .await?;
+        Ok(trades)
+    }
+
     // Symbols order book ticker
-    // -> Best price/qty on the order book for ALL symbols.
+    // -> Best price/qty on the order book for ALL symbols, served from the cache if `cache_ttl` is set
     pub async fn get_all_book_tickers(&self) -> Result<BookTickers> {
+        self.cache
+            .get_or_fetch(
+                "/ticker/allBookTickers".into(),
+                self.get_all_book_tickers_fresh(),
+            )
+            .await
+    }
+
+    // -> Best price/qty on the order book for ALL symbols, bypassing the cache
+    pub async fn get_all_book_tickers_fresh(&self) -> Result<BookTickers> {
         Ok(self
             .transport
             .get::<_, ()>(Version::V1, "/ticker/allBookTickers", None)
@@ -74,21 +144,30 @@ impl Binance {
     }
 
     // -> Best price/qty on the order book for ONE symbol
-    pub async fn get_book_ticker(&self, symbol: &str) -> Result<Ticker> {
-        let symbol = symbol.to_uppercase();
+    pub async fn get_book_ticker<S>(&self, symbol: S) -> Result<Ticker>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
         let all_book_tickers = self.get_all_book_tickers();
 
         let BookTickers::AllBookTickers(book_tickers) = all_book_tickers.await?;
 
         Ok(book_tickers
             .into_iter()
-            .find(|obj| obj.symbol == symbol)
+            .find(|obj| obj.symbol == symbol.as_str())
             .ok_or_else(|| Error::SymbolNotFound)?)
     }
 
     // 24hr ticker price change statistics
-    pub async fn get_24h_price_stats(&self, symbol: &str) -> Result<PriceStats> {
-        let params = json! {{"symbol": symbol}};
+    pub async fn get_24h_price_stats<S>(&self, symbol: S) -> Result<PriceStats>
+    where
+        S: IntoTradingPair,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = SymbolRequest {
+            symbol: symbol.into(),
+        };
         Ok(self
             .transport
             .get(Version::V1, "/ticker/24hr", Some(params))
@@ -97,57 +176,64 @@ impl Binance {
 
     // Returns up to 'limit' klines for given symbol and interval ("1m", "5m", ...)
     // https://github.com/binance-exchange/binance-official-api-docs/blob/master/rest-api.md#klinecandlestick-data
-    pub async fn get_klines<S3, S4, S5>(
+    pub async fn get_klines<S, S3, S4, S5>(
         &self,
-        symbol: &str,
+        symbol: S,
         interval: &str,
         limit: S3,
         start_time: S4,
         end_time: S5,
     ) -> Result<KlineSummaries>
     where
+        S: IntoTradingPair,
+        S3: Into<Option<u16>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u64>>,
+    {
+        let symbol = symbol.into_trading_pair()?;
+        let params = KlinesRequest {
+            symbol: symbol.into(),
+            interval: interval.into(),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            limit: limit.into(),
+        };
+
+        let klines = self
+            .transport
+            .get(Version::V3, "/klines", Some(params))
+            .await?;
+        Ok(KlineSummaries::AllKlineSummaries(klines))
+    }
+
+    // Like `get_klines`, modified for presentation in candlestick charts
+    pub async fn get_ui_klines<S, S3, S4, S5>(
+        &self,
+        symbol: S,
+        interval: &str,
+        limit: S3,
+        start_time: S4,
+        end_time: S5,
+    ) -> Result<Vec<KlineSummary>>
+    where
+        S: IntoTradingPair,
         S3: Into<Option<u16>>,
         S4: Into<Option<u64>>,
         S5: Into<Option<u64>>,
     {
-        let mut params = vec![
-            ("symbol", symbol.to_uppercase()),
-            ("interval", interval.to_string()),
-        ];
-
-        // Add three optional parameters
-        if let Some(lt) = limit.into() {
-            params.push(("limit", lt.to_string()));
-        }
-        if let Some(st) = start_time.into() {
-            params.push(("startTime", st.to_string()));
-        }
-        if let Some(et) = end_time.into() {
-            params.push(("endTime", et.to_string()));
-        }
-        let params: HashMap<&str, String> = HashMap::from_iter(params);
-
-        let f = self.transport.get(Version::V3, "/klines", Some(params));
-
-        let data: Vec<Vec<Value>> = f.await?;
-
-        Ok(KlineSummaries::AllKlineSummaries(
-            data.iter()
-                .map(|row| KlineSummary {
-                    open_time: to_i64(&row[0]),
-                    open: to_f64(&row[1]),
-                    high: to_f64(&row[2]),
-                    low: to_f64(&row[3]),
-                    close: to_f64(&row[4]),
-                    volume: to_f64(&row[5]),
-                    close_time: to_i64(&row[6]),
-                    quote_asset_volume: to_f64(&row[7]),
-                    number_of_trades: to_i64(&row[8]),
-                    taker_buy_base_asset_volume: to_f64(&row[9]),
-                    taker_buy_quote_asset_volume: to_f64(&row[10]),
-                })
-                .collect(),
-        ))
+        let symbol = symbol.into_trading_pair()?;
+        let params = KlinesRequest {
+            symbol: symbol.into(),
+            interval: interval.into(),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            limit: limit.into(),
+        };
+        let klines = self
+            .transport
+            .get(Version::V3, "/uiKlines", Some(params))
+            .await?;
+        Ok(klines)
     }
 
     // 24hr ticker price change statistics
@@ -159,16 +245,9 @@ impl Binance {
     }
 }
 
-fn to_i64(v: &Value) -> i64 {
-    v.as_i64().unwrap()
-}
-
-fn to_f64(v: &Value) -> f64 {
-    v.as_str().unwrap().parse().unwrap()
-}
-
 #[cfg(test)]
 mod test {
+    use crate::model::DepthLimit;
     use crate::tests::test::setup;
     use anyhow::Result;
 
@@ -182,7 +261,15 @@ mod test {
     #[tokio::test]
     async fn test_get_depth_with_limit() -> Result<()> {
         let b = setup()?;
-        b.get_depth("btcusdt", 50).await?;
+        b.get_depth("btcusdt", DepthLimit::L50).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_depth_5000() -> Result<()> {
+        let b = setup()?;
+        let book = b.get_depth("btcusdt", DepthLimit::L5000).await?;
+        assert!(book.bids.len() > 1000);
         Ok(())
     }
 
@@ -207,10 +294,36 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_agg_trades() -> Result<()> {
+        let b = setup()?;
+        b.get_agg_trades("btcusdt", None, 10).await?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_klines() -> Result<()> {
         let b = setup()?;
         b.get_klines("btcusdt", "5m", None, None, None).await?;
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_get_ui_klines() -> Result<()> {
+        let b = setup()?;
+        let klines = b.get_ui_klines("btcusdt", "1m", 10, None, None).await?;
+        assert_eq!(klines.len(), 10);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_klines() -> Result<()> {
+        let b = setup()?;
+        let start = 1_600_000_000_000;
+        let klines = b
+            .download_klines("btcusdt", "1m", start..start + 1500 * 60_000)
+            .await?;
+        assert_eq!(klines.len(), 1500);
+        Ok(())
+    }
 }
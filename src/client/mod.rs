@@ -1,14 +1,55 @@
 mod account;
+mod algo;
+#[cfg(not(target_arch = "wasm32"))]
+mod batch;
+mod blvt;
+#[cfg(all(feature = "browser-websocket", target_arch = "wasm32"))]
+pub mod browser_websocket;
+mod credentials;
+mod dual_investment;
+mod futures;
 mod general;
+#[cfg(not(target_arch = "wasm32"))]
+mod latency;
+mod margin;
 mod market;
+#[cfg(not(target_arch = "wasm32"))]
+mod pagination;
+mod pay;
+mod portfolio_margin;
+mod raw;
+mod rebate;
+mod traits;
 mod userstream;
+mod vip_loan;
+mod wallet;
+#[cfg(not(target_arch = "wasm32"))]
 pub mod websocket;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod ws_api;
 
-use crate::transport::Transport;
+pub use self::traits::{MarketData, Trading, UserStream};
+
+use crate::cache::ResponseCache;
+use crate::model::Symbol;
+use crate::proxy::Proxy;
+use crate::transport::{Credential, Host, HttpConfig, Region, Transport};
+use anyhow::Result;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 #[derive(Clone, Default, Debug)]
 pub struct Binance {
     pub transport: Transport,
+    // Attach a generated `newClientOrderId` to orders placed without one
+    pub auto_client_order_id: bool,
+    cache: Arc<ResponseCache>,
+    // Trading rules of the symbols orders were formatted for with the millisecond timestamp they
+    // were fetched at, kept for `cache_ttl` or until `refresh_symbol_info` if it is not set
+    symbols: Arc<Mutex<HashMap<String, (i64, Symbol)>>>,
 }
 
 impl Binance {
@@ -21,6 +62,141 @@ impl Binance {
     pub fn with_credential(api_key: &str, api_secret: &str) -> Self {
         Self {
             transport: Transport::with_credential(api_key, api_secret),
+            ..Self::default()
         }
     }
+
+    #[must_use]
+    pub fn builder() -> BinanceBuilder {
+        BinanceBuilder::default()
+    }
+
+    // Rotate the API key, e.g. before the old one expires. Clones of this client share the
+    // credential and switch as well, open websocket streams are not affected.
+    pub fn set_credential(&self, api_key: &str, api_secret: &str) {
+        self.transport
+            .set_credential(Some(Credential::new(api_key, api_secret)));
+    }
+
+    // Signed endpoints fail with `Error::NoApiKeySet` afterwards
+    pub fn clear_credential(&self) {
+        self.transport.set_credential(None);
+    }
+
+    // Request weight used on `host` in the current minute, every host has a limit of its own.
+    // Past 80% of the limit, market data requests wait for the next minute, past 95%
+    // cancellations do as well.
+    #[must_use]
+    pub fn used_weight(&self, host: &Host) -> u32 {
+        self.transport.used_weight(host)
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct BinanceBuilder {
+    credential: Option<Credential>,
+    http: HttpConfig,
+    auto_client_order_id: bool,
+    cache_ttl: Option<Duration>,
+}
+
+impl BinanceBuilder {
+    #[must_use]
+    pub fn credential(mut self, api_key: &str, api_secret: &str) -> Self {
+        self.credential = Some(Credential::new(api_key, api_secret));
+        self
+    }
+
+    // Time allowed to establish a connection, `None` waits forever
+    #[must_use]
+    pub const fn connect_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.http.connect_timeout = timeout;
+        self
+    }
+
+    // Time allowed for a whole request, from connecting to reading the body
+    #[must_use]
+    pub const fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.http.timeout = timeout;
+        self
+    }
+
+    // How long idle keep-alive connections stay in the pool
+    #[must_use]
+    pub const fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.http.pool_idle_timeout = timeout;
+        self
+    }
+
+    #[must_use]
+    pub const fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.http.pool_max_idle_per_host = max;
+        self
+    }
+
+    #[must_use]
+    pub const fn tcp_nodelay(mut self, enabled: bool) -> Self {
+        self.http.tcp_nodelay = enabled;
+        self
+    }
+
+    #[must_use]
+    pub const fn tcp_keepalive(mut self, interval: Option<Duration>) -> Self {
+        self.http.tcp_keepalive = interval;
+        self
+    }
+
+    // Speak HTTP/2 right away instead of negotiating it
+    #[must_use]
+    pub const fn http2_prior_knowledge(mut self, enabled: bool) -> Self {
+        self.http.http2_prior_knowledge = enabled;
+        self
+    }
+
+    // Route REST requests through `proxy`, see `BinanceWebsocket::with_proxy` for streams
+    #[must_use]
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.http.proxy = Some(proxy);
+        self
+    }
+
+    // Exchange to talk to, e.g. `Region::Us` for Binance.US. Endpoints it does not serve fail
+    // with `Error::UnsupportedEndpoint` without a request.
+    #[must_use]
+    pub fn region(mut self, region: Region) -> Self {
+        self.http.region = region;
+        self
+    }
+
+    // Request weight per minute, lower it when other processes share the IP
+    #[must_use]
+    pub const fn weight_limit(mut self, limit: u32) -> Self {
+        self.http.weight_limit = limit;
+        self
+    }
+
+    // Serve prices, book tickers and exchange info from memory for `ttl` instead of asking the
+    // exchange again, the `*_fresh` methods always do. Off (`None`) by default. The trading
+    // rules of `symbol_info` are fetched again after `ttl` as well.
+    #[must_use]
+    pub const fn cache_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
+    // Generate a unique `newClientOrderId` for every order so retries can be deduplicated
+    #[must_use]
+    pub const fn auto_client_order_id(mut self, enabled: bool) -> Self {
+        self.auto_client_order_id = enabled;
+        self
+    }
+
+    pub fn build(self) -> Result<Binance> {
+        Ok(Binance {
+            transport: Transport::with_config(self.credential, &self.http)?,
+            auto_client_order_id: self.auto_client_order_id,
+            cache: Arc::new(ResponseCache::new(self.cache_ttl)),
+            symbols: Arc::default(),
+        })
+    }
 }
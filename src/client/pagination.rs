@@ -0,0 +1,535 @@
+use crate::{
+    client::Binance,
+    error::Error,
+    model::{
+        pair::{IntoTradingPair, TradingPair},
+        request::VipLoanOrdersRequest,
+        timestamp,
+        vip_loan::VipLoanOrder,
+        AggTrade, DepositRecord, KlineSummaries, KlineSummary, Order, Page, SystemStatus,
+        TradeHistory, WithdrawRecord,
+    },
+};
+use anyhow::Result;
+use chrono::Utc;
+use futures::{prelude::*, stream};
+use std::{convert::TryFrom, ops::Range, time::Duration};
+use tokio::time::sleep;
+use tracing::{info_span, Instrument};
+
+// Largest page the history endpoints hand out
+const PAGE_LIMIT: u16 = 1000;
+// Largest page of the SAPI endpoints answering with `Page`
+const ROWS_LIMIT: u16 = 100;
+// Pause between two page requests so a long walk stays well below the weight limits
+const PAGE_DELAY: Duration = Duration::from_millis(200);
+// Pause before retrying a page rejected with TOO_MANY_REQUESTS
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(10);
+// Pause before asking whether a maintenance is over
+const MAINTENANCE_BACKOFF: Duration = Duration::from_mins(1);
+const MAX_RETRIES: usize = 3;
+const TOO_MANY_REQUESTS: i64 = -1003;
+// Longest time range the deposit and withdraw history endpoints take
+const HISTORY_WINDOW: u64 = 90 * 24 * 60 * 60 * 1000;
+// Nothing was deposited before the exchange opened, 2017-07-01
+const EXCHANGE_LAUNCH: u64 = 1_498_867_200_000;
+
+// Paginated history endpoints
+impl Binance {
+    // Every order ever placed on ONE symbol, oldest first
+    pub fn all_orders_stream<'a, S>(&'a self, symbol: S) -> impl Stream<Item = Result<Order>> + 'a
+    where
+        S: IntoTradingPair + 'a,
+    {
+        for_pair(symbol, move |symbol| {
+            paginate(
+                self,
+                0,
+                move |order_id| {
+                    let symbol = symbol.clone();
+                    async move { self.get_all_orders(symbol, order_id, PAGE_LIMIT).await }
+                },
+                |_, page: &[Order]| next_id(page, |o| o.order_id),
+            )
+        })
+    }
+
+    // Every trade of the account on ONE symbol, oldest first
+    pub fn my_trades_stream<'a, S>(
+        &'a self,
+        symbol: S,
+    ) -> impl Stream<Item = Result<TradeHistory>> + 'a
+    where
+        S: IntoTradingPair + 'a,
+    {
+        for_pair(symbol, move |symbol| {
+            paginate(
+                self,
+                0,
+                move |from_id| {
+                    let symbol = symbol.clone();
+                    async move { self.get_my_trades(symbol, from_id, PAGE_LIMIT).await }
+                },
+                |_, page: &[TradeHistory]| next_id(page, |t| t.id),
+            )
+        })
+    }
+
+    // Aggregate trades on ONE symbol starting at aggregate trade id `from_id`
+    pub fn agg_trades_stream<'a, S>(
+        &'a self,
+        symbol: S,
+        from_id: u64,
+    ) -> impl Stream<Item = Result<AggTrade>> + 'a
+    where
+        S: IntoTradingPair + 'a,
+    {
+        for_pair(symbol, move |symbol| {
+            paginate(
+                self,
+                from_id,
+                move |from_id| {
+                    let symbol = symbol.clone();
+                    async move { self.get_agg_trades(symbol, from_id, PAGE_LIMIT).await }
+                },
+                |_, page: &[AggTrade]| next_id(page, |t| t.aggregated_trade_id),
+            )
+        })
+    }
+
+    // Klines of ONE symbol opening within `range` (millisecond timestamps), oldest first. An
+    // empty range yields nothing.
+    pub fn klines_stream<'a, S>(
+        &'a self,
+        symbol: S,
+        interval: &str,
+        range: Range<u64>,
+    ) -> impl Stream<Item = Result<KlineSummary>> + 'a
+    where
+        S: IntoTradingPair + 'a,
+    {
+        if range.is_empty() {
+            return stream::empty().left_stream();
+        }
+        let interval = interval.to_string();
+        let end = range.end;
+        for_pair(symbol, move |symbol| {
+            paginate(
+                self,
+                range.start,
+                move |start_time| {
+                    let symbol = symbol.clone();
+                    let interval = interval.clone();
+                    async move {
+                        let KlineSummaries::AllKlineSummaries(klines) = self
+                            .get_klines(
+                                symbol,
+                                &interval,
+                                PAGE_LIMIT,
+                                start_time,
+                                end.saturating_sub(1),
+                            )
+                            .await?;
+                        Ok(klines)
+                    }
+                },
+                move |_, page: &[KlineSummary]| {
+                    next_id(page, |k| timestamp::to_millis(&k.open_time))
+                        .filter(|start_time| *start_time < end)
+                },
+            )
+        })
+        .right_stream()
+    }
+
+    // Downloads every kline of ONE symbol opening within `range` (millisecond timestamps),
+    // splitting the range into as many requests as needed
+    pub async fn download_klines<S>(
+        &self,
+        symbol: S,
+        interval: &str,
+        range: Range<u64>,
+    ) -> Result<Vec<KlineSummary>>
+    where
+        S: IntoTradingPair,
+    {
+        let mut klines: Vec<KlineSummary> = Vec::new();
+        let mut stream = Box::pin(self.klines_stream(symbol, interval, range));
+        while let Some(kline) = stream.try_next().await? {
+            if klines
+                .last()
+                .is_none_or(|last| last.open_time < kline.open_time)
+            {
+                klines.push(kline);
+            }
+        }
+        Ok(klines)
+    }
+
+    // Deposit history since `since` (millisecond timestamp), the exchange launch if `None`,
+    // newest first. The endpoint answers for at most 90 days, so the history is walked
+    // backwards one window at a time.
+    pub fn deposit_history_stream<S>(
+        &self,
+        since: S,
+    ) -> impl Stream<Item = Result<DepositRecord>> + '_
+    where
+        S: Into<Option<u64>>,
+    {
+        let since = since.into().unwrap_or(EXCHANGE_LAUNCH);
+        let Some(window) = latest_window(since, now_ms()) else {
+            return stream::empty().left_stream();
+        };
+        paginate(
+            self,
+            (window, 0),
+            move |(window, offset)| async move {
+                self.get_deposit_history_between(None, window, offset, PAGE_LIMIT)
+                    .await
+            },
+            move |cursor, page: &[DepositRecord]| next_window_page(cursor, page, since),
+        )
+        .right_stream()
+    }
+
+    // Withdraw history since `since` (millisecond timestamp), the exchange launch if `None`,
+    // newest first, walked backwards like `deposit_history_stream`
+    pub fn withdraw_history_stream<S>(
+        &self,
+        since: S,
+    ) -> impl Stream<Item = Result<WithdrawRecord>> + '_
+    where
+        S: Into<Option<u64>>,
+    {
+        let since = since.into().unwrap_or(EXCHANGE_LAUNCH);
+        let Some(window) = latest_window(since, now_ms()) else {
+            return stream::empty().left_stream();
+        };
+        paginate(
+            self,
+            (window, 0),
+            move |(window, offset)| async move {
+                self.get_withdraw_history_between(None, window, offset, PAGE_LIMIT)
+                    .await
+            },
+            move |cursor, page: &[WithdrawRecord]| next_window_page(cursor, page, since),
+        )
+        .right_stream()
+    }
+
+    // Every ongoing VIP loan order matching `request`, whose `current` and `limit` are ignored
+    pub fn vip_loan_ongoing_orders_stream(
+        &self,
+        request: VipLoanOrdersRequest,
+    ) -> impl Stream<Item = Result<VipLoanOrder>> + '_ {
+        paginate_rows(self, move |current| {
+            let request = VipLoanOrdersRequest {
+                current: Some(current),
+                limit: Some(ROWS_LIMIT),
+                ..request.clone()
+            };
+            async move { self.get_vip_loan_ongoing_orders(request).await }
+        })
+    }
+}
+
+// The pages of ONE symbol, an invalid symbol ends the stream with its error right away
+fn for_pair<S, T, St, F>(symbol: S, pages: F) -> impl Stream<Item = Result<T>>
+where
+    S: IntoTradingPair,
+    St: Stream<Item = Result<T>>,
+    F: FnOnce(TradingPair) -> St,
+{
+    future::ready(symbol.into_trading_pair().map_err(anyhow::Error::from))
+        .map_ok(pages)
+        .try_flatten_stream()
+}
+
+// Walks the pages of a history endpoint, starting at cursor `start`.
+// `next` computes the cursor of the following page or `None` once the last page was seen.
+fn paginate<'a, T, C, F, Fut, N>(
+    client: &'a Binance,
+    start: C,
+    fetch: F,
+    next: N,
+) -> impl Stream<Item = Result<T>> + 'a
+where
+    T: 'a,
+    C: Clone + 'a,
+    F: FnMut(C) -> Fut + 'a,
+    Fut: Future<Output = Result<Vec<T>>> + 'a,
+    N: Fn(&C, &[T]) -> Option<C> + 'a,
+{
+    stream::try_unfold(
+        (Some(start), fetch, next, true),
+        move |(cursor, mut fetch, next, first)| async move {
+            let Some(cursor) = cursor else {
+                return Ok(None);
+            };
+            if !first {
+                sleep(PAGE_DELAY).await;
+            }
+            let page =
+                with_backoff(|| client.get_system_status(), || fetch(cursor.clone())).await?;
+            let cursor = next(&cursor, &page);
+            Ok::<_, anyhow::Error>(Some((page, (cursor, fetch, next, false))))
+        },
+    )
+    .map_ok(|page| stream::iter(page.into_iter().map(Ok)))
+    .try_flatten()
+}
+
+// Walks the numbered pages of an endpoint answering with `Page`, starting at page 1, until
+// `total` rows were seen
+fn paginate_rows<'a, T, F, Fut>(
+    client: &'a Binance,
+    mut fetch: F,
+) -> impl Stream<Item = Result<T>> + 'a
+where
+    T: 'a,
+    F: FnMut(u32) -> Fut + 'a,
+    Fut: Future<Output = Result<Page<T>>> + 'a,
+{
+    paginate(
+        client,
+        (1_u32, 0_u64),
+        move |(current, _)| fetch(current).map_ok(|page| vec![page]),
+        |(current, seen), pages: &[Page<T>]| {
+            let page = pages.first()?;
+            let seen = seen + page.rows.len() as u64;
+            (!page.is_last(seen)).then_some((current + 1, seen))
+        },
+    )
+    .map_ok(|page| stream::iter(page.rows.into_iter().map(Ok)))
+    .try_flatten()
+}
+
+// Retries `f` after a rate limit, and after maintenance once `status` reports the exchange back
+// in operation. Gives up with the last error after `MAX_RETRIES` waits.
+async fn with_backoff<T, S, SFut, F, Fut>(mut status: S, mut f: F) -> Result<T>
+where
+    S: FnMut() -> SFut,
+    SFut: Future<Output = Result<SystemStatus>>,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    // The requests of all attempts are children of one span
+    let span = info_span!("binance_retries", retries = 0);
+    let mut retries = 0;
+    loop {
+        match f().instrument(span.clone()).await {
+            Err(e) if retries < MAX_RETRIES && is_rate_limited(&e) => {
+                retries += 1;
+                span.record("retries", retries);
+                sleep(RATE_LIMIT_BACKOFF).await;
+            }
+            Err(e) if retries < MAX_RETRIES && is_maintenance(&e) => loop {
+                retries += 1;
+                span.record("retries", retries);
+                sleep(MAINTENANCE_BACKOFF).await;
+                if !status().instrument(span.clone()).await?.is_maintenance() {
+                    break;
+                }
+                if retries == MAX_RETRIES {
+                    return Err(e);
+                }
+            },
+            result => return result,
+        }
+    }
+}
+
+fn is_rate_limited(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<Error>(),
+        Some(Error::BinanceError { code, .. }) if *code == TOO_MANY_REQUESTS
+    )
+}
+
+fn is_maintenance(e: &anyhow::Error) -> bool {
+    matches!(
+        e.downcast_ref::<Error>(),
+        Some(Error::ExchangeMaintenance(_))
+    )
+}
+
+fn next_id<T>(page: &[T], id: impl Fn(&T) -> u64) -> Option<u64> {
+    if page.len() < usize::from(PAGE_LIMIT) {
+        return None;
+    }
+    page.last().map(|last| id(last) + 1)
+}
+
+#[allow(clippy::cast_possible_truncation)]
+fn next_offset<T>(offset: u32, page: &[T]) -> Option<u32> {
+    if page.len() < usize::from(PAGE_LIMIT) {
+        return None;
+    }
+    Some(offset + page.len() as u32)
+}
+
+// The most recent history window reaching back to `since`, `None` if `since` is not in the past
+fn latest_window(since: u64, now: u64) -> Option<Range<u64>> {
+    (since < now).then(|| now.saturating_sub(HISTORY_WINDOW).max(since)..now)
+}
+
+// The next page of the current window, or the first page of the window before it
+fn next_window_page<T>(
+    (window, offset): &(Range<u64>, u32),
+    page: &[T],
+    since: u64,
+) -> Option<(Range<u64>, u32)> {
+    if let Some(offset) = next_offset(*offset, page) {
+        return Some((window.clone(), offset));
+    }
+    latest_window(since, window.start).map(|window| (window, 0))
+}
+
+fn now_ms() -> u64 {
+    u64::try_from(Utc::now().timestamp_millis()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        latest_window, next_id, next_offset, next_window_page, paginate, paginate_rows,
+        with_backoff, HISTORY_WINDOW, MAINTENANCE_BACKOFF, PAGE_LIMIT,
+    };
+    use crate::{
+        error::Error,
+        model::{Page, SystemStatus},
+        Binance,
+    };
+    use anyhow::Result;
+    use futures::{future, TryStreamExt};
+    use std::cell::Cell;
+    use tokio::time::Instant;
+
+    #[test]
+    fn test_next_id() {
+        let full: Vec<u64> = (0..u64::from(PAGE_LIMIT)).collect();
+        assert_eq!(next_id(&full, |i| *i), Some(u64::from(PAGE_LIMIT)));
+        assert_eq!(next_id(&full[1..], |i| *i), None);
+        assert_eq!(next_offset(1000, &full), Some(2000));
+        assert_eq!(next_offset::<u64>(0, &[]), None);
+    }
+
+    #[test]
+    fn history_windows() {
+        let now = 4 * HISTORY_WINDOW;
+        let since = HISTORY_WINDOW / 2;
+        let window = latest_window(since, now).unwrap();
+        assert_eq!(window, 3 * HISTORY_WINDOW..now);
+        assert_eq!(latest_window(now, now), None);
+
+        // A full page stays in its window, a short one moves to the window before
+        let full: Vec<u64> = (0..u64::from(PAGE_LIMIT)).collect();
+        let cursor = (window, 0);
+        assert_eq!(
+            next_window_page(&cursor, &full, since),
+            Some((3 * HISTORY_WINDOW..now, 1000))
+        );
+        let mut cursor = cursor;
+        let mut windows = Vec::new();
+        while let Some(next) = next_window_page(&cursor, &full[1..], since) {
+            windows.push(next.0.clone());
+            cursor = next;
+        }
+        assert_eq!(
+            windows,
+            [
+                2 * HISTORY_WINDOW..3 * HISTORY_WINDOW,
+                HISTORY_WINDOW..2 * HISTORY_WINDOW,
+                since..HISTORY_WINDOW,
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_paginate() -> Result<()> {
+        let client = Binance::new();
+        let items: Vec<u64> = paginate(
+            &client,
+            0_u64,
+            |from| future::ok((from..(from + 3).min(7)).collect::<Vec<_>>()),
+            |_, page: &[u64]| match page.len() {
+                3 => page.last().map(|last| last + 1),
+                _ => None,
+            },
+        )
+        .try_collect()
+        .await?;
+        assert_eq!(items, (0..7).collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn maintenance_backoff() -> Result<()> {
+        let maintenance = || future::err(Error::ExchangeMaintenance("503".into()).into());
+        let status = |status| {
+            future::ok(SystemStatus {
+                status,
+                msg: String::new(),
+            })
+        };
+
+        // Retried once the status reports normal operation
+        let (attempts, polls) = (Cell::new(0), Cell::new(0));
+        let start = Instant::now();
+        let result: Result<u8> = with_backoff(
+            || {
+                polls.set(polls.get() + 1);
+                status(u8::from(polls.get() < 2))
+            },
+            || {
+                attempts.set(attempts.get() + 1);
+                match attempts.get() {
+                    1 => maintenance(),
+                    _ => future::ok(7),
+                }
+            },
+        )
+        .await;
+        assert_eq!(result.ok(), Some(7));
+        assert_eq!((attempts.get(), polls.get()), (2, 2));
+        assert_eq!(start.elapsed(), MAINTENANCE_BACKOFF * 2);
+
+        // Gives up while the maintenance lasts
+        let result: Result<u8> = with_backoff(|| status(1), maintenance).await;
+        assert!(matches!(
+            result.unwrap_err().downcast_ref(),
+            Some(Error::ExchangeMaintenance(_))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn empty_kline_range() -> Result<()> {
+        let client = Binance::new();
+        let (start, end) = (1_700_000_000_000, 1_600_000_000_000);
+        for range in [0..0, start..end] {
+            let klines: Vec<_> = client
+                .klines_stream("BTCUSDT", "1m", range)
+                .try_collect()
+                .await?;
+            assert!(klines.is_empty());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_paginate_rows() -> Result<()> {
+        let client = Binance::new();
+        let items: Vec<u64> = paginate_rows(&client, |current| {
+            let start = u64::from(current - 1) * 2;
+            future::ok(Page {
+                rows: (start..(start + 2).min(5)).collect(),
+                total: 5,
+            })
+        })
+        .try_collect()
+        .await?;
+        assert_eq!(items, (0..5).collect::<Vec<_>>());
+        Ok(())
+    }
+}
@@ -0,0 +1,93 @@
+use crate::transport::Version;
+use crate::{
+    client::Binance,
+    model::{
+        pay::{GiftCard, GiftCardVerification, PayResponse, PayTransaction, RedeemedGiftCard},
+        request::{
+            CreateGiftCardRequest, PayTransactionsRequest, RedeemGiftCardRequest,
+            VerifyGiftCardRequest,
+        },
+    },
+};
+use anyhow::Result;
+
+// Binance Pay and Gift Card endpoints
+impl Binance {
+    // Pay transactions between `start_time` and `end_time` (Default 100; max 100)
+    pub async fn get_pay_transactions<S, E, L>(
+        &self,
+        start_time: S,
+        end_time: E,
+        limit: L,
+    ) -> Result<Vec<PayTransaction>>
+    where
+        S: Into<Option<u64>>,
+        E: Into<Option<u64>>,
+        L: Into<Option<u16>>,
+    {
+        let params = PayTransactionsRequest {
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            limit: limit.into(),
+        };
+        let response: PayResponse<_> = self
+            .transport
+            .signed_get(Version::SapiV1, "/pay/transactions", Some(params))
+            .await?;
+        Ok(response.into_data()?)
+    }
+
+    // Create a gift card worth `amount` of `token`, paid from the spot wallet
+    pub async fn create_gift_card(&self, token: &str, amount: f64) -> Result<GiftCard> {
+        let params = CreateGiftCardRequest {
+            token: token.into(),
+            amount,
+        };
+        let response: PayResponse<_> = self
+            .transport
+            .signed_post(Version::SapiV1, "/giftcard/createCode", Some(params))
+            .await?;
+        Ok(response.into_data()?)
+    }
+
+    // Redeem a gift card code into the spot wallet
+    pub async fn redeem_gift_card<U>(&self, code: &str, external_uid: U) -> Result<RedeemedGiftCard>
+    where
+        U: Into<Option<String>>,
+    {
+        let params = RedeemGiftCardRequest {
+            code: code.into(),
+            external_uid: external_uid.into(),
+        };
+        let response: PayResponse<_> = self
+            .transport
+            .signed_post(Version::SapiV1, "/giftcard/redeemCode", Some(params))
+            .await?;
+        Ok(response.into_data()?)
+    }
+
+    // Check whether the gift card with `reference_no` is still valid
+    pub async fn verify_gift_card(&self, reference_no: &str) -> Result<GiftCardVerification> {
+        let params = VerifyGiftCardRequest {
+            reference_no: reference_no.into(),
+        };
+        let response: PayResponse<_> = self
+            .transport
+            .signed_get(Version::SapiV1, "/giftcard/verify", Some(params))
+            .await?;
+        Ok(response.into_data()?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tests::test::setup;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_get_pay_transactions() -> Result<()> {
+        let b = setup()?;
+        b.get_pay_transactions(None, None, 10).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+use crate::transport::Version;
+use crate::{
+    client::Binance,
+    model::{
+        portfolio_margin::{
+            CollateralRate, PortfolioMarginAccount, PortfolioMarginBalance, PortfolioMarginOrder,
+            PortfolioMarginOrderRequest,
+        },
+        request::AssetRequest,
+    },
+};
+use anyhow::Result;
+
+// Portfolio Margin endpoints
+impl Binance {
+    // Portfolio margin account information
+    pub async fn get_pm_account(&self) -> Result<PortfolioMarginAccount> {
+        let account = self
+            .transport
+            .signed_get::<_, ()>(Version::PapiV1, "/account", None)
+            .await?;
+        Ok(account)
+    }
+
+    // Portfolio margin balances for ALL assets
+    pub async fn get_pm_balances(&self) -> Result<Vec<PortfolioMarginBalance>> {
+        let balances = self
+            .transport
+            .signed_get::<_, ()>(Version::PapiV1, "/balance", None)
+            .await?;
+        Ok(balances)
+    }
+
+    // Portfolio margin balance for ONE asset
+    pub async fn get_pm_balance(&self, asset: &str) -> Result<PortfolioMarginBalance> {
+        let params = AssetRequest::default().asset(asset);
+        let balance = self
+            .transport
+            .signed_get(Version::PapiV1, "/balance", Some(params))
+            .await?;
+        Ok(balance)
+    }
+
+    // Collateral rate of every asset counted towards the unified margin
+    pub async fn get_pm_collateral_rates(&self) -> Result<Vec<CollateralRate>> {
+        let rates = self
+            .transport
+            .get::<_, ()>(Version::SapiV1, "/portfolio/collateralRate", None)
+            .await?;
+        Ok(rates)
+    }
+
+    // Place a USDⓈ-M futures order through the portfolio margin account
+    pub async fn pm_um_order(
+        &self,
+        order: PortfolioMarginOrderRequest,
+    ) -> Result<PortfolioMarginOrder> {
+        let transaction = self
+            .transport
+            .signed_post(Version::PapiV1, "/um/order", Some(order))
+            .await?;
+        Ok(transaction)
+    }
+
+    // Place a COIN-M futures order through the portfolio margin account
+    pub async fn pm_cm_order(
+        &self,
+        order: PortfolioMarginOrderRequest,
+    ) -> Result<PortfolioMarginOrder> {
+        let transaction = self
+            .transport
+            .signed_post(Version::PapiV1, "/cm/order", Some(order))
+            .await?;
+        Ok(transaction)
+    }
+
+    // Place a cross margin order through the portfolio margin account
+    pub async fn pm_margin_order(
+        &self,
+        order: PortfolioMarginOrderRequest,
+    ) -> Result<PortfolioMarginOrder> {
+        let transaction = self
+            .transport
+            .signed_post(Version::PapiV1, "/margin/order", Some(order))
+            .await?;
+        Ok(transaction)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tests::test::setup;
+    use anyhow::Result;
+
+    #[tokio::test]
+    #[ignore = "requires a portfolio margin account"]
+    async fn test_get_pm_account() -> Result<()> {
+        let b = setup()?;
+        b.get_pm_account().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    #[ignore = "requires a portfolio margin account"]
+    async fn test_get_pm_balances() -> Result<()> {
+        let b = setup()?;
+        b.get_pm_balances().await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_pm_collateral_rates() -> Result<()> {
+        let b = setup()?;
+        b.get_pm_collateral_rates().await?;
+        Ok(())
+    }
+}
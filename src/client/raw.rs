@@ -0,0 +1,150 @@
+use crate::transport::{Endpoint, Host};
+use crate::{client::Binance, error::Error};
+use anyhow::Result;
+use http::Method;
+use serde::Serialize;
+use serde_json::{to_value, Value};
+
+// Endpoints the crate does not wrap (yet), on any host. `path` includes the API prefix, e.g.
+// "/api/v3/time", and `params` must serialize to a map, e.g. a `json!({...})` object.
+impl Binance {
+    pub async fn raw_get<Q: Serialize + Send>(
+        &self,
+        host: Host,
+        path: &str,
+        params: Option<Q>,
+    ) -> Result<Value> {
+        let params = raw_params(params)?;
+        self.transport
+            .request::<_, _, ()>(Method::GET, Endpoint::new(host, path), params, None)
+            .await
+            .and_then(raw_result)
+    }
+
+    pub async fn raw_post<Q: Serialize + Send>(
+        &self,
+        host: Host,
+        path: &str,
+        params: Option<Q>,
+    ) -> Result<Value> {
+        let params = raw_params(params)?;
+        self.transport
+            .request::<_, (), _>(Method::POST, Endpoint::new(host, path), None, params)
+            .await
+            .and_then(raw_result)
+    }
+
+    pub async fn raw_delete<Q: Serialize + Send>(
+        &self,
+        host: Host,
+        path: &str,
+        params: Option<Q>,
+    ) -> Result<Value> {
+        let params = raw_params(params)?;
+        self.transport
+            .request::<_, _, ()>(Method::DELETE, Endpoint::new(host, path), params, None)
+            .await
+            .and_then(raw_result)
+    }
+
+    pub async fn signed_raw_get<Q: Serialize + Send>(
+        &self,
+        host: Host,
+        path: &str,
+        params: Option<Q>,
+    ) -> Result<Value> {
+        let params = raw_params(params)?;
+        self.transport
+            .signed_request::<_, _, ()>(Method::GET, Endpoint::new(host, path), params, None)
+            .await
+            .and_then(raw_result)
+    }
+
+    pub async fn signed_raw_post<Q: Serialize + Send>(
+        &self,
+        host: Host,
+        path: &str,
+        params: Option<Q>,
+    ) -> Result<Value> {
+        let params = raw_params(params)?;
+        self.transport
+            .signed_request::<_, (), _>(Method::POST, Endpoint::new(host, path), None, params)
+            .await
+            .and_then(raw_result)
+    }
+
+    pub async fn signed_raw_delete<Q: Serialize + Send>(
+        &self,
+        host: Host,
+        path: &str,
+        params: Option<Q>,
+    ) -> Result<Value> {
+        let params = raw_params(params)?;
+        self.transport
+            .signed_request::<_, _, ()>(Method::DELETE, Endpoint::new(host, path), params, None)
+            .await
+            .and_then(raw_result)
+    }
+}
+
+// Error statuses fail in the transport already, this catches errors sent with a 200. Some
+// endpoints answer `{"code":200,"msg":"success"}`, so only negative codes are errors.
+fn raw_result(value: Value) -> Result<Value> {
+    let code = value.get("code").and_then(Value::as_i64);
+    let msg = value.get("msg").and_then(Value::as_str);
+    match (code, msg) {
+        (Some(code), Some(msg)) if code < 0 => Err(Error::BinanceError {
+            code,
+            msg: msg.into(),
+        }
+        .into()),
+        _ => Ok(value),
+    }
+}
+
+// Anything but a map would not turn into `key=value` pairs
+fn raw_params<Q: Serialize>(params: Option<Q>) -> Result<Option<Value>> {
+    let Some(params) = params else {
+        return Ok(None);
+    };
+    match to_value(params)? {
+        Value::Null => Ok(None),
+        params @ Value::Object(_) => Ok(Some(params)),
+        other => Err(Error::InvalidParams(other.to_string()).into()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{raw_params, raw_result};
+    use crate::{error::Error, transport::Host, Binance};
+    use anyhow::Result;
+    use serde_json::json;
+
+    #[test]
+    fn raw_params_must_be_a_map() -> Result<()> {
+        assert!(raw_params(Some(json!({"symbol": "BTCUSDT"})))?.is_some());
+        assert!(raw_params::<()>(None)?.is_none());
+        assert!(raw_params(Some(["symbol", "BTCUSDT"])).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn raw_error_bodies() {
+        let error = raw_result(json!({"code": -1121, "msg": "Invalid symbol."})).unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(Error::BinanceError { code: -1121, .. })
+        ));
+        assert!(raw_result(json!({"code": 200, "msg": "success"})).is_ok());
+        assert!(raw_result(json!([{"code": -1}])).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_raw_get() -> Result<()> {
+        let b = Binance::new();
+        let time = b.raw_get(Host::Api, "/api/v3/time", None::<()>).await?;
+        assert!(time["serverTime"].is_u64());
+        Ok(())
+    }
+}
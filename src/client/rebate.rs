@@ -0,0 +1,81 @@
+use crate::transport::Version;
+use crate::{
+    client::Binance,
+    model::{
+        rebate::{BrokerRebate, RebateHistory, RebatePage, ReferralRebate},
+        request::{RebateHistoryRequest, RebateRecordsRequest},
+    },
+};
+use anyhow::Result;
+
+// Rebate and referral endpoints
+impl Binance {
+    // Spot rebate history of ONE page, the range defaults to the last 7 days
+    pub async fn get_spot_rebate_history<S, E, P>(
+        &self,
+        start_time: S,
+        end_time: E,
+        page: P,
+    ) -> Result<RebatePage>
+    where
+        S: Into<Option<u64>>,
+        E: Into<Option<u64>>,
+        P: Into<Option<u32>>,
+    {
+        let params = RebateHistoryRequest {
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            page: page.into(),
+        };
+        let history: RebateHistory = self
+            .transport
+            .signed_get(Version::SapiV1, "/rebate/taxQuery", Some(params))
+            .await?;
+        Ok(history.data)
+    }
+
+    // Recent commission of the users referred through the API referral program
+    pub async fn get_referral_rebate_records(
+        &self,
+        request: RebateRecordsRequest,
+    ) -> Result<Vec<ReferralRebate>> {
+        let records = self
+            .transport
+            .signed_get(
+                Version::SapiV1,
+                "/apiReferral/rebate/recentRecord",
+                Some(request),
+            )
+            .await?;
+        Ok(records)
+    }
+
+    // Recent commission earned on the trades of broker sub-accounts
+    pub async fn get_broker_rebate_records(
+        &self,
+        request: RebateRecordsRequest,
+    ) -> Result<Vec<BrokerRebate>> {
+        let records = self
+            .transport
+            .signed_get(
+                Version::SapiV1,
+                "/broker/rebate/recentRecord",
+                Some(request),
+            )
+            .await?;
+        Ok(records)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tests::test::setup;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_get_spot_rebate_history() -> Result<()> {
+        let b = setup()?;
+        b.get_spot_rebate_history(None, None, None).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+// The client split by concern, so strategies can be written against `impl Trading` and run on
+// `Binance`, `SimulatedBinance` or a mock alike. The futures are `Send`, so a strategy can be
+// spawned on a multi-threaded runtime. Implementations may still use `async fn`. The REST
+// client's futures are not `Send` on wasm, so `Binance` implements the traits natively only.
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::client::Binance;
+use crate::model::{
+    Balance, Order, OrderCanceled, Success, SymbolPrice, Ticker, Transaction, UserDataStream,
+};
+use anyhow::Result;
+use std::future::Future;
+
+pub trait MarketData {
+    fn get_price(&self, symbol: &str) -> impl Future<Output = Result<SymbolPrice>> + Send;
+
+    fn get_book_ticker(&self, symbol: &str) -> impl Future<Output = Result<Ticker>> + Send;
+}
+
+pub trait Trading {
+    fn limit_buy(
+        &self,
+        symbol: &str,
+        qty: f64,
+        price: f64,
+    ) -> impl Future<Output = Result<Transaction>> + Send;
+
+    fn limit_sell(
+        &self,
+        symbol: &str,
+        qty: f64,
+        price: f64,
+    ) -> impl Future<Output = Result<Transaction>> + Send;
+
+    fn market_buy(
+        &self,
+        symbol: &str,
+        qty: f64,
+    ) -> impl Future<Output = Result<Transaction>> + Send;
+
+    fn market_sell(
+        &self,
+        symbol: &str,
+        qty: f64,
+    ) -> impl Future<Output = Result<Transaction>> + Send;
+
+    fn cancel_order(
+        &self,
+        symbol: &str,
+        order_id: u64,
+    ) -> impl Future<Output = Result<OrderCanceled>> + Send;
+
+    fn order_status(
+        &self,
+        symbol: &str,
+        order_id: u64,
+    ) -> impl Future<Output = Result<Order>> + Send;
+
+    fn get_open_orders(&self, symbol: &str) -> impl Future<Output = Result<Vec<Order>>> + Send;
+
+    fn get_balance(&self, asset: &str) -> impl Future<Output = Result<Balance>> + Send;
+}
+
+pub trait UserStream {
+    fn user_stream_start(&self) -> impl Future<Output = Result<UserDataStream>> + Send;
+
+    fn user_stream_keep_alive(
+        &self,
+        listen_key: &str,
+    ) -> impl Future<Output = Result<Success>> + Send;
+
+    fn user_stream_close(&self, listen_key: &str) -> impl Future<Output = Result<Success>> + Send;
+}
+
+// The inherent methods take precedence, `Self::method` does not recurse
+#[cfg(not(target_arch = "wasm32"))]
+impl MarketData for Binance {
+    async fn get_price(&self, symbol: &str) -> Result<SymbolPrice> {
+        Self::get_price(self, symbol).await
+    }
+
+    async fn get_book_ticker(&self, symbol: &str) -> Result<Ticker> {
+        Self::get_book_ticker(self, symbol).await
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl Trading for Binance {
+    async fn limit_buy(&self, symbol: &str, qty: f64, price: f64) -> Result<Transaction> {
+        Self::limit_buy(self, symbol, qty, price).await
+    }
+
+    async fn limit_sell(&self, symbol: &str, qty: f64, price: f64) -> Result<Transaction> {
+        Self::limit_sell(self, symbol, qty, price).await
+    }
+
+    async fn market_buy(&self, symbol: &str, qty: f64) -> Result<Transaction> {
+        Self::market_buy(self, symbol, qty).await
+    }
+
+    async fn market_sell(&self, symbol: &str, qty: f64) -> Result<Transaction> {
+        Self::market_sell(self, symbol, qty).await
+    }
+
+    async fn cancel_order(&self, symbol: &str, order_id: u64) -> Result<OrderCanceled> {
+        Self::cancel_order(self, symbol, order_id).await
+    }
+
+    async fn order_status(&self, symbol: &str, order_id: u64) -> Result<Order> {
+        Self::order_status(self, symbol, order_id).await
+    }
+
+    async fn get_open_orders(&self, symbol: &str) -> Result<Vec<Order>> {
+        Self::get_open_orders(self, symbol).await
+    }
+
+    async fn get_balance(&self, asset: &str) -> Result<Balance> {
+        Self::get_balance(self, asset).await
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl UserStream for Binance {
+    async fn user_stream_start(&self) -> Result<UserDataStream> {
+        Self::user_stream_start(self).await
+    }
+
+    async fn user_stream_keep_alive(&self, listen_key: &str) -> Result<Success> {
+        Self::user_stream_keep_alive(self, listen_key).await
+    }
+
+    async fn user_stream_close(&self, listen_key: &str) -> Result<Success> {
+        Self::user_stream_close(self, listen_key).await
+    }
+}
@@ -0,0 +1,75 @@
+use crate::transport::Version;
+use crate::{
+    client::Binance,
+    model::{
+        request::{VipCollateralAccountRequest, VipLoanOrdersRequest, VipLoanRepayRequest},
+        vip_loan::{VipCollateralAccount, VipLoanOrder, VipLoanRepayment, VipLoanRows},
+    },
+};
+use anyhow::Result;
+
+// VIP Loan endpoints
+impl Binance {
+    // Ongoing VIP loan orders, filtered by the fields set in `request`
+    pub async fn get_vip_loan_ongoing_orders(
+        &self,
+        request: VipLoanOrdersRequest,
+    ) -> Result<VipLoanRows<VipLoanOrder>> {
+        let orders = self
+            .transport
+            .signed_get(Version::SapiV1, "/loan/vip/ongoing/orders", Some(request))
+            .await?;
+        Ok(orders)
+    }
+
+    // Repay `amount` of the VIP loan order `order_id`
+    pub async fn vip_loan_repay(&self, order_id: u64, amount: f64) -> Result<VipLoanRepayment> {
+        let params = VipLoanRepayRequest { order_id, amount };
+        let repayment = self
+            .transport
+            .signed_post(Version::SapiV1, "/loan/vip/repay", Some(params))
+            .await?;
+        Ok(repayment)
+    }
+
+    // Collateral accounts backing VIP loans, optionally for ONE order or account
+    pub async fn get_vip_collateral_accounts<O, A>(
+        &self,
+        order_id: O,
+        collateral_account_id: A,
+    ) -> Result<VipLoanRows<VipCollateralAccount>>
+    where
+        O: Into<Option<u64>>,
+        A: Into<Option<u64>>,
+    {
+        let params = VipCollateralAccountRequest {
+            order_id: order_id.into(),
+            collateral_account_id: collateral_account_id.into(),
+        };
+        let accounts = self
+            .transport
+            .signed_get(
+                Version::SapiV1,
+                "/loan/vip/collateral/account",
+                Some(params),
+            )
+            .await?;
+        Ok(accounts)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::model::request::VipLoanOrdersRequest;
+    use crate::tests::test::setup;
+    use anyhow::Result;
+
+    #[tokio::test]
+    #[ignore = "needs a VIP loan account"]
+    async fn test_get_vip_loan_ongoing_orders() -> Result<()> {
+        let b = setup()?;
+        b.get_vip_loan_ongoing_orders(VipLoanOrdersRequest::default())
+            .await?;
+        Ok(())
+    }
+}
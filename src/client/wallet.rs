@@ -0,0 +1,300 @@
+use crate::error::Error;
+use crate::transport::Version;
+use crate::{
+    client::Binance,
+    model::{
+        request::{
+            CapitalHistoryRequest, DepositAddressRequest, UserAssetRequest, WithdrawRequest,
+        },
+        CoinInfo, DepositAddress, DepositRecord, FundingAsset, TravelRuleWithdrawal, UserAsset,
+        WithdrawQuote, WithdrawRecord, Withdrawal,
+    },
+};
+use anyhow::Result;
+use std::ops::Range;
+
+// Wallet endpoints
+impl Binance {
+    // Deposit address of `coin` on `network`, the coin's default network if `None`. Check
+    // `DepositAddress::memo` before depositing, some networks need a tag besides the address.
+    pub async fn get_deposit_address<'a, N>(&self, coin: &str, network: N) -> Result<DepositAddress>
+    where
+        N: Into<Option<&'a str>>,
+    {
+        let params = deposit_address_params(coin, network.into());
+        let address = self
+            .transport
+            .signed_get(Version::SapiV1, "/capital/deposit/address", Some(params))
+            .await?;
+        Ok(address)
+    }
+
+    // Every deposit address of `coin` on `network`, e.g. after generating additional ones
+    pub async fn get_deposit_addresses<'a, N>(
+        &self,
+        coin: &str,
+        network: N,
+    ) -> Result<Vec<DepositAddress>>
+    where
+        N: Into<Option<&'a str>>,
+    {
+        let params = deposit_address_params(coin, network.into());
+        let addresses = self
+            .transport
+            .signed_get(
+                Version::SapiV1,
+                "/capital/deposit/address/list",
+                Some(params),
+            )
+            .await?;
+        Ok(addresses)
+    }
+
+    // Deposit and withdraw settings of every coin, with the networks each is available on
+    pub async fn get_all_coins_info(&self) -> Result<Vec<CoinInfo>> {
+        let coins = self
+            .transport
+            .signed_get::<_, ()>(Version::SapiV1, "/capital/config/getall", None)
+            .await?;
+        Ok(coins)
+    }
+
+    // Fee and received amount of withdrawing `amount` of `coin` on `network`, the coin's
+    // default network if `None`. Fails with `Error::InvalidWithdrawal` if the network does not
+    // take the amount, so nothing is sent that the exchange would reject.
+    pub async fn withdraw_quote<'a, N>(
+        &self,
+        coin: &str,
+        network: N,
+        amount: f64,
+    ) -> Result<WithdrawQuote>
+    where
+        N: Into<Option<&'a str>>,
+    {
+        let coins = self.get_all_coins_info().await?;
+        let info = coins
+            .iter()
+            .find(|info| info.coin.eq_ignore_ascii_case(coin))
+            .ok_or_else(|| Error::InvalidWithdrawal(format!("unknown coin {coin}")))?;
+        Ok(info.withdraw_quote(network.into(), amount)?)
+    }
+
+    // Withdraw `amount` of `coin` to `address` on the coin's default network, from the spot
+    // wallet. Use `withdraw_with` for other networks, tags and wallets.
+    pub async fn withdraw(&self, coin: &str, address: &str, amount: f64) -> Result<Withdrawal> {
+        self.withdraw_with(WithdrawRequest::new(coin, address, amount))
+            .await
+    }
+
+    pub async fn withdraw_with(&self, request: WithdrawRequest) -> Result<Withdrawal> {
+        let withdrawal = self
+            .transport
+            .signed_post(Version::SapiV1, "/capital/withdraw/apply", Some(request))
+            .await?;
+        Ok(withdrawal)
+    }
+
+    // Withdraw from an account of a region enforcing the travel rule, `request` must carry the
+    // answers to the region's questionnaire
+    pub async fn travel_rule_withdraw(
+        &self,
+        request: WithdrawRequest,
+    ) -> Result<TravelRuleWithdrawal> {
+        if request.questionnaire.is_none() {
+            return Err(Error::InvalidWithdrawal("questionnaire missing".into()).into());
+        }
+        let withdrawal = self
+            .transport
+            .signed_post(
+                Version::SapiV1,
+                "/localentity/withdraw/apply",
+                Some(request),
+            )
+            .await?;
+        Ok(withdrawal)
+    }
+
+    // Deposit history of the last 90 days, newest first, optionally for ONE coin
+    pub async fn get_deposit_history<'a, C, O, L>(
+        &self,
+        coin: C,
+        offset: O,
+        limit: L,
+    ) -> Result<Vec<DepositRecord>>
+    where
+        C: Into<Option<&'a str>>,
+        O: Into<Option<u32>>,
+        L: Into<Option<u16>>,
+    {
+        let params = history_params(coin.into(), None, offset.into(), limit.into());
+        self.deposit_history(params).await
+    }
+
+    // Deposit history within `window` (millisecond timestamps, at most 90 days), newest first,
+    // optionally for ONE coin
+    pub async fn get_deposit_history_between<'a, C, O, L>(
+        &self,
+        coin: C,
+        window: Range<u64>,
+        offset: O,
+        limit: L,
+    ) -> Result<Vec<DepositRecord>>
+    where
+        C: Into<Option<&'a str>>,
+        O: Into<Option<u32>>,
+        L: Into<Option<u16>>,
+    {
+        let params = history_params(coin.into(), Some(window), offset.into(), limit.into());
+        self.deposit_history(params).await
+    }
+
+    // Withdraw history of the last 90 days, newest first, optionally for ONE coin
+    pub async fn get_withdraw_history<'a, C, O, L>(
+        &self,
+        coin: C,
+        offset: O,
+        limit: L,
+    ) -> Result<Vec<WithdrawRecord>>
+    where
+        C: Into<Option<&'a str>>,
+        O: Into<Option<u32>>,
+        L: Into<Option<u16>>,
+    {
+        let params = history_params(coin.into(), None, offset.into(), limit.into());
+        self.withdraw_history(params).await
+    }
+
+    // Withdraw history within `window` (millisecond timestamps, at most 90 days), newest first,
+    // optionally for ONE coin
+    pub async fn get_withdraw_history_between<'a, C, O, L>(
+        &self,
+        coin: C,
+        window: Range<u64>,
+        offset: O,
+        limit: L,
+    ) -> Result<Vec<WithdrawRecord>>
+    where
+        C: Into<Option<&'a str>>,
+        O: Into<Option<u32>>,
+        L: Into<Option<u16>>,
+    {
+        let params = history_params(coin.into(), Some(window), offset.into(), limit.into());
+        self.withdraw_history(params).await
+    }
+
+    async fn deposit_history(&self, params: CapitalHistoryRequest) -> Result<Vec<DepositRecord>> {
+        let deposits = self
+            .transport
+            .signed_get(Version::SapiV1, "/capital/deposit/hisrec", Some(params))
+            .await?;
+        Ok(deposits)
+    }
+
+    async fn withdraw_history(&self, params: CapitalHistoryRequest) -> Result<Vec<WithdrawRecord>> {
+        let withdrawals = self
+            .transport
+            .signed_get(Version::SapiV1, "/capital/withdraw/history", Some(params))
+            .await?;
+        Ok(withdrawals)
+    }
+
+    // Funding wallet balances, which `get_account` does not include
+    pub async fn get_funding_assets<'a, A, V>(
+        &self,
+        asset: A,
+        need_btc_valuation: V,
+    ) -> Result<Vec<FundingAsset>>
+    where
+        A: Into<Option<&'a str>>,
+        V: Into<Option<bool>>,
+    {
+        let params = user_asset_params(asset.into(), need_btc_valuation.into());
+        let assets = self
+            .transport
+            .signed_post(Version::SapiV1, "/asset/get-funding-asset", Some(params))
+            .await?;
+        Ok(assets)
+    }
+
+    // Spot wallet assets with a positive balance, optionally for ONE asset
+    pub async fn get_user_assets<'a, A, V>(
+        &self,
+        asset: A,
+        need_btc_valuation: V,
+    ) -> Result<Vec<UserAsset>>
+    where
+        A: Into<Option<&'a str>>,
+        V: Into<Option<bool>>,
+    {
+        let params = user_asset_params(asset.into(), need_btc_valuation.into());
+        let assets = self
+            .transport
+            .signed_post(Version::SapiV3, "/asset/getUserAsset", Some(params))
+            .await?;
+        Ok(assets)
+    }
+}
+
+fn deposit_address_params(coin: &str, network: Option<&str>) -> DepositAddressRequest {
+    DepositAddressRequest {
+        coin: coin.into(),
+        network: network.map(String::from),
+    }
+}
+
+fn user_asset_params(asset: Option<&str>, need_btc_valuation: Option<bool>) -> UserAssetRequest {
+    UserAssetRequest {
+        asset: asset.map(String::from),
+        need_btc_valuation,
+    }
+}
+
+// The exchange takes inclusive bounds
+fn history_params(
+    coin: Option<&str>,
+    window: Option<Range<u64>>,
+    offset: Option<u32>,
+    limit: Option<u16>,
+) -> CapitalHistoryRequest {
+    CapitalHistoryRequest {
+        coin: coin.map(String::from),
+        start_time: window.as_ref().map(|window| window.start),
+        end_time: window.map(|window| window.end.saturating_sub(1)),
+        offset,
+        limit,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::tests::test::setup;
+    use anyhow::Result;
+
+    #[tokio::test]
+    async fn test_get_deposit_address() -> Result<()> {
+        let b = setup()?;
+        b.get_deposit_address("usdt", "trx").await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_deposit_history() -> Result<()> {
+        let b = setup()?;
+        b.get_deposit_history(None, None, None).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_withdraw_history() -> Result<()> {
+        let b = setup()?;
+        b.get_withdraw_history("usdt", None, 10).await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_funding_assets() -> Result<()> {
+        let b = setup()?;
+        b.get_funding_assets(None, true).await?;
+        Ok(())
+    }
+}
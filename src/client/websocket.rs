@@ -1,130 +1,868 @@
 use crate::{
     error::Error,
-    model::websocket::{AccountUpdate, BinanceWebsocketMessage, Subscription, UserOrderUpdate},
+    model::websocket::{
+        parse_stream_text, BinanceWebsocketMessage, Subscription, SymbolAllowlist, TimeUnit,
+    },
+    proxy::Proxy,
+};
+#[cfg(feature = "recorder")]
+use crate::{
+    recorder::{RecordedFrame, Recorder},
+    signing::timestamp,
 };
 use anyhow::{anyhow, Result};
-use futures::{prelude::*, stream::SplitStream};
-use serde::{Deserialize, Serialize};
-use serde_json::from_str;
+use futures::{future::BoxFuture, prelude::*, stream::SplitStream};
+use serde::Deserialize;
+use serde_json::{from_str, value::RawValue, Value};
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
+    fmt,
     pin::Pin,
+    sync::Arc,
     task::{Context, Poll},
+    time::Duration,
 };
 use streamunordered::{StreamUnordered, StreamYield};
-use tokio::net::TcpStream;
-use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tokio::{
+    net::TcpStream,
+    sync::broadcast,
+    time::{sleep_until, Instant, Sleep},
+};
+use tokio_tungstenite::{
+    client_async_tls, connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream,
+};
+use url::Url;
 
-const WS_URL: &str = "wss://stream.binance.com:9443/ws";
+const WS_HOST: &str = "stream.binance.com";
+// Serves public market streams only, no user data
+const DATA_STREAM_HOST: &str = "data-stream.binance.vision";
+// Streams the exchange allows on one connection
+pub const MAX_STREAMS_PER_CONNECTION: usize = 1024;
 
-#[allow(dead_code)]
 type WSStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
 pub type StoredStream = SplitStream<WSStream>;
 
+type Frame = Result<Message, tokio_tungstenite::tungstenite::Error>;
+
+// Host and port of the spot streams, the default `stream.binance.com:9443`. Port 443 helps on
+// networks blocking 9443, the market data only host tends to have better availability.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WsConfig {
+    pub host: String,
+    pub port: u16,
+}
+
+impl Default for WsConfig {
+    fn default() -> Self {
+        Self::new(WS_HOST, 9443)
+    }
+}
+
+impl WsConfig {
+    #[must_use]
+    pub fn new(host: &str, port: u16) -> Self {
+        Self {
+            host: host.into(),
+            port,
+        }
+    }
+
+    // `stream.binance.com:443`
+    #[must_use]
+    pub fn port_443() -> Self {
+        Self::new(WS_HOST, 443)
+    }
+
+    // `stream.binance.us:9443`, the streams of Binance.US
+    #[must_use]
+    pub fn binance_us() -> Self {
+        Self::new("stream.binance.us", 9443)
+    }
+
+    // `data-stream.binance.vision:443`, market streams only
+    #[must_use]
+    pub fn data_stream() -> Self {
+        Self::new(DATA_STREAM_HOST, 443)
+    }
+
+    fn base_url(&self) -> String {
+        format!("wss://{}:{}/ws", self.host, self.port)
+    }
+}
+
+type Callback = Arc<dyn Fn(Subscription) -> BoxFuture<'static, ()> + Send + Sync>;
+type ErrorCallback = Arc<dyn Fn(Subscription, String) -> BoxFuture<'static, ()> + Send + Sync>;
+
+// Callbacks run on their own tokio task, the stream does not wait for them
+#[derive(Default, Clone)]
+struct Callbacks {
+    disconnect: Option<Callback>,
+    reconnect: Option<Callback>,
+    parse_error: Option<ErrorCallback>,
+}
+
+impl fmt::Debug for Callbacks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Callbacks")
+            .field("disconnect", &self.disconnect.is_some())
+            .field("reconnect", &self.reconnect.is_some())
+            .field("parse_error", &self.parse_error.is_some())
+            .finish()
+    }
+}
+
+// Subscriptions sharing one connection by stream name, several on a combined stream
+#[derive(Debug, Default)]
+struct Connection {
+    streams: HashMap<String, Subscription>,
+    // Frames come wrapped as `{"stream": .., "data": ..}`
+    combined: bool,
+}
+
+impl Connection {
+    // Any of the subscriptions, for frames not tied to one stream like pings and closes
+    fn any(&self) -> Option<&Subscription> {
+        self.streams.values().next()
+    }
+}
+
+#[derive(Deserialize)]
+struct CombinedFrame<'a> {
+    #[serde(borrow)]
+    stream: &'a str,
+    #[serde(borrow)]
+    data: &'a RawValue,
+}
+
+// What the stream does with a text frame that fails to parse, e.g. an event type added by the
+// exchange after this release
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ParseErrorPolicy {
+    // Yield the error
+    #[default]
+    FailFast,
+    // Log the error and continue with the next frame
+    SkipAndLog,
+    // Yield `BinanceWebsocketMessage::Unknown` with the frame as JSON
+    EmitUnknown,
+}
+
 #[allow(clippy::module_name_repetitions)]
 #[derive(Default)]
 pub struct BinanceWebsocket {
     subscriptions: HashMap<Subscription, usize>,
-    tokens: HashMap<usize, Subscription>,
+    tokens: HashMap<usize, Connection>,
+    max_streams: Option<usize>,
+    allowlist: Option<SymbolAllowlist>,
+    parse_error_policy: ParseErrorPolicy,
     streams: StreamUnordered<StoredStream>,
+    time_unit: TimeUnit,
+    stale_timeout: Option<Duration>,
+    stale_timeouts: HashMap<Subscription, Option<Duration>>,
+    last_activity: HashMap<usize, Instant>,
+    watchdog: Option<Pin<Box<Sleep>>>,
+    proxy: Option<Proxy>,
+    config: WsConfig,
+    callbacks: Callbacks,
+    // Connections `on_disconnect` was already called for
+    disconnected: HashSet<usize>,
+    #[cfg(feature = "recorder")]
+    recorder: Option<Recorder>,
 }
 
 impl BinanceWebsocket {
+    // Streams subscribed afterwards carry timestamps in `time_unit`
+    #[must_use]
+    pub const fn with_time_unit(mut self, time_unit: TimeUnit) -> Self {
+        self.time_unit = time_unit;
+        self
+    }
+
+    #[must_use]
+    pub const fn time_unit(&self) -> TimeUnit {
+        self.time_unit
+    }
+
+    // Connect spot streams subscribed afterwards to `config`, futures and leveraged token streams
+    // keep their own hosts
+    #[must_use]
+    pub fn with_config(mut self, config: WsConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    // Write every text frame of every subscription to `recorder` as it arrives, for replay with
+    // `ReplayWebsocket`
+    #[cfg(feature = "recorder")]
+    #[must_use]
+    pub fn with_recorder(mut self, recorder: Recorder) -> Self {
+        self.recorder = Some(recorder);
+        self
+    }
+
+    // Tunnel streams subscribed afterwards through `proxy`
+    #[must_use]
+    pub fn with_proxy(mut self, proxy: Proxy) -> Self {
+        self.proxy = Some(proxy);
+        self
+    }
+
+    // Yield only the entries of `allowlist` symbols from the all-market ticker arrays, i.e.
+    // `TickerAll`, `MiniTickerAll` and `RollingWindowTickerAll`. The other entries are skipped
+    // without being deserialized, an update without any allowed symbol yields an empty array.
+    #[must_use]
+    pub fn with_symbol_allowlist(mut self, allowlist: SymbolAllowlist) -> Self {
+        self.allowlist = Some(allowlist);
+        self
+    }
+
+    // Put at most `max_streams` subscriptions on one combined connection, capped at
+    // `MAX_STREAMS_PER_CONNECTION`. Fewer streams per connection keep URLs short and limit
+    // what one dropped connection takes down.
+    #[must_use]
+    pub fn with_max_streams_per_connection(mut self, max_streams: usize) -> Self {
+        self.max_streams = Some(max_streams.clamp(1, MAX_STREAMS_PER_CONNECTION));
+        self
+    }
+
+    // Emit `BinanceWebsocketMessage::StaleStream` for a subscription that stayed silent for
+    // `timeout`. Pings count as activity, so quiet streams are only reported once the
+    // connection itself went dead. User data streams are not watched unless set explicitly.
+    #[must_use]
+    pub const fn with_stale_timeout(mut self, timeout: Duration) -> Self {
+        self.stale_timeout = Some(timeout);
+        self
+    }
+
+    // Called once the connection of a subscription failed or was closed by the exchange, e.g. to
+    // `resubscribe` and resynchronize state
+    #[must_use]
+    pub fn on_disconnect<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(Subscription) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.callbacks.disconnect = Some(Arc::new(move |sub| f(sub).boxed()));
+        self
+    }
+
+    // Called after `resubscribe` opened a fresh connection, e.g. to refetch open orders that
+    // changed in between
+    #[must_use]
+    pub fn on_reconnect<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(Subscription) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.callbacks.reconnect = Some(Arc::new(move |sub| f(sub).boxed()));
+        self
+    }
+
+    // Called with the error of a message that could not be parsed, the error is still yielded
+    // by the stream
+    #[must_use]
+    pub fn on_parse_error<F, Fut>(mut self, f: F) -> Self
+    where
+        F: Fn(Subscription, String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = ()> + Send + 'static,
+    {
+        self.callbacks.parse_error = Some(Arc::new(move |sub, e| f(sub, e).boxed()));
+        self
+    }
+
+    // Keep the stream going through frames it cannot parse, `ParseErrorPolicy::FailFast` by
+    // default. `on_parse_error` is called either way.
+    #[must_use]
+    pub const fn with_parse_error_policy(mut self, policy: ParseErrorPolicy) -> Self {
+        self.parse_error_policy = policy;
+        self
+    }
+
+    // Override the stale timeout of ONE subscription, `None` disables the watchdog for it
+    pub fn set_stale_timeout(&mut self, subscription: &Subscription, timeout: Option<Duration>) {
+        self.stale_timeouts.insert(subscription.clone(), timeout);
+        self.watchdog = None;
+    }
+
+    // Drop the connection of `subscription` and open a fresh one, e.g. after it went stale
+    pub async fn resubscribe(&mut self, subscription: &Subscription) -> Result<()> {
+        self.unsubscribe(subscription);
+        self.subscribe(subscription).await?;
+        if let Some(on_reconnect) = &self.callbacks.reconnect {
+            tokio::spawn(on_reconnect(subscription.clone()));
+        }
+        Ok(())
+    }
+
     pub async fn subscribe(&mut self, subscription: &Subscription) -> Result<()> {
-        let sub = match subscription {
-            Subscription::AggregateTrade(ref symbol) => format!("{}@aggTrade", symbol),
-            Subscription::Candlestick(ref symbol, ref interval) => {
-                format!("{}@kline_{}", symbol, interval)
+        let endpoint = self.endpoint(subscription)?;
+        let stream = connect(self.proxy.as_ref(), endpoint).await?;
+        let token = self.streams.insert(stream.split().1);
+        self.track(token, vec![subscription.clone()], false);
+        Ok(())
+    }
+
+    // Subscribe to many streams over combined stream connections. The streams are spread over
+    // as many connections as the per connection limit requires, streams of different hosts never
+    // share one. Subscriptions already open are skipped. The streams are chosen by URL, so no
+    // message counts against the limit of control messages per second.
+    pub async fn subscribe_combined(&mut self, subscriptions: &[Subscription]) -> Result<()> {
+        for (base, chunk) in self.plan_connections(subscriptions)? {
+            let endpoint = self.combined_endpoint(&base, &chunk);
+            let stream = connect(self.proxy.as_ref(), endpoint).await?;
+            let token = self.streams.insert(stream.split().1);
+            self.track(token, chunk, true);
+        }
+        Ok(())
+    }
+
+    // Closes the connection of `subscription`. Other subscriptions on the same combined
+    // connection keep it open, only the frames of `subscription` are dropped from then on and
+    // `None` is returned.
+    pub fn unsubscribe(&mut self, subscription: &Subscription) -> Option<StoredStream> {
+        let token = self.subscriptions.remove(subscription)?;
+        let connection = self.tokens.get_mut(&token)?;
+        connection.streams.remove(&subscription.to_string());
+        if !connection.streams.is_empty() {
+            return None;
+        }
+        self.tokens.remove(&token);
+        self.last_activity.remove(&token);
+        self.disconnected.remove(&token);
+        StreamUnordered::take(Pin::new(&mut self.streams), token)
+    }
+
+    // Subscriptions by connection, for debugging how streams were spread
+    #[must_use]
+    pub fn connections(&self) -> HashMap<usize, Vec<Subscription>> {
+        self.tokens
+            .iter()
+            .map(|(token, connection)| (*token, connection.streams.values().cloned().collect()))
+            .collect()
+    }
+
+    // Connection `subscription` is received on
+    #[must_use]
+    pub fn connection_of(&self, subscription: &Subscription) -> Option<usize> {
+        self.subscriptions.get(subscription).copied()
+    }
+
+    fn track(&mut self, token: usize, subscriptions: Vec<Subscription>, combined: bool) {
+        let mut connection = Connection {
+            combined,
+            ..Connection::default()
+        };
+        for sub in subscriptions {
+            self.subscriptions.insert(sub.clone(), token);
+            connection.streams.insert(sub.to_string(), sub);
+        }
+        self.tokens.insert(token, connection);
+        self.last_activity.insert(token, Instant::now());
+        self.watchdog = None;
+    }
+
+    // Base URL and streams of each combined connection to open for `subscriptions`
+    fn plan_connections(
+        &self,
+        subscriptions: &[Subscription],
+    ) -> Result<Vec<(String, Vec<Subscription>)>> {
+        let max_streams = self.max_streams.unwrap_or(MAX_STREAMS_PER_CONNECTION);
+        let mut seen = HashSet::new();
+        let mut by_base: Vec<(String, Vec<Subscription>)> = Vec::new();
+        for sub in subscriptions {
+            if self.subscriptions.contains_key(sub) || !seen.insert(sub) {
+                continue;
+            }
+            let base = self.base_of(sub)?;
+            match by_base.iter_mut().find(|(b, _)| *b == base) {
+                Some((_, subs)) => subs.push(sub.clone()),
+                None => by_base.push((base, vec![sub.clone()])),
+            }
+        }
+        Ok(by_base
+            .into_iter()
+            .flat_map(|(base, subs)| {
+                subs.chunks(max_streams)
+                    .map(|chunk| (base.clone(), chunk.to_vec()))
+                    .collect::<Vec<_>>()
+            })
+            .collect())
+    }
+
+    fn combined_endpoint(&self, base: &str, subscriptions: &[Subscription]) -> String {
+        let streams = subscriptions
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("/");
+        let base = base.strip_suffix("/ws").unwrap_or(base);
+        let mut endpoint = format!("{base}/stream?streams={streams}");
+        if let Some(param) = self.time_unit.query_param() {
+            endpoint = format!("{endpoint}&{param}");
+        }
+        endpoint
+    }
+
+    fn endpoint(&self, subscription: &Subscription) -> Result<String> {
+        let base = self.base_of(subscription)?;
+        let mut endpoint = format!("{base}/{subscription}");
+        if let Some(param) = self.time_unit.query_param() {
+            endpoint = format!("{endpoint}?{param}");
+        }
+        Ok(endpoint)
+    }
+
+    fn base_of(&self, subscription: &Subscription) -> Result<String> {
+        if let Some(base) = subscription.dedicated_base() {
+            return Ok(base.into());
+        }
+        if matches!(subscription, Subscription::UserData(..))
+            && self.config.host == DATA_STREAM_HOST
+        {
+            return Err(Error::UnknownStream(format!(
+                "{DATA_STREAM_HOST} serves no user data streams"
+            ))
+            .into());
+        }
+        Ok(self.config.base_url())
+    }
+
+    // Hand the messages of all subscriptions to any number of receivers, from a task that runs
+    // until every subscription is gone. Get a receiver with `Sender::subscribe`, each sees the
+    // messages sent after it subscribed. A receiver more than `capacity` messages behind skips
+    // the oldest, see `broadcast::error::RecvError::Lagged`. Errors are only logged, the task
+    // resubscribes subscriptions whose connection dropped or went stale by itself. It ends once
+    // a resubscribe failed for the last one, the channel closes with the returned sender.
+    #[must_use]
+    pub fn broadcast(self, capacity: usize) -> broadcast::Sender<BinanceWebsocketMessage> {
+        let (sender, _) = broadcast::channel(capacity);
+        tokio::spawn(fan_out(self, sender.clone()));
+        sender
+    }
+
+    fn notify_disconnect(&mut self, token: usize) {
+        if !self.disconnected.insert(token) {
+            return;
+        }
+        if let (Some(on_disconnect), Some(connection)) =
+            (&self.callbacks.disconnect, self.tokens.get(&token))
+        {
+            for sub in connection.streams.values() {
+                tokio::spawn(on_disconnect(sub.clone()));
+            }
+        }
+    }
+
+    // Subscription a frame belongs to, with frames of combined connections unwrapped. `None`
+    // for frames of a stream unsubscribed from a combined connection.
+    fn route(&self, token: usize, frame: Frame) -> Option<(Subscription, Frame)> {
+        let connection = self.tokens.get(&token)?;
+        match frame {
+            Ok(Message::Text(text)) if connection.combined => match from_str(&text) {
+                Ok(CombinedFrame { stream, data }) => {
+                    let sub = connection.streams.get(stream)?.clone();
+                    Some((sub, Ok(Message::Text(data.get().to_string()))))
+                }
+                // Fails in `parse_message` like any malformed frame
+                Err(_) => Some((connection.any()?.clone(), Ok(Message::Text(text)))),
+            },
+            frame => Some((connection.any()?.clone(), frame)),
+        }
+    }
+
+    fn parse(&self, sub: &Subscription, text: &str) -> Result<BinanceWebsocketMessage> {
+        let Some(allowlist) = &self.allowlist else {
+            return parse_stream_text(sub, text);
+        };
+        let message = match sub {
+            Subscription::TickerAll => BinanceWebsocketMessage::TickerAll(allowlist.select(text)?),
+            Subscription::MiniTickerAll => {
+                BinanceWebsocketMessage::MiniTickerAll(allowlist.select(text)?)
+            }
+            Subscription::RollingWindowTickerAll(..) => {
+                BinanceWebsocketMessage::RollingWindowTickerAll(allowlist.select(text)?)
             }
-            Subscription::Depth(ref symbol) => format!("{}@depth", symbol),
-            Subscription::MiniTicker(ref symbol) => format!("{}@miniTicker", symbol),
-            Subscription::MiniTickerAll => "!miniTicker@arr".to_string(),
-            Subscription::OrderBook(ref symbol, depth) => format!("{}@depth{}", symbol, depth),
-            Subscription::Ticker(ref symbol) => format!("{}@ticker", symbol),
-            Subscription::TickerAll => "!ticker@arr".to_string(),
-            Subscription::Trade(ref symbol) => format!("{}@trade", symbol),
-            Subscription::UserData(ref key) => key.clone(),
+            _ => return parse_stream_text(sub, text),
         };
+        Ok(message)
+    }
 
-        let endpoint = format!("{}/{}", WS_URL, sub);
+    // `None` if the frame is to be skipped
+    fn handle_parse_error(
+        &self,
+        sub: &Subscription,
+        text: &str,
+        e: anyhow::Error,
+    ) -> Option<Result<BinanceWebsocketMessage>> {
+        self.notify_parse_error(sub, &e);
+        match self.parse_error_policy {
+            ParseErrorPolicy::FailFast => Some(Err(e)),
+            ParseErrorPolicy::SkipAndLog => {
+                log::warn!("Skipped a frame of {sub}: {e}");
+                None
+            }
+            ParseErrorPolicy::EmitUnknown => {
+                let payload = from_str(text).unwrap_or_else(|_| Value::String(text.into()));
+                Some(Ok(BinanceWebsocketMessage::Unknown(payload)))
+            }
+        }
+    }
 
-        let token = self
-            .streams
-            .insert(connect_async(endpoint).await?.0.split().1);
+    fn notify_parse_error(&self, sub: &Subscription, e: &anyhow::Error) {
+        if let Some(on_parse_error) = &self.callbacks.parse_error {
+            tokio::spawn(on_parse_error(sub.clone(), e.to_string()));
+        }
+    }
 
-        self.subscriptions.insert(subscription.clone(), token);
-        self.tokens.insert(token, subscription.clone());
-        Ok(())
+    #[cfg(feature = "recorder")]
+    fn record(&mut self, sub: &Subscription, text: &str) {
+        let Some(recorder) = self.recorder.as_mut() else {
+            return;
+        };
+        let frame = RecordedFrame {
+            received: timestamp(),
+            stream: sub.to_string(),
+            frame: text.into(),
+        };
+        if let Err(e) = recorder.record(&frame) {
+            log::warn!("Failed to record frame of {sub}: {e}");
+        }
     }
 
-    pub fn unsubscribe(&mut self, subscription: &Subscription) -> Option<StoredStream> {
-        let streams = Pin::new(&mut self.streams);
-        self.subscriptions
-            .get(subscription)
-            .and_then(|token| StreamUnordered::take(streams, *token))
+    fn stale_timeout_of(&self, subscription: &Subscription) -> Option<Duration> {
+        match self.stale_timeouts.get(subscription) {
+            Some(timeout) => *timeout,
+            None if matches!(subscription, Subscription::UserData(..)) => None,
+            None => self.stale_timeout,
+        }
+    }
+
+    // Reports the first stale subscription, or arms the watchdog for the next deadline
+    fn poll_stale(&mut self, cx: &mut Context<'_>) -> Option<Subscription> {
+        let now = Instant::now();
+        let mut next_deadline: Option<Instant> = None;
+
+        for (token, connection) in &self.tokens {
+            // A connection is as stale as its most sensitive subscription
+            let watched = connection
+                .streams
+                .values()
+                .filter_map(|sub| Some((self.stale_timeout_of(sub)?, sub)))
+                .min_by_key(|(timeout, _)| *timeout);
+            let (Some((timeout, sub)), Some(last)) = (watched, self.last_activity.get(token))
+            else {
+                continue;
+            };
+            let deadline = *last + timeout;
+            if deadline <= now {
+                let sub = sub.clone();
+                self.last_activity.insert(*token, now);
+                return Some(sub);
+            }
+            next_deadline = Some(next_deadline.map_or(deadline, |d| d.min(deadline)));
+        }
+
+        self.watchdog = next_deadline.map(|deadline| Box::pin(sleep_until(deadline)));
+        if let Some(watchdog) = self.watchdog.as_mut() {
+            // Registers the waker, the deadline lies in the future
+            let _ = watchdog.as_mut().poll(cx);
+        }
+        None
     }
 }
 
 impl Stream for BinanceWebsocket {
     type Item = Result<BinanceWebsocketMessage>;
 
-    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
-        match Pin::new(&mut self.as_mut().get_mut().streams).poll_next(cx) {
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        match Pin::new(&mut this.streams).poll_next(cx) {
             Poll::Ready(Some((y, token))) => match y {
                 StreamYield::Item(item) => {
-                    let sub = self.tokens.get(&token).unwrap();
-                    Poll::Ready({
-                        Some(
+                    this.last_activity.insert(token, Instant::now());
+                    let Some((sub, item)) = this.route(token, item) else {
+                        cx.waker().wake_by_ref();
+                        return Poll::Pending;
+                    };
+                    let message = match item {
+                        Ok(Message::Close(..)) | Err(_) => {
+                            this.notify_disconnect(token);
                             item.map_err(|e| anyhow!("error: {:?}", e))
-                                .and_then(|m| parse_message(sub, m)),
-                        )
-                    })
+                                .and_then(|m| parse_message(&sub, m))
+                        }
+                        Ok(Message::Text(text)) => {
+                            #[cfg(feature = "recorder")]
+                            this.record(&sub, &text);
+                            match this.parse(&sub, &text) {
+                                Ok(message) => Ok(message),
+                                Err(e) => {
+                                    let Some(message) = this.handle_parse_error(&sub, &text, e)
+                                    else {
+                                        cx.waker().wake_by_ref();
+                                        return Poll::Pending;
+                                    };
+                                    message
+                                }
+                            }
+                        }
+                        Ok(m) => {
+                            parse_message(&sub, m).inspect_err(|e| this.notify_parse_error(&sub, e))
+                        }
+                    };
+                    Poll::Ready(Some(message))
+                }
+                StreamYield::Finished(_) => {
+                    this.last_activity.remove(&token);
+                    if this.disconnected.contains(&token) || !this.tokens.contains_key(&token) {
+                        // Reported with its close frame or error already
+                        cx.waker().wake_by_ref();
+                        return Poll::Pending;
+                    }
+                    this.notify_disconnect(token);
+                    Poll::Ready(Some(Err(anyhow!("Socket closed without a close frame"))))
                 }
-                StreamYield::Finished(_) => Poll::Pending,
             },
             Poll::Ready(None) => Poll::Ready(Some(Err(Error::NoStreamSubscribed.into()))),
-            Poll::Pending => Poll::Pending,
+            Poll::Pending => this.poll_stale(cx).map_or(Poll::Pending, |sub| {
+                Poll::Ready(Some(Ok(BinanceWebsocketMessage::StaleStream(sub))))
+            }),
         }
     }
 }
 
-fn parse_message(sub: &Subscription, msg: Message) -> Result<BinanceWebsocketMessage> {
-    let msg = match msg {
-        Message::Text(msg) => msg,
-        Message::Binary(b) => return Ok(BinanceWebsocketMessage::Binary(b)),
-        Message::Pong(..) => return Ok(BinanceWebsocketMessage::Pong),
-        Message::Ping(..) => return Ok(BinanceWebsocketMessage::Ping),
-        Message::Close(..) => return Err(anyhow!("Socket closed")),
-        Message::Frame(msg) => return Err(anyhow!("Unexpected frame: {:?}", msg)),
+async fn connect(proxy: Option<&Proxy>, endpoint: String) -> Result<WSStream> {
+    let stream = match proxy {
+        Some(proxy) => {
+            let url = Url::parse(&endpoint)?;
+            let host = url.host_str().unwrap_or_default();
+            let port = url.port_or_known_default().unwrap_or(443);
+            let tunnel = proxy.connect(host, port).await?;
+            client_async_tls(endpoint, tunnel).await?.0
+        }
+        None => connect_async(endpoint).await?.0,
     };
+    Ok(stream)
+}
 
-    let message = match sub {
-        Subscription::AggregateTrade(..) => {
-            BinanceWebsocketMessage::AggregateTrade(from_str(&msg)?)
-        }
-        Subscription::Candlestick(..) => BinanceWebsocketMessage::Candlestick(from_str(&msg)?),
-        Subscription::Depth(..) => BinanceWebsocketMessage::Depth(from_str(&msg)?),
-        Subscription::MiniTicker(..) => BinanceWebsocketMessage::MiniTicker(from_str(&msg)?),
-        Subscription::MiniTickerAll => BinanceWebsocketMessage::MiniTickerAll(from_str(&msg)?),
-        Subscription::OrderBook(..) => BinanceWebsocketMessage::OrderBook(from_str(&msg)?),
-        Subscription::Ticker(..) => BinanceWebsocketMessage::Ticker(from_str(&msg)?),
-        Subscription::TickerAll => BinanceWebsocketMessage::TickerAll(from_str(&msg)?),
-        Subscription::Trade(..) => BinanceWebsocketMessage::Trade(from_str(&msg)?),
-        Subscription::UserData(..) => {
-            let msg: Either<AccountUpdate, UserOrderUpdate> = from_str(&msg)?;
-            match msg {
-                Either::Left(m) => BinanceWebsocketMessage::UserAccountUpdate(m),
-                Either::Right(m) => BinanceWebsocketMessage::UserOrderUpdate(m),
+// What `fan_out` needs to bring back subscriptions whose connection dropped or went stale
+trait Reconnect: Stream<Item = Result<BinanceWebsocketMessage>> + Unpin {
+    // Subscriptions of the connections that failed or were closed by the exchange
+    fn dropped(&self) -> Vec<Subscription>;
+
+    fn reconnect<'a>(&'a mut self, subscription: &'a Subscription) -> BoxFuture<'a, Result<()>>;
+}
+
+impl Reconnect for BinanceWebsocket {
+    fn dropped(&self) -> Vec<Subscription> {
+        self.disconnected
+            .iter()
+            .filter_map(|token| self.tokens.get(token))
+            .flat_map(|connection| connection.streams.values().cloned())
+            .collect()
+    }
+
+    fn reconnect<'a>(&'a mut self, subscription: &'a Subscription) -> BoxFuture<'a, Result<()>> {
+        self.resubscribe(subscription).boxed()
+    }
+}
+
+async fn fan_out<S: Reconnect>(mut stream: S, sender: broadcast::Sender<BinanceWebsocketMessage>) {
+    while let Some(message) = stream.next().await {
+        let mut dropped = stream.dropped();
+        match message {
+            // Without receivers the message is dropped, like on a live stream nobody reads
+            Ok(message) => {
+                if let BinanceWebsocketMessage::StaleStream(sub) = &message {
+                    dropped.push(sub.clone());
+                }
+                let _ = sender.send(message);
             }
+            Err(e) if matches!(e.downcast_ref(), Some(Error::NoStreamSubscribed)) => break,
+            Err(e) => log::warn!("broadcast websocket: {e}"),
         }
-    };
-    Ok(message)
+        for sub in dropped {
+            if let Err(e) = stream.reconnect(&sub).await {
+                log::warn!("broadcast websocket: resubscribing {sub}: {e}");
+            }
+        }
+    }
+}
+
+pub fn parse_message(sub: &Subscription, msg: Message) -> Result<BinanceWebsocketMessage> {
+    match msg {
+        Message::Text(msg) => parse_stream_text(sub, &msg),
+        Message::Binary(b) => Ok(BinanceWebsocketMessage::Binary(b)),
+        Message::Pong(..) => Ok(BinanceWebsocketMessage::Pong),
+        Message::Ping(..) => Ok(BinanceWebsocketMessage::Ping),
+        Message::Close(..) => Err(anyhow!("Socket closed")),
+        Message::Frame(msg) => Err(anyhow!("Unexpected frame: {:?}", msg)),
+    }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(untagged)]
-enum Either<L, R> {
-    Left(L),
-    Right(R),
+#[cfg(test)]
+mod test {
+    use super::{fan_out, parse_message, BinanceWebsocket, ParseErrorPolicy, Reconnect, WsConfig};
+    use crate::{
+        model::websocket::{BinanceWebsocketMessage, Subscription},
+        testing::TestStream,
+    };
+    use anyhow::Result;
+    use futures::{future::BoxFuture, prelude::*};
+    use std::{
+        pin::Pin,
+        sync::{Arc, Mutex},
+        task::{Context, Poll},
+    };
+    use tokio::sync::broadcast::{self, error::RecvError};
+    use tokio_tungstenite::tungstenite::Message;
+
+    // Records what `fan_out` resubscribes
+    struct Reconnecting(TestStream, Arc<Mutex<Vec<Subscription>>>);
+
+    impl Stream for Reconnecting {
+        type Item = Result<BinanceWebsocketMessage>;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            self.0.poll_next_unpin(cx)
+        }
+    }
+
+    impl Reconnect for Reconnecting {
+        fn dropped(&self) -> Vec<Subscription> {
+            Vec::new()
+        }
+
+        fn reconnect<'a>(
+            &'a mut self,
+            subscription: &'a Subscription,
+        ) -> BoxFuture<'a, Result<()>> {
+            self.1.lock().unwrap().push(subscription.clone());
+            future::ok(()).boxed()
+        }
+    }
+
+    #[test]
+    fn endpoints() {
+        let ws = BinanceWebsocket::default();
+        let trades = Subscription::Trade("btcusdt".into());
+        assert_eq!(
+            ws.endpoint(&trades).unwrap(),
+            "wss://stream.binance.com:9443/ws/btcusdt@trade"
+        );
+
+        let ws = BinanceWebsocket::default().with_config(WsConfig::data_stream());
+        assert_eq!(
+            ws.endpoint(&trades).unwrap(),
+            "wss://data-stream.binance.vision:443/ws/btcusdt@trade"
+        );
+        assert!(ws.endpoint(&Subscription::UserData("key".into())).is_err());
+        assert_eq!(
+            ws.endpoint(&Subscription::MarkPriceAll).unwrap(),
+            "wss://fstream.binance.com/ws/!markPrice@arr"
+        );
+    }
+
+    #[test]
+    fn combined_connections() {
+        let mut ws = BinanceWebsocket::default().with_max_streams_per_connection(2);
+        let trades = Subscription::Trade("btcusdt".into());
+        let subs = [
+            trades.clone(),
+            Subscription::Trade("ethusdt".into()),
+            Subscription::MarkPriceAll,
+            Subscription::Trade("bnbusdt".into()),
+            trades.clone(),
+        ];
+        let plan = ws.plan_connections(&subs).unwrap();
+        let sizes: Vec<_> = plan.iter().map(|(_, chunk)| chunk.len()).collect();
+        assert_eq!(sizes, [2, 1, 1]);
+        assert_eq!(
+            ws.combined_endpoint(&plan[0].0, &plan[0].1),
+            "wss://stream.binance.com:9443/stream?streams=btcusdt@trade/ethusdt@trade"
+        );
+        assert_eq!(
+            ws.combined_endpoint(&plan[2].0, &plan[2].1),
+            "wss://fstream.binance.com/stream?streams=!markPrice@arr"
+        );
+
+        let (_, chunk) = plan.into_iter().next().unwrap();
+        ws.track(7, chunk, true);
+        assert_eq!(ws.connection_of(&trades), Some(7));
+        assert_eq!(ws.connections()[&7].len(), 2);
+        assert_eq!(ws.plan_connections(&subs).unwrap().len(), 2);
+
+        let frame = r#"{"stream":"btcusdt@trade","data":{"e":"trade","E":1718100000123,"s":"BTCUSDT","t":3634212513,"p":"67321.01000000","q":"0.00200000","T":1718100000122,"m":false,"M":true}}"#;
+        let (sub, message) = ws.route(7, Ok(Message::Text(frame.into()))).unwrap();
+        assert_eq!(sub, trades);
+        assert!(matches!(
+            parse_message(&sub, message.unwrap()).unwrap(),
+            BinanceWebsocketMessage::Trade(..)
+        ));
+
+        // The other stream keeps the connection, frames of the dropped one are skipped
+        assert!(ws.unsubscribe(&trades).is_none());
+        assert!(ws.route(7, Ok(Message::Text(frame.into()))).is_none());
+        assert_eq!(ws.connections()[&7].len(), 1);
+    }
+
+    #[test]
+    fn parse_error_policy() {
+        let trades = Subscription::Trade("btcusdt".into());
+        let frame = r#"{"e":"trade2","E":1718100000123}"#;
+        let ws = BinanceWebsocket::default();
+        let error = ws.parse(&trades, frame).unwrap_err();
+        assert!(ws
+            .handle_parse_error(&trades, frame, error)
+            .unwrap()
+            .is_err());
+
+        let ws = ws.with_parse_error_policy(ParseErrorPolicy::SkipAndLog);
+        let error = ws.parse(&trades, frame).unwrap_err();
+        assert!(ws.handle_parse_error(&trades, frame, error).is_none());
+
+        let ws = ws.with_parse_error_policy(ParseErrorPolicy::EmitUnknown);
+        for frame in [frame, "not json"] {
+            let error = ws.parse(&trades, frame).unwrap_err();
+            let Some(Ok(BinanceWebsocketMessage::Unknown(payload))) =
+                ws.handle_parse_error(&trades, frame, error)
+            else {
+                panic!("expected an unknown message");
+            };
+            assert!(payload["e"] == "trade2" || payload == "not json");
+        }
+    }
+
+    #[tokio::test]
+    async fn broadcast() {
+        let trades = Subscription::Trade("btcusdt".into());
+        let stream = TestStream::builder()
+            .message(BinanceWebsocketMessage::Ping)
+            .disconnect()
+            .stale(&trades)
+            .build();
+        let (sender, mut first) = broadcast::channel(8);
+        let mut second = sender.subscribe();
+        let reconnected = Arc::new(Mutex::new(Vec::new()));
+        fan_out(Reconnecting(stream, reconnected.clone()), sender).await;
+        assert_eq!(*reconnected.lock().unwrap(), vec![trades]);
+
+        for receiver in [&mut first, &mut second] {
+            assert!(matches!(
+                receiver.recv().await,
+                Ok(BinanceWebsocketMessage::Ping)
+            ));
+            assert!(matches!(
+                receiver.recv().await,
+                Ok(BinanceWebsocketMessage::StaleStream(..))
+            ));
+            assert!(matches!(receiver.recv().await, Err(RecvError::Closed)));
+        }
+
+        // Ends once no subscription is left
+        let sender = BinanceWebsocket::default().broadcast(8);
+        let mut receiver = sender.subscribe();
+        drop(sender);
+        assert!(matches!(receiver.recv().await, Err(RecvError::Closed)));
+    }
 }
@@ -0,0 +1,319 @@
+use crate::{
+    error::Error,
+    model::{
+        pair::IntoTradingPair,
+        request::{query_number, NewOrderRequest, OrderQueryRequest, SessionLogonRequest},
+        websocket::{parse_event, BinanceWebsocketMessage},
+        ws_api::{SessionStatus, WsApiMessage},
+        OrderCanceled, OrderResponse,
+    },
+    signing::{timestamp, Ed25519Key},
+};
+use anyhow::Result;
+use futures::{
+    channel::{
+        mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+        oneshot,
+    },
+    lock::Mutex as AsyncMutex,
+    prelude::*,
+    stream::SplitSink,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Map, Value};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, PoisonError,
+    },
+};
+use tokio::{net::TcpStream, task::JoinHandle};
+use tokio_tungstenite::{connect_async, tungstenite::Message, MaybeTlsStream, WebSocketStream};
+use tracing::{field, info_span, Instrument};
+
+const WS_API_URL: &str = "wss://ws-api.binance.com:443/ws-api/v3";
+
+type WSSink = SplitSink<WebSocketStream<MaybeTlsStream<TcpStream>>, Message>;
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<Result<Value>>>>>;
+type Events = Arc<Mutex<Option<UnboundedSender<Result<BinanceWebsocketMessage>>>>>;
+
+// One authenticated connection to the websocket API. After `session_logon` requests are signed
+// by the session, so orders go out without a signature each and user data events arrive on the
+// same socket without a listen key.
+#[allow(clippy::module_name_repetitions)]
+#[derive(Debug)]
+pub struct WebsocketApi {
+    api_key: String,
+    key: Ed25519Key,
+    sink: AsyncMutex<WSSink>,
+    pending: Pending,
+    events: Events,
+    next_id: AtomicU64,
+    reader: JoinHandle<()>,
+}
+
+impl WebsocketApi {
+    // `key` is the private half of the Ed25519 API key `api_key`
+    pub async fn connect(api_key: &str, key: Ed25519Key) -> Result<Self> {
+        let (sink, mut stream) = connect_async(WS_API_URL).await?.0.split();
+        let pending = Pending::default();
+        let events = Events::default();
+
+        let (reader_pending, reader_events) = (Arc::clone(&pending), Arc::clone(&events));
+        let reader = tokio::spawn(async move {
+            while let Some(Ok(message)) = stream.next().await {
+                match message {
+                    Message::Text(text) => dispatch(&text, &reader_pending, &reader_events),
+                    Message::Close(..) => break,
+                    _ => {}
+                }
+            }
+            // Requests still waiting fail with `Error::WebsocketApiClosed`, the event stream ends
+            lock(&reader_pending).clear();
+            lock(&reader_events).take();
+        });
+
+        Ok(Self {
+            api_key: api_key.into(),
+            key,
+            sink: AsyncMutex::new(sink),
+            pending,
+            events,
+            next_id: AtomicU64::new(1),
+            reader,
+        })
+    }
+
+    // Authenticate the connection with the Ed25519 key
+    pub async fn session_logon(&self) -> Result<SessionStatus> {
+        let timestamp = timestamp();
+        let payload = format!("apiKey={}&timestamp={timestamp}", self.api_key);
+        let logon = SessionLogonRequest {
+            api_key: self.api_key.clone(),
+            timestamp,
+            signature: self.key.sign(&payload),
+        };
+        self.request("session.logon", Some(serde_json::to_value(logon)?))
+            .await
+    }
+
+    pub async fn session_status(&self) -> Result<SessionStatus> {
+        self.request("session.status", None).await
+    }
+
+    // Back to an unauthenticated connection, the user data subscription ends as well
+    pub async fn session_logout(&self) -> Result<SessionStatus> {
+        self.request("session.logout", None).await
+    }
+
+    // Account, balance and order events of the logged on account, until the connection closes
+    // or `session_logout`. Subscribing again replaces the previous stream.
+    pub async fn subscribe_user_data(
+        &self,
+    ) -> Result<UnboundedReceiver<Result<BinanceWebsocketMessage>>> {
+        let (sender, receiver) = unbounded();
+        *lock(&self.events) = Some(sender);
+        if let Err(e) = self
+            .request::<Value>("userDataStream.subscribe", None)
+            .await
+        {
+            lock(&self.events).take();
+            return Err(e);
+        }
+        Ok(receiver)
+    }
+
+    // Place an order with the logged on session. The response type picks how much the exchange
+    // reports back, as with `Binance::new_order`.
+    pub async fn place_order<R: OrderResponse>(&self, order: NewOrderRequest) -> Result<R> {
+        let order = order.response_type(R::RESPONSE_TYPE);
+        self.request("order.place", Some(signed_params(&order)?))
+            .await
+    }
+
+    pub async fn cancel_order<S>(&self, symbol: S, order_id: u64) -> Result<OrderCanceled>
+    where
+        S: IntoTradingPair,
+    {
+        let cancel = OrderQueryRequest {
+            symbol: symbol.into_trading_pair()?.into(),
+            ..OrderQueryRequest::default()
+        }
+        .order_id(order_id);
+        self.request("order.cancel", Some(signed_params(&cancel)?))
+            .await
+    }
+
+    async fn request<T: DeserializeOwned>(&self, method: &str, params: Option<Value>) -> Result<T> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let param = |name: &str| {
+            params
+                .as_ref()
+                .and_then(|params| params.get(name)?.as_str().map(str::to_owned))
+        };
+        let span = info_span!(
+            "binance_ws_request",
+            method,
+            id,
+            symbol = param("symbol").as_deref(),
+            correlation_id = param("newClientOrderId").as_deref(),
+            outcome = field::Empty,
+        );
+        let result = self
+            .send_request(id, method, params)
+            .instrument(span.clone())
+            .await;
+        match &result {
+            Ok(_) => span.record("outcome", "ok"),
+            Err(e) => span.record("outcome", field::display(e)),
+        };
+        result
+    }
+
+    async fn send_request<T: DeserializeOwned>(
+        &self,
+        id: u64,
+        method: &str,
+        params: Option<Value>,
+    ) -> Result<T> {
+        let (sender, receiver) = oneshot::channel();
+        lock(&self.pending).insert(id, sender);
+
+        let mut request = json!({ "id": id, "method": method });
+        if let Some(params) = params {
+            request["params"] = params;
+        }
+        if let Err(e) = self
+            .sink
+            .lock()
+            .await
+            .send(Message::Text(request.to_string()))
+            .await
+        {
+            lock(&self.pending).remove(&id);
+            return Err(e.into());
+        }
+
+        let result = receiver.await.map_err(|_| Error::WebsocketApiClosed)??;
+        Ok(serde_json::from_value(result)?)
+    }
+}
+
+impl Drop for WebsocketApi {
+    fn drop(&mut self) {
+        self.reader.abort();
+    }
+}
+
+// Answers go to the request waiting for them, events to the user data subscriber
+fn dispatch(text: &str, pending: &Pending, events: &Events) {
+    match serde_json::from_str(text) {
+        Ok(WsApiMessage::Response(response)) => {
+            let sender = lock(pending).remove(&response.id);
+            if let Some(sender) = sender {
+                let _ = sender.send(response.into_result().map_err(Into::into));
+            }
+        }
+        Ok(WsApiMessage::Event { event }) => {
+            if let Some(sender) = lock(events).as_ref() {
+                let _ = sender.unbounded_send(parse_event(&event.to_string()));
+            }
+        }
+        Err(e) => log::warn!("Unexpected websocket API message {text}: {e}"),
+    }
+}
+
+// The fields of `request` plus the timestamp, numbers as plain decimal strings
+fn signed_params<R: Serialize + Debug>(request: &R) -> Result<Value> {
+    let Value::Object(fields) = serde_json::to_value(request)? else {
+        return Err(Error::InvalidParams(format!("{request:?}")).into());
+    };
+    let mut params: Map<String, Value> = fields
+        .into_iter()
+        .filter(|(_, value)| !value.is_null())
+        .map(|(key, value)| match value {
+            Value::Number(number) => (key, Value::String(query_number(&number))),
+            other => (key, other),
+        })
+        .collect();
+    params.insert("timestamp".into(), timestamp().into());
+    Ok(Value::Object(params))
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{dispatch, signed_params, Events, Pending};
+    use crate::model::{
+        request::{NewOrderRequest, OrderQueryRequest},
+        websocket::BinanceWebsocketMessage,
+        Side,
+    };
+    use futures::channel::{mpsc::unbounded, oneshot};
+
+    #[test]
+    fn order_params() {
+        let params = signed_params(&NewOrderRequest::limit(
+            "btcusdt",
+            Side::Buy,
+            0.01,
+            65_000.5,
+        ))
+        .unwrap();
+        assert_eq!(params["symbol"], "BTCUSDT");
+        assert_eq!(params["price"], "65000.5");
+        assert_eq!(params["timeInForce"], "GTC");
+        assert!(params.get("stopPrice").is_none());
+        assert!(params["timestamp"].is_u64());
+
+        let order =
+            NewOrderRequest::market_quote("SHIBUSDT", Side::Buy, 0.000_008_12).trailing_delta(250);
+        let params = signed_params(&order).unwrap();
+        assert_eq!(params["quoteOrderQty"], "0.00000812");
+        assert_eq!(params["trailingDelta"], "250");
+
+        let cancel = OrderQueryRequest {
+            symbol: "BTCUSDT".into(),
+            ..OrderQueryRequest::default()
+        }
+        .order_id(42);
+        let params = signed_params(&cancel).unwrap();
+        assert_eq!(params["orderId"], "42");
+        assert!(params.get("origClientOrderId").is_none());
+    }
+
+    #[test]
+    fn dispatch_messages() {
+        let pending = Pending::default();
+        let events = Events::default();
+        let (sender, mut receiver) = oneshot::channel();
+        pending.lock().unwrap().insert(7, sender);
+        let (event_sender, mut event_receiver) = unbounded();
+        *events.lock().unwrap() = Some(event_sender);
+
+        dispatch(
+            r#"{"id":7,"status":200,"result":{"apiKey":null,"authorizedSince":null,"connectedSince":1649729873021,"returnRateLimits":false,"serverTime":1649730611671},"rateLimits":[]}"#,
+            &pending,
+            &events,
+        );
+        let result = receiver.try_recv().unwrap().unwrap().unwrap();
+        assert_eq!(result["connectedSince"], 1_649_729_873_021_u64);
+        assert!(pending.lock().unwrap().is_empty());
+
+        dispatch(
+            r#"{"subscriptionId":0,"event":{"e":"outboundAccountPosition","E":1564034571105,"u":1564034571073,"B":[{"a":"ETH","f":"10000.000000","l":"0.000000"}]}}"#,
+            &pending,
+            &events,
+        );
+        let event = event_receiver.try_recv().unwrap().unwrap();
+        assert!(matches!(
+            event,
+            BinanceWebsocketMessage::UserAccountUpdate(..)
+        ));
+    }
+}
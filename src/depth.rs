@@ -0,0 +1,481 @@
+//! Local order book kept in sync with the diff depth stream.
+//!
+//! Seed a `DepthCache` with a `get_depth` snapshot and feed it every `depthUpdate` event of the
+//! symbol. Events arriving before the snapshot are buffered and replayed on top of it. A missed
+//! event fails with `Error::DepthGap` and empties the book until the next snapshot.
+//!
+//! Price levels are keyed by `PriceTicks` of the symbol's tick size, so lookups and range
+//! queries never compare floats.
+//!
+//! `OrderBookSync` drives a `DepthCache` from a websocket stream and does the snapshot handling
+//! itself: it fetches one to start, and on a missed event fetches a new one, replays the events
+//! buffered meanwhile and reports `SyncEvent::Resynced`.
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    client::Binance,
+    model::{websocket::BinanceWebsocketMessage, DepthLimit},
+};
+use crate::{
+    error::Error,
+    model::{ticks::PriceTicks, websocket::Depth, OrderBook, Side, Symbol},
+};
+#[cfg(not(target_arch = "wasm32"))]
+use futures::{future::BoxFuture, Stream, StreamExt};
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+// Events kept while waiting for a snapshot, about a minute of a busy 100ms stream
+const MAX_BUFFERED: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PriceLevel {
+    pub price: f64,
+    pub qty: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct DepthCache {
+    tick_size: f64,
+    // `None` until the first snapshot, and again after a gap
+    last_update_id: Option<u64>,
+    // Whether an event was applied since the snapshot
+    bridged: bool,
+    bids: BTreeMap<PriceTicks, PriceLevel>,
+    asks: BTreeMap<PriceTicks, PriceLevel>,
+    buffered: Vec<Depth>,
+}
+
+impl DepthCache {
+    #[must_use]
+    pub const fn new(tick_size: f64) -> Self {
+        Self {
+            tick_size,
+            last_update_id: None,
+            bridged: false,
+            bids: BTreeMap::new(),
+            asks: BTreeMap::new(),
+            buffered: Vec::new(),
+        }
+    }
+
+    // Keyed by the tick size of the symbol's PRICE_FILTER
+    pub fn for_symbol(symbol: &Symbol) -> Result<Self, Error> {
+        symbol
+            .tick_size()
+            .and_then(|tick_size| tick_size.parse().ok())
+            .filter(|tick_size| *tick_size > 0.0)
+            .map(Self::new)
+            .ok_or_else(|| Error::InvalidSymbol(symbol.symbol.clone()))
+    }
+
+    // Replace the book with `snapshot`, then apply the buffered events that follow it
+    pub fn apply_snapshot(&mut self, snapshot: &OrderBook) -> Result<(), Error> {
+        self.bids = snapshot
+            .bids
+            .iter()
+            .map(|bid| (self.ticks(bid.price), level(bid.price, bid.qty)))
+            .collect();
+        self.asks = snapshot
+            .asks
+            .iter()
+            .map(|ask| (self.ticks(ask.price), level(ask.price, ask.qty)))
+            .collect();
+        self.last_update_id = Some(snapshot.last_update_id);
+        self.bridged = false;
+        for event in std::mem::take(&mut self.buffered) {
+            self.apply(&event)?;
+        }
+        Ok(())
+    }
+
+    // Apply a `depthUpdate` event. `Ok(false)` if it was buffered for the next snapshot or is
+    // already contained in the book.
+    pub fn apply(&mut self, event: &Depth) -> Result<bool, Error> {
+        let Some(last_update_id) = self.last_update_id else {
+            if self.buffered.len() == MAX_BUFFERED {
+                self.buffered.remove(0);
+            }
+            self.buffered.push(event.clone());
+            return Ok(false);
+        };
+        if event.is_outdated(last_update_id) {
+            return Ok(false);
+        }
+        let in_sequence = if self.bridged {
+            event.first_update_id == last_update_id + 1
+        } else {
+            event.bridges_snapshot(last_update_id)
+        };
+        if !in_sequence {
+            self.clear();
+            return Err(Error::DepthGap {
+                last_update_id,
+                first_update_id: event.first_update_id,
+            });
+        }
+
+        for bid in &event.bids {
+            let ticks = self.ticks(bid.price);
+            update(&mut self.bids, ticks, level(bid.price, bid.qty));
+        }
+        for ask in &event.asks {
+            let ticks = self.ticks(ask.price);
+            update(&mut self.asks, ticks, level(ask.price, ask.qty));
+        }
+        self.last_update_id = Some(event.final_update_id);
+        self.bridged = true;
+        Ok(true)
+    }
+
+    // Whether the book reflects a snapshot and every event since
+    #[must_use]
+    pub const fn is_synced(&self) -> bool {
+        self.last_update_id.is_some()
+    }
+
+    #[must_use]
+    pub const fn last_update_id(&self) -> Option<u64> {
+        self.last_update_id
+    }
+
+    #[must_use]
+    pub fn best_bid(&self) -> Option<PriceLevel> {
+        self.bids.values().next_back().copied()
+    }
+
+    #[must_use]
+    pub fn best_ask(&self) -> Option<PriceLevel> {
+        self.asks.values().next().copied()
+    }
+
+    #[must_use]
+    pub fn mid_price(&self) -> Option<f64> {
+        Some(f64::midpoint(
+            self.best_bid()?.price,
+            self.best_ask()?.price,
+        ))
+    }
+
+    // Bids from the highest price down
+    pub fn bids(&self) -> impl Iterator<Item = &PriceLevel> {
+        self.bids.values().rev()
+    }
+
+    // Asks from the lowest price up
+    pub fn asks(&self) -> impl Iterator<Item = &PriceLevel> {
+        self.asks.values()
+    }
+
+    // The best `n` levels of one side, best first
+    #[must_use]
+    pub fn levels(&self, side: &Side, n: usize) -> Vec<PriceLevel> {
+        match side {
+            Side::Buy => self.bids().take(n).copied().collect(),
+            Side::Sell => self.asks().take(n).copied().collect(),
+        }
+    }
+
+    // Quantity on both sides at prices within `range`, bounds included
+    #[must_use]
+    pub fn volume_within(&self, range: RangeInclusive<f64>) -> f64 {
+        let start = self.ticks_at_least(*range.start());
+        let Some(end) = self.ticks_at_most(*range.end()) else {
+            return 0.0;
+        };
+        if start > end {
+            return 0.0;
+        }
+        self.bids
+            .range(PriceTicks(start)..=PriceTicks(end))
+            .chain(self.asks.range(PriceTicks(start)..=PriceTicks(end)))
+            .map(|(_, level)| level.qty)
+            .sum()
+    }
+
+    fn clear(&mut self) {
+        self.bids.clear();
+        self.asks.clear();
+        self.last_update_id = None;
+        self.bridged = false;
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn ticks(&self, price: f64) -> PriceTicks {
+        PriceTicks((price / self.tick_size).round() as u64)
+    }
+
+    // Smallest tick at or above `price`, the cast saturates for huge prices
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn ticks_at_least(&self, price: f64) -> u64 {
+        (price / self.tick_size - 1e-9).ceil().max(0.0) as u64
+    }
+
+    // Largest tick at or below `price`, `None` below zero
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    fn ticks_at_most(&self, price: f64) -> Option<u64> {
+        let ticks = (price / self.tick_size + 1e-9).floor();
+        (ticks >= 0.0).then_some(ticks as u64)
+    }
+}
+
+const fn level(price: f64, qty: f64) -> PriceLevel {
+    PriceLevel { price, qty }
+}
+
+// A quantity of zero removes the level
+fn update(side: &mut BTreeMap<PriceTicks, PriceLevel>, ticks: PriceTicks, level: PriceLevel) {
+    if level.qty == 0.0 {
+        side.remove(&ticks);
+    } else {
+        side.insert(ticks, level);
+    }
+}
+
+// What `OrderBookSync::next` did to the book
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncEvent {
+    // The first snapshot was applied
+    Synced { last_update_id: u64 },
+    // A `depthUpdate` event was applied
+    Updated { last_update_id: u64 },
+    // A new snapshot was applied after a missed event, `missed` is the update id the book
+    // was at when the gap was detected
+    Resynced { missed: u64, last_update_id: u64 },
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+type SnapshotSource = Box<dyn FnMut() -> BoxFuture<'static, anyhow::Result<OrderBook>> + Send>;
+
+// A `DepthCache` fed from a diff depth stream, refetching the snapshot whenever it falls behind
+#[cfg(not(target_arch = "wasm32"))]
+pub struct OrderBookSync<S> {
+    stream: S,
+    symbol: String,
+    book: DepthCache,
+    snapshot: SnapshotSource,
+    // Update id the book was at when the last gap was detected
+    missed: Option<u64>,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<S> OrderBookSync<S>
+where
+    S: Stream<Item = anyhow::Result<BinanceWebsocketMessage>> + Unpin,
+{
+    // `stream` has to be subscribed to `Subscription::Depth(symbol)` or a faster variant, events
+    // of other symbols and other messages are skipped
+    #[must_use]
+    pub fn new(
+        client: &Binance,
+        symbol: &str,
+        limit: impl Into<Option<DepthLimit>>,
+        stream: S,
+        book: DepthCache,
+    ) -> Self {
+        let client = client.clone();
+        let limit = limit.into();
+        let name = symbol.to_uppercase();
+        Self::with_snapshots(symbol, stream, book, move || {
+            let client = client.clone();
+            let name = name.clone();
+            Box::pin(async move { client.get_depth(&name, limit).await })
+        })
+    }
+
+    // With snapshots from somewhere else than `get_depth`, e.g. a replay
+    #[must_use]
+    pub fn with_snapshots<F>(symbol: &str, stream: S, book: DepthCache, snapshot: F) -> Self
+    where
+        F: FnMut() -> BoxFuture<'static, anyhow::Result<OrderBook>> + Send + 'static,
+    {
+        Self {
+            stream,
+            symbol: symbol.to_uppercase(),
+            book,
+            snapshot: Box::new(snapshot),
+            missed: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn book(&self) -> &DepthCache {
+        &self.book
+    }
+
+    // Apply the next change to the book, `None` once the stream ended. Errors of the stream and
+    // of fetching a snapshot are passed on, the next call carries on where this one failed.
+    pub async fn next(&mut self) -> Option<anyhow::Result<SyncEvent>> {
+        loop {
+            if !self.book.is_synced() {
+                let snapshot = match (self.snapshot)().await {
+                    Ok(snapshot) => snapshot,
+                    Err(e) => return Some(Err(e)),
+                };
+                // A snapshot older than the buffered events is a gap again, fetch another one
+                if let Err(e) = self.book.apply_snapshot(&snapshot) {
+                    log::warn!("{} order book: {e}", self.symbol);
+                    continue;
+                }
+                let last_update_id = self
+                    .book
+                    .last_update_id()
+                    .unwrap_or(snapshot.last_update_id);
+                return Some(Ok(self.missed.take().map_or(
+                    SyncEvent::Synced { last_update_id },
+                    |missed| SyncEvent::Resynced {
+                        missed,
+                        last_update_id,
+                    },
+                )));
+            }
+
+            let depth = match self.stream.next().await? {
+                Ok(BinanceWebsocketMessage::Depth(depth)) if depth.symbol == self.symbol => depth,
+                Ok(_) => continue,
+                Err(e) => return Some(Err(e)),
+            };
+            match self.book.apply(&depth) {
+                Ok(true) => {
+                    return Some(Ok(SyncEvent::Updated {
+                        last_update_id: depth.final_update_id,
+                    }))
+                }
+                Ok(false) => {}
+                Err(Error::DepthGap { last_update_id, .. }) => {
+                    log::warn!("{} order book missed events, resyncing", self.symbol);
+                    self.missed.get_or_insert(last_update_id);
+                    // Replayed on top of the new snapshot
+                    self.book.apply(&depth).ok();
+                }
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{DepthCache, OrderBookSync, SyncEvent};
+    use crate::{
+        error::Error,
+        model::{
+            websocket::{BinanceWebsocketMessage, Depth},
+            OrderBook, Side,
+        },
+        testing::TestStream,
+    };
+    use anyhow::Result;
+    use futures::FutureExt;
+    use serde_json::from_str;
+    use std::sync::{Arc, Mutex};
+
+    fn depth(first: u64, last: u64, bids: &str, asks: &str) -> Result<Depth> {
+        Ok(from_str(&format!(
+            r#"{{"e":"depthUpdate","E":1672515782136,"s":"BNBBTC","U":{first},"u":{last},"b":{bids},"a":{asks}}}"#
+        ))?)
+    }
+
+    #[test]
+    fn depth_cache() -> Result<()> {
+        let mut book = DepthCache::new(0.0001);
+        // Buffered until the snapshot, then dropped as outdated
+        assert!(!book.apply(&depth(150, 155, r#"[["0.0020","1"]]"#, "[]")?)?);
+        assert!(!book.apply(&depth(156, 160, r#"[["0.0024","10"]]"#, "[]")?)?);
+
+        let snapshot: OrderBook = from_str(
+            r#"{"lastUpdateId":158,"bids":[["0.0024","5"],["0.0023","20"],["0.0022","7"]],"asks":[["0.0026","100"],["0.0027","3"]]}"#,
+        )?;
+        book.apply_snapshot(&snapshot)?;
+        assert_eq!(book.last_update_id(), Some(160));
+        assert!((book.best_bid().unwrap().qty - 10.0).abs() < f64::EPSILON);
+
+        assert!(book.apply(&depth(
+            161,
+            165,
+            r#"[["0.0023","0"]]"#,
+            r#"[["0.0025","1"]]"#
+        )?)?);
+        assert!((book.best_ask().unwrap().price - 0.0025).abs() < f64::EPSILON);
+        assert!((book.mid_price().unwrap() - 0.00245).abs() < 1e-12);
+        let bids = book.levels(&Side::Buy, 5);
+        assert_eq!(bids.len(), 2);
+        assert!((bids[1].price - 0.0022).abs() < f64::EPSILON);
+        assert_eq!(book.levels(&Side::Sell, 1).len(), 1);
+        assert!((book.volume_within(0.0022..=0.0025) - 18.0).abs() < f64::EPSILON);
+        assert!(book.volume_within(0.00221..=0.00239).abs() < f64::EPSILON);
+
+        let gap = book.apply(&depth(170, 171, "[]", "[]")?);
+        assert!(matches!(
+            gap,
+            Err(Error::DepthGap {
+                last_update_id: 165,
+                ..
+            })
+        ));
+        assert!(!book.is_synced());
+        assert!(book.best_bid().is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn order_book_sync() -> Result<()> {
+        let snapshots: Arc<Mutex<Vec<OrderBook>>> = Arc::new(Mutex::new(vec![
+            from_str(r#"{"lastUpdateId":100,"bids":[["0.0024","5"]],"asks":[["0.0026","1"]]}"#)?,
+            from_str(r#"{"lastUpdateId":200,"bids":[["0.0023","8"]],"asks":[["0.0025","2"]]}"#)?,
+        ]));
+        let stream = TestStream::builder()
+            .message(BinanceWebsocketMessage::Depth(depth(
+                99,
+                101,
+                r#"[["0.0024","6"]]"#,
+                "[]",
+            )?))
+            .message(BinanceWebsocketMessage::Ping)
+            .message(BinanceWebsocketMessage::Depth(depth(110, 120, "[]", "[]")?))
+            .message(BinanceWebsocketMessage::Depth(depth(
+                201,
+                205,
+                r#"[["0.0023","9"]]"#,
+                "[]",
+            )?))
+            .build();
+        let source = Arc::clone(&snapshots);
+        let mut sync =
+            OrderBookSync::with_snapshots("bnbbtc", stream, DepthCache::new(0.0001), move || {
+                let snapshot = source.lock().unwrap().remove(0);
+                async move { Ok(snapshot) }.boxed()
+            });
+
+        assert_eq!(
+            sync.next().await.unwrap()?,
+            SyncEvent::Synced {
+                last_update_id: 100
+            }
+        );
+        assert_eq!(
+            sync.next().await.unwrap()?,
+            SyncEvent::Updated {
+                last_update_id: 101
+            }
+        );
+        // 102 to 109 were missed
+        assert_eq!(
+            sync.next().await.unwrap()?,
+            SyncEvent::Resynced {
+                missed: 101,
+                last_update_id: 200
+            }
+        );
+        assert!((sync.book().best_bid().unwrap().qty - 8.0).abs() < f64::EPSILON);
+        assert_eq!(
+            sync.next().await.unwrap()?,
+            SyncEvent::Updated {
+                last_update_id: 205
+            }
+        );
+        assert!(sync.next().await.is_none());
+        assert!(snapshots.lock().unwrap().is_empty());
+        Ok(())
+    }
+}
@@ -1,5 +1,7 @@
+use crate::model::ApiKeyPermission;
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::{fmt, time::Duration};
 use thiserror::Error;
 
 #[allow(clippy::pub_enum_variant_names)]
@@ -15,6 +17,66 @@ pub enum Error {
     NoApiKeySet,
     #[error("No stream is subscribed")]
     NoStreamSubscribed,
+    #[error("Invalid symbol: {0}")]
+    InvalidSymbol(String),
+    #[error("Api key lacks the {0:?} permission")]
+    MissingApiKeyPermission(ApiKeyPermission),
+    #[error("Invalid depth limit: {0}")]
+    InvalidDepthLimit(u64),
+    #[error("Timestamp out of range: {0}")]
+    InvalidTimestamp(u64),
+    #[error("Unknown stream: {0}")]
+    UnknownStream(String),
+    #[error("Unknown event: {0}")]
+    UnknownEvent(String),
+    #[error("Cannot measure latency from {0} samples")]
+    InvalidLatencySamples(usize),
+    #[error("Request parameters must be a map: {0}")]
+    InvalidParams(String),
+    #[error("Exchange under maintenance: {0}")]
+    ExchangeMaintenance(String),
+    #[error("Unsupported proxy: {0}")]
+    UnsupportedProxy(String),
+    #[error("Invalid candle interval: {0:?}")]
+    InvalidCandleInterval(Duration),
+    #[error("Invalid execution plan: {0}")]
+    InvalidExecutionPlan(String),
+    #[error("No market data for {0}")]
+    NoMarketData(String),
+    #[error("Position mode cannot be changed: {0}")]
+    PositionModeLocked(String),
+    #[error("Margin type cannot be changed: {0}")]
+    MarginTypeLocked(String),
+    #[error("Invalid private key: {0}")]
+    InvalidKey(String),
+    #[error("Websocket API connection closed")]
+    WebsocketApiClosed,
+    #[error("Endpoint not available in this region: {0}")]
+    UnsupportedEndpoint(String),
+    #[error("Credential not found: {0}")]
+    MissingCredential(String),
+    #[error("Invalid withdrawal: {0}")]
+    InvalidWithdrawal(String),
+    #[error("IP banned until {until:?}")]
+    IpBanned { until: Option<u64> },
+    #[error("Request blocked by the CDN with status {0}")]
+    Cloudflare(u16),
+    #[error("Depth update {first_update_id} does not follow update {last_update_id}")]
+    DepthGap {
+        last_update_id: u64,
+        first_update_id: u64,
+    },
+}
+
+// Attached as context to the error of an order placed with an auto-generated client order id,
+// so the caller can look the order up with `find_order_by_client_id` after e.g. a timeout.
+#[derive(Debug, Clone)]
+pub struct ClientOrderId(pub String);
+
+impl fmt::Display for ClientOrderId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "newClientOrderId: {}", self.0)
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -0,0 +1,321 @@
+//! TWAP/VWAP execution of a large order as a series of smaller child orders.
+//!
+//! An `Execution` splits the quantity of an `ExecutionPlan` into slices spread over its
+//! duration, evenly (TWAP) or weighted by a volume profile (VWAP). Each slice is placed as a
+//! LIMIT or MARKET order, optionally capped to a share of the recent market volume, and what
+//! a cap held back is carried over to the next slice. What it still holds back at the last
+//! slice is not placed, see `Progress::unplaced`. Fills are taken from the order responses and
+//! from the user data stream.
+
+use crate::{
+    client::Binance,
+    error::Error,
+    model::{websocket::BinanceWebsocketMessage, KlineSummaries, OrderFull, Side},
+};
+use anyhow::Result;
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    Stream, StreamExt,
+};
+use std::{collections::HashMap, convert::TryFrom, time::Duration};
+use tokio::time::{sleep_until, Instant};
+
+// Binance quantities have at most 8 decimals
+const QTY_SCALE: f64 = 100_000_000.0;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    // `slices` child orders of equal size
+    Twap { slices: u32 },
+    // One child order per weight, sized by its share of the total, e.g. the volumes of the
+    // klines covering the same time of day in the past
+    Vwap(Vec<f64>),
+}
+
+#[derive(Debug, Clone)]
+pub struct ExecutionPlan {
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: f64,
+    pub duration: Duration,
+    pub schedule: Schedule,
+    // Most a slice may take of the market volume in the minute before it, e.g. 0.1 for 10%
+    pub participation: Option<f64>,
+    // Child orders are LIMIT orders at this price, MARKET orders without one
+    pub limit_price: Option<f64>,
+    // LOT_SIZE step child quantities are rounded down to
+    pub qty_step: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Progress {
+    pub target: f64,
+    pub placed: f64,
+    pub filled: f64,
+    pub slices_done: usize,
+    pub slices: usize,
+}
+
+impl Progress {
+    #[must_use]
+    pub fn remaining(&self) -> f64 {
+        (self.target - self.filled).max(0.0)
+    }
+
+    // Quantity no child order was placed for. Once `is_scheduled`, what participation caps held
+    // back at the last slice and rounding to the step size left over.
+    #[must_use]
+    pub fn unplaced(&self) -> f64 {
+        (self.target - self.placed).max(0.0)
+    }
+
+    #[must_use]
+    pub const fn is_scheduled(&self) -> bool {
+        self.slices_done == self.slices
+    }
+}
+
+#[derive(Debug)]
+pub struct Execution {
+    plan: ExecutionPlan,
+    // Quantity that should be placed once slice `i` is done
+    cumulative_targets: Vec<f64>,
+    next_slice: usize,
+    placed: f64,
+    // Executed quantity of every child order
+    children: HashMap<u64, f64>,
+    subscribers: Vec<UnboundedSender<Progress>>,
+}
+
+impl Execution {
+    pub fn new(plan: ExecutionPlan) -> Result<Self> {
+        let weights = match &plan.schedule {
+            Schedule::Twap { slices } => vec![1.0; usize::try_from(*slices).unwrap_or_default()],
+            Schedule::Vwap(weights) => weights.clone(),
+        };
+        let total: f64 = weights.iter().sum();
+        if plan.quantity <= 0.0 {
+            return Err(Error::InvalidExecutionPlan("quantity must be positive".into()).into());
+        }
+        if weights.is_empty() || weights.iter().any(|w| *w < 0.0) || total <= 0.0 {
+            return Err(Error::InvalidExecutionPlan("schedule has no slices".into()).into());
+        }
+
+        let mut sum = 0.0;
+        let cumulative_targets = weights
+            .iter()
+            .map(|weight| {
+                sum += weight;
+                plan.quantity * sum / total
+            })
+            .collect();
+        Ok(Self {
+            plan,
+            cumulative_targets,
+            next_slice: 0,
+            placed: 0.0,
+            children: HashMap::new(),
+            subscribers: Vec::new(),
+        })
+    }
+
+    #[must_use]
+    pub fn progress(&self) -> Progress {
+        Progress {
+            target: self.plan.quantity,
+            placed: self.placed,
+            filled: self.children.values().sum(),
+            slices_done: self.next_slice,
+            slices: self.cumulative_targets.len(),
+        }
+    }
+
+    // Time after the start the next slice is due, `None` once every slice is placed
+    #[must_use]
+    pub fn next_slice_at(&self) -> Option<Duration> {
+        if self.next_slice >= self.cumulative_targets.len() {
+            return None;
+        }
+        let slices = u32::try_from(self.cumulative_targets.len()).unwrap_or(u32::MAX);
+        let index = u32::try_from(self.next_slice).unwrap_or(u32::MAX);
+        Some(self.plan.duration / slices * index)
+    }
+
+    // Places one child order for all slices due `elapsed` after the start
+    pub async fn place_due(&mut self, client: &Binance, elapsed: Duration) -> Result<Progress> {
+        let mut due = self.next_slice;
+        while due < self.cumulative_targets.len() && self.slice_at(due) <= elapsed {
+            due += 1;
+        }
+        if due == self.next_slice {
+            return Ok(self.progress());
+        }
+
+        let mut qty = self.cumulative_targets[due - 1] - self.placed;
+        if let Some(participation) = self.plan.participation {
+            qty = qty.min(self.volume_cap(client, participation).await?);
+        }
+        let qty = self.round_qty(qty);
+        self.next_slice = due;
+        if qty > 0.0 {
+            let order: OrderFull = client
+                .new_order(
+                    &self.plan.symbol,
+                    self.plan.side.clone(),
+                    qty,
+                    self.plan.limit_price,
+                )
+                .await?;
+            let filled = order.fills.iter().map(|fill| fill.qty).sum();
+            self.placed += qty;
+            self.record_fill(order.result.order_id, filled);
+        }
+        Ok(self.notify())
+    }
+
+    // Takes the execution reports of the user data stream, `None` for anything else
+    pub fn apply(&mut self, message: &BinanceWebsocketMessage) -> Option<Progress> {
+        let BinanceWebsocketMessage::UserOrderUpdate(update) = message else {
+            return None;
+        };
+        if !self.children.contains_key(&update.order_id) {
+            return None;
+        }
+        self.record_fill(update.order_id, update.accumulated_qty_filled_trades);
+        Some(self.notify())
+    }
+
+    // Places every slice on schedule while applying `user_stream`, returns once the last slice
+    // is placed. Keep feeding `apply` afterwards to follow LIMIT child orders still open, and
+    // place `Progress::unplaced` separately if the participation cap left any.
+    pub async fn run<S>(&mut self, client: &Binance, mut user_stream: S) -> Result<Progress>
+    where
+        S: Stream<Item = Result<BinanceWebsocketMessage>> + Unpin,
+    {
+        let start = Instant::now();
+        let mut stream_open = true;
+        while let Some(at) = self.next_slice_at() {
+            tokio::select! {
+                () = sleep_until(start + at) => {
+                    self.place_due(client, start.elapsed()).await?;
+                }
+                message = user_stream.next(), if stream_open => match message {
+                    Some(message) => {
+                        self.apply(&message?);
+                    }
+                    None => stream_open = false,
+                },
+            }
+        }
+        let progress = self.progress();
+        if progress.unplaced() > 0.0 {
+            log::warn!(
+                "execution of {}: {} left unplaced after the last slice",
+                self.plan.symbol,
+                progress.unplaced()
+            );
+        }
+        Ok(progress)
+    }
+
+    pub fn progress_updates(&mut self) -> impl Stream<Item = Progress> {
+        let (sender, receiver): (_, UnboundedReceiver<Progress>) = unbounded();
+        self.subscribers.push(sender);
+        receiver
+    }
+
+    fn slice_at(&self, index: usize) -> Duration {
+        let slices = u32::try_from(self.cumulative_targets.len()).unwrap_or(u32::MAX);
+        self.plan.duration / slices * u32::try_from(index).unwrap_or(u32::MAX)
+    }
+
+    // Participation cap of a slice from the volume of the last closed minute
+    async fn volume_cap(&self, client: &Binance, participation: f64) -> Result<f64> {
+        let KlineSummaries::AllKlineSummaries(klines) = client
+            .get_klines(&self.plan.symbol, "1m", 2, None, None)
+            .await?;
+        let volume = klines.first().map_or(0.0, |kline| kline.volume);
+        let slices = u32::try_from(self.cumulative_targets.len()).unwrap_or(u32::MAX);
+        let slice_minutes = (self.plan.duration / slices).as_secs_f64() / 60.0;
+        Ok(participation * volume * slice_minutes.max(1.0))
+    }
+
+    fn round_qty(&self, qty: f64) -> f64 {
+        let qty = match self.plan.qty_step {
+            Some(step) if step > 0.0 => (qty / step + 1e-9).floor() * step,
+            _ => qty,
+        };
+        (qty * QTY_SCALE).floor() / QTY_SCALE
+    }
+
+    // Execution reports and order responses may arrive in any order, the larger fill wins
+    fn record_fill(&mut self, order_id: u64, filled: f64) {
+        let executed = self.children.entry(order_id).or_default();
+        *executed = executed.max(filled);
+    }
+
+    fn notify(&mut self) -> Progress {
+        let progress = self.progress();
+        self.subscribers
+            .retain(|subscriber| subscriber.unbounded_send(progress).is_ok());
+        progress
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Execution, ExecutionPlan, Schedule};
+    use crate::model::{websocket::parse_event, Side};
+    use anyhow::Result;
+    use std::time::Duration;
+
+    fn plan(schedule: Schedule) -> ExecutionPlan {
+        ExecutionPlan {
+            symbol: "ETHBTC".into(),
+            side: Side::Buy,
+            quantity: 10.0,
+            duration: Duration::from_mins(10),
+            schedule,
+            participation: None,
+            limit_price: None,
+            qty_step: Some(0.001),
+        }
+    }
+
+    #[test]
+    fn slices() -> Result<()> {
+        let twap = Execution::new(plan(Schedule::Twap { slices: 4 }))?;
+        assert_eq!(twap.cumulative_targets, vec![2.5, 5.0, 7.5, 10.0]);
+        assert_eq!(twap.next_slice_at(), Some(Duration::ZERO));
+        assert_eq!(twap.slice_at(3), Duration::from_secs(450));
+        assert!((twap.round_qty(1.234_567) - 1.234).abs() < 1e-12);
+
+        let vwap = Execution::new(plan(Schedule::Vwap(vec![1.0, 3.0, 1.0])))?;
+        assert_eq!(vwap.cumulative_targets, vec![2.0, 8.0, 10.0]);
+
+        assert!(Execution::new(plan(Schedule::Twap { slices: 0 })).is_err());
+        assert!(Execution::new(plan(Schedule::Vwap(vec![0.0, 0.0]))).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn fills_from_user_stream() -> Result<()> {
+        let mut execution = Execution::new(plan(Schedule::Twap { slices: 2 }))?;
+        execution.record_fill(4_293_153, 0.0);
+        let update = parse_event(
+            r#"{"e":"executionReport","E":1499405658658,"s":"ETHBTC","c":"mUvoqJxFIILMdfAW5iGSOW","S":"BUY","o":"LIMIT","f":"GTC","q":"5.00000000","p":"0.10264410","P":"0.00000000","F":"0.00000000","g":-1,"C":"","x":"TRADE","X":"PARTIALLY_FILLED","r":"NONE","i":4293153,"l":"1.00000000","z":"1.50000000","L":"0.10264410","n":"0","N":null,"T":1499405658657,"t":1,"I":8641984,"w":true,"m":false,"M":false,"O":1499405658657,"Z":"0.15","Y":"0.1","Q":"0.00000000"}"#,
+        )?;
+        let progress = execution.apply(&update).unwrap();
+        assert!((progress.filled - 1.5).abs() < f64::EPSILON);
+        assert!((progress.remaining() - 8.5).abs() < f64::EPSILON);
+        assert!(!progress.is_scheduled());
+
+        // A participation cap held back the last 2 at the final slice
+        execution.next_slice = 2;
+        execution.placed = 8.0;
+        let progress = execution.progress();
+        assert!(progress.is_scheduled());
+        assert!((progress.unplaced() - 2.0).abs() < f64::EPSILON);
+        Ok(())
+    }
+}
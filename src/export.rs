@@ -0,0 +1,305 @@
+//! Writers persisting downloaded market data and account history for research pipelines.
+//!
+//! Klines, trades and aggregate trades are written as CSV with a header line, or with the
+//! `parquet-export` feature as Arrow record batches and Parquet files. Timestamps are written
+//! as milliseconds in both formats.
+
+use crate::model::{timestamp, AggTrade, HistoricalTrade, KlineSummary, TradeHistory};
+use anyhow::Result;
+use std::{fmt, io::Write};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    UInt64,
+    Int64,
+    Float64,
+    Boolean,
+    Utf8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldValue {
+    UInt64(u64),
+    Int64(i64),
+    Float64(f64),
+    Boolean(bool),
+    Utf8(String),
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UInt64(value) => write!(f, "{value}"),
+            Self::Int64(value) => write!(f, "{value}"),
+            Self::Float64(value) => write!(f, "{value}"),
+            Self::Boolean(value) => write!(f, "{value}"),
+            Self::Utf8(value) => write!(f, "{value}"),
+        }
+    }
+}
+
+// A model that can be written as one row. `values` returns one value per column, each of the
+// column's type.
+pub trait ExportRecord {
+    const COLUMNS: &'static [(&'static str, ColumnType)];
+
+    fn values(&self) -> Vec<FieldValue>;
+}
+
+impl ExportRecord for KlineSummary {
+    const COLUMNS: &'static [(&'static str, ColumnType)] = &[
+        ("open_time", ColumnType::UInt64),
+        ("open", ColumnType::Float64),
+        ("high", ColumnType::Float64),
+        ("low", ColumnType::Float64),
+        ("close", ColumnType::Float64),
+        ("volume", ColumnType::Float64),
+        ("close_time", ColumnType::UInt64),
+        ("quote_asset_volume", ColumnType::Float64),
+        ("number_of_trades", ColumnType::Int64),
+        ("taker_buy_base_asset_volume", ColumnType::Float64),
+        ("taker_buy_quote_asset_volume", ColumnType::Float64),
+    ];
+
+    fn values(&self) -> Vec<FieldValue> {
+        vec![
+            FieldValue::UInt64(timestamp::to_millis(&self.open_time)),
+            FieldValue::Float64(self.open),
+            FieldValue::Float64(self.high),
+            FieldValue::Float64(self.low),
+            FieldValue::Float64(self.close),
+            FieldValue::Float64(self.volume),
+            FieldValue::UInt64(timestamp::to_millis(&self.close_time)),
+            FieldValue::Float64(self.quote_asset_volume),
+            FieldValue::Int64(self.number_of_trades),
+            FieldValue::Float64(self.taker_buy_base_asset_volume),
+            FieldValue::Float64(self.taker_buy_quote_asset_volume),
+        ]
+    }
+}
+
+impl ExportRecord for TradeHistory {
+    const COLUMNS: &'static [(&'static str, ColumnType)] = &[
+        ("symbol", ColumnType::Utf8),
+        ("id", ColumnType::UInt64),
+        ("order_id", ColumnType::UInt64),
+        ("price", ColumnType::Float64),
+        ("qty", ColumnType::Float64),
+        ("commission", ColumnType::Float64),
+        ("commission_asset", ColumnType::Utf8),
+        ("time", ColumnType::UInt64),
+        ("is_buyer", ColumnType::Boolean),
+        ("is_maker", ColumnType::Boolean),
+        ("is_best_match", ColumnType::Boolean),
+    ];
+
+    fn values(&self) -> Vec<FieldValue> {
+        vec![
+            FieldValue::Utf8(self.symbol.clone()),
+            FieldValue::UInt64(self.id),
+            FieldValue::UInt64(self.order_id),
+            FieldValue::Float64(self.price),
+            FieldValue::Float64(self.qty),
+            FieldValue::Float64(self.commission),
+            FieldValue::Utf8(self.commission_asset.clone()),
+            FieldValue::UInt64(timestamp::to_millis(&self.time)),
+            FieldValue::Boolean(self.is_buyer),
+            FieldValue::Boolean(self.is_maker),
+            FieldValue::Boolean(self.is_best_match),
+        ]
+    }
+}
+
+impl ExportRecord for HistoricalTrade {
+    const COLUMNS: &'static [(&'static str, ColumnType)] = &[
+        ("id", ColumnType::Int64),
+        ("price", ColumnType::Float64),
+        ("qty", ColumnType::Float64),
+        ("quote_qty", ColumnType::Float64),
+        ("time", ColumnType::UInt64),
+        ("is_buyer_maker", ColumnType::Boolean),
+        ("is_best_match", ColumnType::Boolean),
+    ];
+
+    fn values(&self) -> Vec<FieldValue> {
+        vec![
+            FieldValue::Int64(self.id),
+            FieldValue::Float64(self.price),
+            FieldValue::Float64(self.qty),
+            FieldValue::Float64(self.quote_qty),
+            FieldValue::UInt64(timestamp::to_millis(&self.time)),
+            FieldValue::Boolean(self.is_buyer_maker),
+            FieldValue::Boolean(self.is_best_match),
+        ]
+    }
+}
+
+impl ExportRecord for AggTrade {
+    const COLUMNS: &'static [(&'static str, ColumnType)] = &[
+        ("aggregated_trade_id", ColumnType::UInt64),
+        ("price", ColumnType::Float64),
+        ("qty", ColumnType::Float64),
+        ("first_trade_id", ColumnType::UInt64),
+        ("last_trade_id", ColumnType::UInt64),
+        ("time", ColumnType::UInt64),
+        ("is_buyer_maker", ColumnType::Boolean),
+        ("is_best_match", ColumnType::Boolean),
+    ];
+
+    fn values(&self) -> Vec<FieldValue> {
+        vec![
+            FieldValue::UInt64(self.aggregated_trade_id),
+            FieldValue::Float64(self.price),
+            FieldValue::Float64(self.qty),
+            FieldValue::UInt64(self.first_trade_id),
+            FieldValue::UInt64(self.last_trade_id),
+            FieldValue::UInt64(timestamp::to_millis(&self.time)),
+            FieldValue::Boolean(self.is_buyer_maker),
+            FieldValue::Boolean(self.is_best_match),
+        ]
+    }
+}
+
+// Header line, then one line per row
+pub fn write_csv<W: Write, T: ExportRecord>(writer: W, rows: &[T]) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(writer);
+    writer.write_record(T::COLUMNS.iter().map(|(name, _)| name))?;
+    for row in rows {
+        writer.write_record(row.values().iter().map(ToString::to_string))?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(feature = "parquet-export")]
+pub use self::arrow::{to_record_batch, write_parquet};
+
+#[cfg(feature = "parquet-export")]
+mod arrow {
+    use super::{ColumnType, ExportRecord, FieldValue};
+    use anyhow::{anyhow, Result};
+    use arrow_array::{
+        ArrayRef, BooleanArray, Float64Array, Int64Array, RecordBatch, StringArray, UInt64Array,
+    };
+    use arrow_schema::{DataType, Field, Schema};
+    use parquet::arrow::ArrowWriter;
+    use std::{io::Write, sync::Arc};
+
+    // One non-nullable column per `T::COLUMNS` entry
+    pub fn to_record_batch<T: ExportRecord>(rows: &[T]) -> Result<RecordBatch> {
+        let mut columns: Vec<Vec<FieldValue>> = T::COLUMNS.iter().map(|_| Vec::new()).collect();
+        for row in rows {
+            for (column, value) in columns.iter_mut().zip(row.values()) {
+                column.push(value);
+            }
+        }
+
+        let fields: Vec<Field> = T::COLUMNS
+            .iter()
+            .map(|(name, column_type)| Field::new(*name, data_type(*column_type), false))
+            .collect();
+        let arrays = T::COLUMNS
+            .iter()
+            .zip(columns)
+            .map(|((name, column_type), values)| array(name, *column_type, values))
+            .collect::<Result<Vec<ArrayRef>>>()?;
+        Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+    }
+
+    pub fn write_parquet<W: Write + Send, T: ExportRecord>(writer: W, rows: &[T]) -> Result<()> {
+        let batch = to_record_batch(rows)?;
+        let mut writer = ArrowWriter::try_new(writer, batch.schema(), None)?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+
+    const fn data_type(column_type: ColumnType) -> DataType {
+        match column_type {
+            ColumnType::UInt64 => DataType::UInt64,
+            ColumnType::Int64 => DataType::Int64,
+            ColumnType::Float64 => DataType::Float64,
+            ColumnType::Boolean => DataType::Boolean,
+            ColumnType::Utf8 => DataType::Utf8,
+        }
+    }
+
+    fn array(name: &str, column_type: ColumnType, values: Vec<FieldValue>) -> Result<ArrayRef> {
+        let values = values.into_iter();
+        let array: Option<ArrayRef> = match column_type {
+            ColumnType::UInt64 => typed(values, |value| match value {
+                FieldValue::UInt64(value) => Some(value),
+                _ => None,
+            })
+            .map(|values| Arc::new(UInt64Array::from(values)) as ArrayRef),
+            ColumnType::Int64 => typed(values, |value| match value {
+                FieldValue::Int64(value) => Some(value),
+                _ => None,
+            })
+            .map(|values| Arc::new(Int64Array::from(values)) as ArrayRef),
+            ColumnType::Float64 => typed(values, |value| match value {
+                FieldValue::Float64(value) => Some(value),
+                _ => None,
+            })
+            .map(|values| Arc::new(Float64Array::from(values)) as ArrayRef),
+            ColumnType::Boolean => typed(values, |value| match value {
+                FieldValue::Boolean(value) => Some(value),
+                _ => None,
+            })
+            .map(|values| Arc::new(BooleanArray::from(values)) as ArrayRef),
+            ColumnType::Utf8 => typed(values, |value| match value {
+                FieldValue::Utf8(value) => Some(value),
+                _ => None,
+            })
+            .map(|values| Arc::new(StringArray::from(values)) as ArrayRef),
+        };
+        array.ok_or_else(|| anyhow!("column {name} is not of type {column_type:?}"))
+    }
+
+    // `None` if any value is of another type
+    fn typed<T>(
+        values: impl Iterator<Item = FieldValue>,
+        f: impl Fn(FieldValue) -> Option<T>,
+    ) -> Option<Vec<T>> {
+        values.map(f).collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::write_csv;
+    use crate::model::TradeHistory;
+    use anyhow::Result;
+    use serde_json::from_str;
+
+    #[test]
+    fn trades_as_csv() -> Result<()> {
+        let trades: Vec<TradeHistory> = from_str(
+            r#"[{"symbol":"BNBBTC","id":28457,"orderId":100234,"price":"4.00000100","qty":"12.00000000","commission":"10.10000000","commissionAsset":"BNB","time":1499865549590,"isBuyer":true,"isMaker":false,"isBestMatch":true}]"#,
+        )?;
+        let mut csv = Vec::new();
+        write_csv(&mut csv, &trades)?;
+        assert_eq!(
+            String::from_utf8(csv)?,
+            "symbol,id,order_id,price,qty,commission,commission_asset,time,is_buyer,is_maker,is_best_match\nBNBBTC,28457,100234,4.000001,12,10.1,BNB,1499865549590,true,false,true\n"
+        );
+        Ok(())
+    }
+
+    #[cfg(feature = "parquet-export")]
+    #[test]
+    fn agg_trades_as_parquet() -> Result<()> {
+        let trades: Vec<crate::model::AggTrade> = from_str(
+            r#"[{"a":26129,"p":"0.01633102","q":"4.70443515","f":27781,"l":27781,"T":1498793709153,"m":true,"M":true},{"a":26130,"p":"0.01633103","q":"1.5","f":27782,"l":27783,"T":1498793709154,"m":false,"M":true}]"#,
+        )?;
+        let batch = super::to_record_batch(&trades)?;
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.num_columns(), 8);
+        assert_eq!(batch.schema().field(5).name(), "time");
+
+        let mut parquet = Vec::new();
+        super::write_parquet(&mut parquet, &trades)?;
+        assert!(parquet.starts_with(b"PAR1"));
+        Ok(())
+    }
+}
@@ -0,0 +1,256 @@
+//! Loader for the public market data archives published on <https://data.binance.vision>.
+//!
+//! Archives are zipped CSV files without the REST API rate limits, which makes them the
+//! preferred source for large backtests. Rows are parsed into the same models the REST
+//! endpoints return, with timestamps normalized to milliseconds.
+
+use crate::model::{
+    timestamp::{self, Millis},
+    AggTrade, HistoricalTrade, KlineSummary,
+};
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use csv::{ReaderBuilder, StringRecord};
+use log::debug;
+use std::io::{Cursor, Read};
+use std::str::FromStr;
+use zip::ZipArchive;
+
+const BASE: &str = "https://data.binance.vision/data/spot";
+
+// Timestamps above this value are in microseconds rather than milliseconds
+const MICROSECOND_THRESHOLD: u64 = 100_000_000_000_000;
+
+#[derive(Debug, Clone, Copy)]
+pub enum ArchivePeriod {
+    Daily(NaiveDate),
+    Monthly(i32, u32), // year, month
+}
+
+impl ArchivePeriod {
+    const fn frequency(self) -> &'static str {
+        match self {
+            Self::Daily(..) => "daily",
+            Self::Monthly(..) => "monthly",
+        }
+    }
+
+    fn suffix(self) -> String {
+        match self {
+            Self::Daily(date) => date.format("%Y-%m-%d").to_string(),
+            Self::Monthly(year, month) => format!("{year:04}-{month:02}"),
+        }
+    }
+}
+
+#[derive(Clone, Default, Debug)]
+pub struct HistoricalData {
+    client: reqwest::Client,
+}
+
+impl HistoricalData {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub async fn klines(
+        &self,
+        symbol: &str,
+        interval: &str,
+        period: ArchivePeriod,
+    ) -> Result<Vec<KlineSummary>> {
+        let symbol = symbol.to_uppercase();
+        let url = format!(
+            "{BASE}/{}/klines/{symbol}/{interval}/{symbol}-{interval}-{}.zip",
+            period.frequency(),
+            period.suffix()
+        );
+        parse_klines(self.download(&url).await?.as_slice())
+    }
+
+    pub async fn trades(
+        &self,
+        symbol: &str,
+        period: ArchivePeriod,
+    ) -> Result<Vec<HistoricalTrade>> {
+        let symbol = symbol.to_uppercase();
+        let url = format!(
+            "{BASE}/{}/trades/{symbol}/{symbol}-trades-{}.zip",
+            period.frequency(),
+            period.suffix()
+        );
+        parse_trades(self.download(&url).await?.as_slice())
+    }
+
+    pub async fn agg_trades(&self, symbol: &str, period: ArchivePeriod) -> Result<Vec<AggTrade>> {
+        let symbol = symbol.to_uppercase();
+        let url = format!(
+            "{BASE}/{}/aggTrades/{symbol}/{symbol}-aggTrades-{}.zip",
+            period.frequency(),
+            period.suffix()
+        );
+        parse_agg_trades(self.download(&url).await?.as_slice())
+    }
+
+    // Fetches a zip archive and returns its single CSV entry
+    async fn download(&self, url: &str) -> Result<Vec<u8>> {
+        debug!("url: {url}");
+        let bytes = self
+            .client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+        let mut csv = Vec::new();
+        archive.by_index(0)?.read_to_end(&mut csv)?;
+        Ok(csv)
+    }
+}
+
+pub fn parse_klines<R: Read>(csv: R) -> Result<Vec<KlineSummary>> {
+    records(csv)?
+        .iter()
+        .map(|row| {
+            Ok(KlineSummary {
+                open_time: time(row, 0)?,
+                open: field(row, 1)?,
+                high: field(row, 2)?,
+                low: field(row, 3)?,
+                close: field(row, 4)?,
+                volume: field(row, 5)?,
+                close_time: time(row, 6)?,
+                quote_asset_volume: field(row, 7)?,
+                number_of_trades: field(row, 8)?,
+                taker_buy_base_asset_volume: field(row, 9)?,
+                taker_buy_quote_asset_volume: field(row, 10)?,
+            })
+        })
+        .collect()
+}
+
+pub fn parse_trades<R: Read>(csv: R) -> Result<Vec<HistoricalTrade>> {
+    records(csv)?
+        .iter()
+        .map(|row| {
+            Ok(HistoricalTrade {
+                id: field(row, 0)?,
+                price: field(row, 1)?,
+                qty: field(row, 2)?,
+                quote_qty: field(row, 3)?,
+                time: time(row, 4)?,
+                is_buyer_maker: flag(row, 5)?,
+                is_best_match: flag(row, 6)?,
+            })
+        })
+        .collect()
+}
+
+pub fn parse_agg_trades<R: Read>(csv: R) -> Result<Vec<AggTrade>> {
+    records(csv)?
+        .iter()
+        .map(|row| {
+            Ok(AggTrade {
+                aggregated_trade_id: field(row, 0)?,
+                price: field(row, 1)?,
+                qty: field(row, 2)?,
+                first_trade_id: field(row, 3)?,
+                last_trade_id: field(row, 4)?,
+                time: time(row, 5)?,
+                is_buyer_maker: flag(row, 6)?,
+                is_best_match: flag(row, 7)?,
+            })
+        })
+        .collect()
+}
+
+// Reads every data row, skipping the header line some archives start with
+fn records<R: Read>(csv: R) -> Result<Vec<StringRecord>> {
+    let mut reader = ReaderBuilder::new().has_headers(false).from_reader(csv);
+    let mut rows = Vec::new();
+    for row in reader.records() {
+        let row = row?;
+        if row.get(0).is_some_and(|f| f.parse::<i64>().is_ok()) {
+            rows.push(row);
+        }
+    }
+    Ok(rows)
+}
+
+fn field<T>(row: &StringRecord, index: usize) -> Result<T>
+where
+    T: FromStr,
+    T::Err: std::error::Error + Send + Sync + 'static,
+{
+    let value = row
+        .get(index)
+        .ok_or_else(|| anyhow!("missing column {index} in {row:?}"))?;
+    Ok(value.trim().parse()?)
+}
+
+fn time<T: Millis>(row: &StringRecord, index: usize) -> Result<T> {
+    let ts: u64 = field(row, index)?;
+    let millis = if ts >= MICROSECOND_THRESHOLD {
+        ts / 1000
+    } else {
+        ts
+    };
+    Ok(timestamp::from_millis(millis)?)
+}
+
+fn flag(row: &StringRecord, index: usize) -> Result<bool> {
+    Ok(field::<String>(row, index)?.eq_ignore_ascii_case("true"))
+}
+
+#[cfg(test)]
+mod test {
+    use super::{parse_agg_trades, parse_klines, parse_trades, ArchivePeriod};
+    use crate::model::timestamp::to_millis;
+    use anyhow::Result;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn archive_period_suffix() {
+        let day = NaiveDate::from_ymd_opt(2024, 1, 5).unwrap();
+        assert_eq!(ArchivePeriod::Daily(day).suffix(), "2024-01-05");
+        assert_eq!(ArchivePeriod::Monthly(2024, 3).suffix(), "2024-03");
+    }
+
+    #[test]
+    fn klines_csv() -> Result<()> {
+        let csv = "open_time,open,high,low,close,volume,close_time,quote_volume,count,taker_buy_volume,taker_buy_quote_volume,ignore\n\
+                   1704067200000,42283.58,42298.62,42261.02,42298.61,35.92724,1704067259999,1519032.07,1327,19.20903,812122.73,0\n\
+                   1735689600000000,93576.00,93610.93,93537.50,93610.93,8.21827,1735689659999999,768978.06,1785,3.71984,348049.27,0\n";
+        let klines = parse_klines(csv.as_bytes())?;
+        assert_eq!(klines.len(), 2);
+        assert_eq!(to_millis(&klines[0].open_time), 1_704_067_200_000);
+        assert_eq!(klines[0].number_of_trades, 1327);
+        assert_eq!(to_millis(&klines[1].open_time), 1_735_689_600_000);
+        assert_eq!(to_millis(&klines[1].close_time), 1_735_689_659_999);
+        Ok(())
+    }
+
+    #[test]
+    fn trades_csv() -> Result<()> {
+        let csv = "3357451513,42283.58000000,0.00104000,43.97492320,1704067200357,False,True\n";
+        let trades = parse_trades(csv.as_bytes())?;
+        assert_eq!(trades[0].id, 3_357_451_513);
+        assert!(!trades[0].is_buyer_maker);
+        assert!(trades[0].is_best_match);
+        Ok(())
+    }
+
+    #[test]
+    fn agg_trades_csv() -> Result<()> {
+        let csv =
+            "2964493766,42283.58000000,0.00104000,3357451513,3357451513,1704067200357,True,True\n";
+        let trades = parse_agg_trades(csv.as_bytes())?;
+        assert_eq!(trades[0].aggregated_trade_id, 2_964_493_766);
+        assert_eq!(to_millis(&trades[0].time), 1_704_067_200_357);
+        assert!(trades[0].is_buyer_maker);
+        Ok(())
+    }
+}
@@ -1,10 +1,49 @@
 #![warn(clippy::all, clippy::pedantic, clippy::nursery)]
 #![allow(clippy::missing_errors_doc)]
 
+#[cfg(all(
+    not(target_arch = "wasm32"),
+    not(any(feature = "native-tls", feature = "rustls"))
+))]
+compile_error!("enable the `native-tls` or the `rustls` feature to select a TLS backend");
+
+#[cfg(not(target_arch = "wasm32"))]
+pub mod analytics;
+mod cache;
+pub mod candles;
 mod client;
+pub mod depth;
 pub mod error;
+#[cfg(all(feature = "execution", not(target_arch = "wasm32")))]
+pub mod execution;
+#[cfg(feature = "export")]
+pub mod export;
+#[cfg(feature = "historical-data")]
+pub mod historical_data;
 pub mod model;
+pub mod monitor;
+pub mod portfolio;
+pub mod proxy;
+#[cfg(all(feature = "recorder", not(target_arch = "wasm32")))]
+pub mod recorder;
+pub mod router;
+pub mod scheduler;
+pub mod signing;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod simulation;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod testing;
+pub mod tracker;
 mod transport;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod user_data;
 mod tests;
 
-pub use crate::client::{websocket::BinanceWebsocket, Binance};
+pub use crate::client::{Binance, BinanceBuilder, MarketData, Trading, UserStream};
+pub use crate::transport::{Endpoint, Host, Region, Version};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::client::websocket::{BinanceWebsocket, WsConfig};
+#[cfg(not(target_arch = "wasm32"))]
+pub use crate::client::ws_api::WebsocketApi;
+#[cfg(all(feature = "browser-websocket", target_arch = "wasm32"))]
+pub use crate::client::browser_websocket::BrowserWebsocket;
@@ -0,0 +1,249 @@
+use super::{string_or_float, timestamp, Side, Timestamp};
+use serde::{Deserialize, Serialize};
+
+// Slices `quantity` into sub-orders spread evenly over `duration` seconds (300 to 86400)
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TwapOrderRequest {
+    pub symbol: String,
+    pub side: Side,
+    pub quantity: f64,
+    pub duration: u32,
+    pub client_algo_id: Option<String>,
+    // Sub-orders are not filled beyond this price
+    pub limit_price: Option<f64>,
+}
+
+impl TwapOrderRequest {
+    #[must_use]
+    pub fn new(symbol: &str, side: Side, quantity: f64, duration: u32) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            side,
+            quantity,
+            duration,
+            client_algo_id: None,
+            limit_price: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn limit_price(mut self, price: f64) -> Self {
+        self.limit_price = Some(price);
+        self
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PositionSide {
+    Both,
+    Long,
+    Short,
+}
+
+// Share of the market volume a volume participation order may take
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AlgoUrgency {
+    Low,
+    Medium,
+    High,
+}
+
+// Futures algo order: volume participation when `urgency` is set, TWAP over `duration` otherwise
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesAlgoOrderRequest {
+    pub symbol: String,
+    pub side: Side,
+    pub position_side: Option<PositionSide>,
+    pub quantity: f64,
+    pub urgency: Option<AlgoUrgency>,
+    pub duration: Option<u32>,
+    pub client_algo_id: Option<String>,
+    pub reduce_only: Option<bool>,
+    pub limit_price: Option<f64>,
+}
+
+impl FuturesAlgoOrderRequest {
+    #[must_use]
+    pub fn volume_participation(
+        symbol: &str,
+        side: Side,
+        quantity: f64,
+        urgency: AlgoUrgency,
+    ) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            side,
+            position_side: None,
+            quantity,
+            urgency: Some(urgency),
+            duration: None,
+            client_algo_id: None,
+            reduce_only: None,
+            limit_price: None,
+        }
+    }
+
+    #[must_use]
+    pub fn twap(symbol: &str, side: Side, quantity: f64, duration: u32) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            side,
+            position_side: None,
+            quantity,
+            urgency: None,
+            duration: Some(duration),
+            client_algo_id: None,
+            reduce_only: None,
+            limit_price: None,
+        }
+    }
+
+    // Required in hedge mode
+    #[must_use]
+    pub const fn position_side(mut self, position_side: PositionSide) -> Self {
+        self.position_side = Some(position_side);
+        self
+    }
+
+    #[must_use]
+    pub const fn reduce_only(mut self) -> Self {
+        self.reduce_only = Some(true);
+        self
+    }
+
+    #[must_use]
+    pub const fn limit_price(mut self, price: f64) -> Self {
+        self.limit_price = Some(price);
+        self
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NewAlgoOrder {
+    pub client_algo_id: String,
+    pub success: bool,
+    pub code: i64,
+    pub msg: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CanceledAlgoOrder {
+    pub algo_id: u64,
+    pub success: bool,
+    pub code: i64,
+    pub msg: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgoOrders {
+    pub total: u64,
+    pub orders: Vec<AlgoOrder>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgoOrder {
+    pub algo_id: u64,
+    pub symbol: String,
+    pub side: Side,
+    pub position_side: Option<PositionSide>,
+    #[serde(with = "string_or_float")]
+    pub total_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub executed_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub executed_amt: f64,
+    #[serde(with = "string_or_float")]
+    pub avg_price: f64,
+    pub client_algo_id: String,
+    #[serde(with = "timestamp")]
+    pub book_time: Timestamp,
+    #[serde(with = "timestamp")]
+    pub end_time: Timestamp,
+    pub algo_status: String,
+    pub algo_type: String,
+    pub urgency: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgoSubOrders {
+    pub total: u64,
+    #[serde(with = "string_or_float")]
+    pub executed_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub executed_amt: f64,
+    pub sub_orders: Vec<AlgoSubOrder>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgoSubOrder {
+    pub algo_id: u64,
+    pub order_id: u64,
+    pub order_status: String,
+    #[serde(with = "string_or_float")]
+    pub executed_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub executed_amt: f64,
+    #[serde(with = "string_or_float")]
+    pub fee_amt: f64,
+    pub fee_asset: String,
+    #[serde(with = "timestamp")]
+    pub book_time: Timestamp,
+    #[serde(with = "string_or_float")]
+    pub avg_price: f64,
+    pub side: Side,
+    pub symbol: String,
+    pub sub_id: u64,
+    pub time_in_force: String,
+    #[serde(with = "string_or_float")]
+    pub orig_qty: f64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AlgoOrders, AlgoSubOrders, AlgoUrgency, FuturesAlgoOrderRequest};
+    use crate::model::Side;
+    use anyhow::Result;
+    use serde_json::{from_str, json, to_value};
+
+    #[test]
+    fn algo_orders() -> Result<()> {
+        let json = r#"{"total":1,"orders":[{"algoId":14517,"symbol":"ETHUSDT","side":"SELL","totalQty":"5.000","executedQty":"0.000","executedAmt":"0.00000000","avgPrice":"0.00","clientAlgoId":"d7096549481642f8a0bb69e9e2e31f2e","bookTime":1649756817004,"endTime":0,"algoStatus":"WORKING","algoType":"VP","urgency":"LOW"}]}"#;
+        let orders: AlgoOrders = from_str(json)?;
+        assert_eq!(orders.orders[0].algo_id, 14517);
+        Ok(())
+    }
+
+    #[test]
+    fn algo_sub_orders() -> Result<()> {
+        let json = r#"{"total":1,"executedQty":"1.000","executedAmt":"3229.44000000","subOrders":[{"algoId":13723,"orderId":8389765519993908929,"orderStatus":"FILLED","executedQty":"1.000","executedAmt":"3229.44000000","feeAmt":"-1.61471999","feeAsset":"USDT","bookTime":1649319001964,"avgPrice":"3229.44","side":"SELL","symbol":"ETHUSDT","subId":1,"timeInForce":"IMMEDIATE_OR_CANCEL","origQty":"1.000"}]}"#;
+        let sub_orders: AlgoSubOrders = from_str(json)?;
+        assert_eq!(sub_orders.sub_orders[0].sub_id, 1);
+        Ok(())
+    }
+
+    #[test]
+    fn futures_algo_order_request() -> Result<()> {
+        let vp = FuturesAlgoOrderRequest::volume_participation(
+            "btcusdt",
+            Side::Buy,
+            1.5,
+            AlgoUrgency::High,
+        )
+        .reduce_only();
+        let value = to_value(vp)?;
+        assert_eq!(value["urgency"], json!("HIGH"));
+        assert_eq!(value["duration"], json!(null));
+        assert_eq!(value["reduceOnly"], json!(true));
+        Ok(())
+    }
+}
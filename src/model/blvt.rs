@@ -0,0 +1,97 @@
+use super::{string_or_float, timestamp, Timestamp};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlvtInfo {
+    pub token_name: String,
+    pub description: String,
+    pub underlying: String,
+    #[serde(with = "string_or_float")]
+    pub token_issued: f64,
+    pub basket: String,
+    #[serde(default)]
+    pub current_baskets: Vec<BlvtBasket>,
+    #[serde(with = "string_or_float")]
+    pub nav: f64,
+    #[serde(with = "string_or_float")]
+    pub real_leverage: f64,
+    #[serde(with = "string_or_float")]
+    pub funding_rate: f64,
+    #[serde(with = "string_or_float")]
+    pub daily_management_fee: f64,
+    #[serde(with = "string_or_float")]
+    pub purchase_fee_pct: f64,
+    #[serde(with = "string_or_float")]
+    pub daily_purchase_limit: f64,
+    #[serde(with = "string_or_float")]
+    pub redeem_fee_pct: f64,
+    #[serde(with = "string_or_float")]
+    pub daily_redeem_limit: f64,
+    #[serde(with = "timestamp")]
+    pub timestamp: Timestamp,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlvtBasket {
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    #[serde(with = "string_or_float")]
+    pub notional_value: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlvtSubscription {
+    pub id: u64,
+    pub status: String,
+    pub token_name: String,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    #[serde(with = "string_or_float")]
+    pub cost: f64,
+    #[serde(with = "timestamp")]
+    pub timestamp: Timestamp,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlvtRedemption {
+    pub id: u64,
+    pub status: String,
+    pub token_name: String,
+    #[serde(with = "string_or_float")]
+    pub redeem_amount: f64,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    #[serde(with = "timestamp")]
+    pub timestamp: Timestamp,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BlvtUserLimit {
+    pub token_name: String,
+    #[serde(with = "string_or_float")]
+    pub user_daily_total_purchase_limit: f64,
+    #[serde(with = "string_or_float")]
+    pub user_daily_total_redeem_limit: f64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::BlvtInfo;
+    use anyhow::Result;
+    use serde_json::from_str;
+
+    #[test]
+    fn blvt_info() -> Result<()> {
+        let json = r#"[{"tokenName":"BTCDOWN","description":"3X Short Bitcoin Token","underlying":"BTC","tokenIssued":"717953.95","basket":"-821.474 BTCUSDT Futures","currentBaskets":[{"symbol":"BTCUSDT","amount":"-183.321","notionalValue":"-22601.48759"}],"nav":"4.79081327","realLeverage":"4.29","fundingRate":"0.001087","dailyManagementFee":"0.0001","purchaseFeePct":"0.0010","dailyPurchaseLimit":"100000","redeemFeePct":"0.0010","dailyRedeemLimit":"1000000","timestamp":1583127900000}]"#;
+        let info: Vec<BlvtInfo> = from_str(json)?;
+        assert_eq!(info[0].token_name, "BTCDOWN");
+        assert_eq!(info[0].current_baskets[0].symbol, "BTCUSDT");
+        Ok(())
+    }
+}
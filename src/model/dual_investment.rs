@@ -0,0 +1,132 @@
+use super::{string_or_float, timestamp, Timestamp};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DualInvestmentOptionType {
+    Call,
+    Put,
+}
+
+// How a settled position is rolled into the next product
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AutoCompoundPlan {
+    #[default]
+    None,
+    Standard,
+    Advance,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DualInvestmentPage<T> {
+    pub total: u64,
+    pub list: Vec<T>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DualInvestmentProduct {
+    pub id: String,
+    pub invest_coin: String,
+    pub exercised_coin: String,
+    #[serde(with = "string_or_float")]
+    pub strike_price: f64,
+    pub duration: u32,
+    #[serde(with = "timestamp")]
+    pub settle_date: Timestamp,
+    pub purchase_decimal: u32,
+    #[serde(with = "timestamp")]
+    pub purchase_end_time: Timestamp,
+    pub can_purchase: bool,
+    #[serde(with = "string_or_float")]
+    pub apr: f64,
+    pub order_id: u64,
+    #[serde(with = "string_or_float")]
+    pub min_amount: f64,
+    #[serde(with = "string_or_float")]
+    pub max_amount: f64,
+    pub option_type: DualInvestmentOptionType,
+    #[serde(default)]
+    pub is_auto_compound_enable: bool,
+    #[serde(default)]
+    pub auto_compound_plan_list: Vec<AutoCompoundPlan>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DualInvestmentPosition {
+    pub id: String,
+    pub invest_coin: String,
+    pub exercised_coin: String,
+    #[serde(with = "string_or_float")]
+    pub subscription_amount: f64,
+    #[serde(with = "string_or_float")]
+    pub strike_price: f64,
+    pub duration: u32,
+    #[serde(with = "timestamp")]
+    pub settle_date: Timestamp,
+    pub purchase_status: String,
+    #[serde(with = "string_or_float")]
+    pub apr: f64,
+    pub order_id: u64,
+    #[serde(with = "timestamp")]
+    pub purchase_end_time: Timestamp,
+    pub option_type: DualInvestmentOptionType,
+    #[serde(default)]
+    pub auto_compound_plan: AutoCompoundPlan,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DualInvestmentSubscription {
+    pub position_id: u64,
+    pub invest_coin: String,
+    pub exercised_coin: String,
+    #[serde(with = "string_or_float")]
+    pub subscription_amount: f64,
+    pub duration: u32,
+    #[serde(default)]
+    pub auto_compound_plan: AutoCompoundPlan,
+    #[serde(with = "string_or_float")]
+    pub strike_price: f64,
+    #[serde(with = "timestamp")]
+    pub settle_date: Timestamp,
+    pub purchase_status: String,
+    #[serde(with = "string_or_float")]
+    pub apr: f64,
+    pub order_id: u64,
+    #[serde(with = "timestamp")]
+    pub purchase_time: Timestamp,
+    pub option_type: DualInvestmentOptionType,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoCompoundStatus {
+    pub position_id: String,
+    pub auto_compound_plan: AutoCompoundPlan,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        AutoCompoundPlan, DualInvestmentOptionType, DualInvestmentPage, DualInvestmentProduct,
+    };
+    use anyhow::Result;
+    use serde_json::from_str;
+
+    #[test]
+    fn dual_investment_products() -> Result<()> {
+        let json = r#"{"total":1,"list":[{"id":"741590","investCoin":"USDT","exercisedCoin":"BNB","strikePrice":"380","duration":4,"settleDate":1709020800000,"purchaseDecimal":8,"purchaseEndTime":1708934400000,"canPurchase":true,"apr":"0.6076","orderId":8257205859,"minAmount":"0.1","maxAmount":"25265.7","createTimestamp":1708560798000,"optionType":"PUT","isAutoCompoundEnable":true,"autoCompoundPlanList":["STANDARD","ADVANCE"]}]}"#;
+        let products: DualInvestmentPage<DualInvestmentProduct> = from_str(json)?;
+        let product = &products.list[0];
+        assert_eq!(product.option_type, DualInvestmentOptionType::Put);
+        assert_eq!(
+            product.auto_compound_plan_list,
+            [AutoCompoundPlan::Standard, AutoCompoundPlan::Advance]
+        );
+        Ok(())
+    }
+}
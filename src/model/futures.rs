@@ -0,0 +1,422 @@
+use super::{
+    algo::PositionSide, string_or_float, timestamp, OrderType, Side, TimeInForce, Timestamp,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesOrderRequest {
+    pub symbol: String,
+    pub side: Side,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    // Required in hedge mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position_side: Option<PositionSide>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_in_force: Option<TimeInForce>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce_only: Option<bool>,
+    // Millisecond timestamp a GTD order is canceled at
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub good_till_date: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_client_order_id: Option<String>,
+}
+
+impl FuturesOrderRequest {
+    #[must_use]
+    pub fn market(symbol: &str, side: Side, quantity: f64) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            side,
+            order_type: OrderType::Market,
+            position_side: None,
+            quantity: Some(quantity),
+            price: None,
+            time_in_force: None,
+            reduce_only: None,
+            good_till_date: None,
+            new_client_order_id: None,
+        }
+    }
+
+    #[must_use]
+    pub fn limit(symbol: &str, side: Side, quantity: f64, price: f64) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            side,
+            order_type: OrderType::Limit,
+            position_side: None,
+            quantity: Some(quantity),
+            price: Some(price),
+            time_in_force: Some(TimeInForce::GTC),
+            reduce_only: None,
+            good_till_date: None,
+            new_client_order_id: None,
+        }
+    }
+
+    // LIMIT order that is canceled instead of taking liquidity
+    #[must_use]
+    pub fn post_only(symbol: &str, side: Side, quantity: f64, price: f64) -> Self {
+        Self::limit(symbol, side, quantity, price).time_in_force(TimeInForce::GTX)
+    }
+
+    #[must_use]
+    pub const fn time_in_force(mut self, time_in_force: TimeInForce) -> Self {
+        self.time_in_force = Some(time_in_force);
+        self
+    }
+
+    // Required in hedge mode
+    #[must_use]
+    pub const fn position_side(mut self, position_side: PositionSide) -> Self {
+        self.position_side = Some(position_side);
+        self
+    }
+
+    // Keep the order until `timestamp` (milliseconds), which has to be at least 600 seconds
+    // away when placing it
+    #[must_use]
+    pub const fn good_till_date(mut self, timestamp: u64) -> Self {
+        self.time_in_force = Some(TimeInForce::GTD);
+        self.good_till_date = Some(timestamp);
+        self
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesOrder {
+    pub symbol: String,
+    pub order_id: u64,
+    pub client_order_id: String,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    #[serde(default, with = "string_or_float")]
+    pub avg_price: f64,
+    #[serde(with = "string_or_float")]
+    pub orig_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub executed_qty: f64,
+    pub status: String,
+    pub time_in_force: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub side: Side,
+    #[serde(default)]
+    pub reduce_only: bool,
+    #[serde(with = "timestamp")]
+    pub update_time: Timestamp,
+    // Fields the model does not cover yet, e.g. `positionSide` or `workingType`
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MarginType {
+    Isolated,
+    Crossed,
+}
+
+// `true` in hedge mode (separate LONG and SHORT positions), `false` in one-way mode
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionMode {
+    pub dual_side_position: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Leverage {
+    pub symbol: String,
+    pub leverage: u8,
+    #[serde(with = "string_or_float")]
+    pub max_notional_value: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRate {
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub funding_rate: f64,
+    #[serde(with = "timestamp")]
+    pub funding_time: Timestamp,
+    // Empty for funding events before the mark price was recorded
+    #[serde(default)]
+    pub mark_price: String,
+}
+
+// Mark price, index price and funding of ONE symbol
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PremiumIndex {
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub mark_price: f64,
+    #[serde(with = "string_or_float")]
+    pub index_price: f64,
+    #[serde(with = "string_or_float")]
+    pub estimated_settle_price: f64,
+    #[serde(with = "string_or_float")]
+    pub last_funding_rate: f64,
+    #[serde(with = "string_or_float")]
+    pub interest_rate: f64,
+    #[serde(with = "timestamp")]
+    pub next_funding_time: Timestamp,
+    #[serde(with = "timestamp")]
+    pub time: Timestamp,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterest {
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub sum_open_interest: f64,
+    #[serde(with = "string_or_float")]
+    pub sum_open_interest_value: f64,
+    #[serde(with = "timestamp")]
+    pub timestamp: Timestamp,
+}
+
+// Share of accounts (or of positions of the top traders) long and short
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LongShortRatio {
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub long_short_ratio: f64,
+    #[serde(with = "string_or_float")]
+    pub long_account: f64,
+    #[serde(with = "string_or_float")]
+    pub short_account: f64,
+    #[serde(with = "timestamp")]
+    pub timestamp: Timestamp,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TakerLongShortRatio {
+    #[serde(with = "string_or_float")]
+    pub buy_sell_ratio: f64,
+    #[serde(with = "string_or_float")]
+    pub buy_vol: f64,
+    #[serde(with = "string_or_float")]
+    pub sell_vol: f64,
+    #[serde(with = "timestamp")]
+    pub timestamp: Timestamp,
+}
+
+// A composite index and the weights of its base assets
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexInfo {
+    pub symbol: String,
+    #[serde(with = "timestamp")]
+    pub time: Timestamp,
+    pub component: String,
+    pub base_asset_list: Vec<IndexComponent>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexComponent {
+    pub base_asset: String,
+    pub quote_asset: String,
+    #[serde(with = "string_or_float")]
+    pub weight_in_quantity: f64,
+    #[serde(with = "string_or_float")]
+    pub weight_in_percentage: f64,
+}
+
+// The spot markets the index price of ONE symbol is computed from
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexConstituents {
+    pub symbol: String,
+    #[serde(with = "timestamp")]
+    pub time: Timestamp,
+    pub constituents: Vec<IndexConstituent>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexConstituent {
+    pub exchange: String,
+    pub symbol: String,
+    // Fields the model does not cover yet, e.g. `price` or `weight`
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+// Wallet of the futures account, with the positions of ALL symbols
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesAccount {
+    #[serde(with = "string_or_float")]
+    pub total_wallet_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub total_unrealized_profit: f64,
+    #[serde(with = "string_or_float")]
+    pub total_margin_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub available_balance: f64,
+    pub assets: Vec<FuturesAsset>,
+    pub positions: Vec<FuturesPosition>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesAsset {
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub wallet_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub unrealized_profit: f64,
+    // Wallet balance plus unrealized profit
+    #[serde(with = "string_or_float")]
+    pub margin_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub available_balance: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesPosition {
+    pub symbol: String,
+    // Negative for short positions, zero for symbols without a position
+    #[serde(with = "string_or_float")]
+    pub position_amt: f64,
+    #[serde(with = "string_or_float")]
+    pub entry_price: f64,
+    #[serde(with = "string_or_float")]
+    pub unrealized_profit: f64,
+    #[serde(with = "string_or_float")]
+    pub leverage: f64,
+    #[serde(default)]
+    pub isolated: bool,
+    // BOTH in one-way mode, LONG or SHORT in hedge mode
+    pub position_side: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        FundingRate, FuturesAccount, FuturesOrder, FuturesOrderRequest, IndexConstituents,
+        IndexInfo, Leverage, LongShortRatio, OpenInterest, PremiumIndex, TakerLongShortRatio,
+    };
+    use crate::error::BinanceResponse;
+    use crate::model::Side;
+    use anyhow::Result;
+    use serde_json::{from_str, json, to_value};
+
+    #[test]
+    fn order_time_in_force() -> Result<()> {
+        let post_only = to_value(FuturesOrderRequest::post_only(
+            "BTCUSDT",
+            Side::Buy,
+            1.0,
+            9000.0,
+        ))?;
+        assert_eq!(post_only["timeInForce"], json!("GTX"));
+
+        let order = FuturesOrderRequest::limit("BTCUSDT", Side::Sell, 1.0, 9500.0)
+            .good_till_date(1_693_207_680_000);
+        let order = to_value(order)?;
+        assert_eq!(order["timeInForce"], json!("GTD"));
+        assert_eq!(order["goodTillDate"], json!(1_693_207_680_000_u64));
+        Ok(())
+    }
+
+    #[test]
+    fn batch_order_results() -> Result<()> {
+        let json = r#"[{"clientOrderId":"testOrder","cumQty":"0","cumQuote":"0","executedQty":"0","orderId":22542179,"avgPrice":"0.00000","origQty":"10","price":"0","reduceOnly":false,"side":"BUY","positionSide":"SHORT","status":"NEW","stopPrice":"9300","symbol":"BTCUSDT","timeInForce":"GTD","type":"TRAILING_STOP_MARKET","origType":"TRAILING_STOP_MARKET","activatePrice":"9020","priceRate":"0.3","updateTime":1566818724722,"workingType":"CONTRACT_PRICE","priceProtect":false,"priceMatch":"NONE","selfTradePreventionMode":"NONE","goodTillDate":1693207680000},{"code":-2022,"msg":"ReduceOnly Order is rejected."}]"#;
+        let results: Vec<BinanceResponse<FuturesOrder>> = from_str(json)?;
+        let mut results = results.into_iter().map(BinanceResponse::into_result);
+        let order = results.next().unwrap()?;
+        assert_eq!(order.order_id, 22_542_179);
+        assert_eq!(order.extra["positionSide"], "SHORT");
+        assert!(results.next().unwrap().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn leverage() -> Result<()> {
+        let leverage: Leverage =
+            from_str(r#"{"leverage":21,"maxNotionalValue":"1000000","symbol":"BTCUSDT"}"#)?;
+        assert_eq!(leverage.leverage, 21);
+        assert!((leverage.max_notional_value - 1_000_000.0).abs() < f64::EPSILON);
+        Ok(())
+    }
+
+    #[test]
+    fn funding_and_mark_price() -> Result<()> {
+        let rates: Vec<FundingRate> = from_str(
+            r#"[{"symbol":"BTCUSDT","fundingRate":"-0.03750000","fundingTime":1570608000000,"markPrice":"34287.54619963"},{"symbol":"BTCUSDT","fundingRate":"0.00010000","fundingTime":1570636800000,"markPrice":""}]"#,
+        )?;
+        assert!((rates[0].funding_rate + 0.0375).abs() < f64::EPSILON);
+        assert!(rates[1].mark_price.is_empty());
+
+        let index: PremiumIndex = from_str(
+            r#"{"symbol":"BTCUSDT","markPrice":"11793.63104562","indexPrice":"11781.80495970","estimatedSettlePrice":"11781.16138815","lastFundingRate":"0.00038246","interestRate":"0.00010000","nextFundingTime":1597392000000,"time":1597370495002}"#,
+        )?;
+        assert!((index.mark_price - 11_793.631_045_62).abs() < 1e-9);
+        Ok(())
+    }
+
+    #[test]
+    fn futures_data() -> Result<()> {
+        let open_interest: Vec<OpenInterest> = from_str(
+            r#"[{"symbol":"BTCUSDT","sumOpenInterest":"20403.63700000","sumOpenInterestValue":"150570784.07809979","CMCCirculatingSupply":"165880.538","timestamp":"1583127900000"}]"#,
+        )?;
+        assert!((open_interest[0].sum_open_interest - 20_403.637).abs() < 1e-9);
+
+        let ratios: Vec<LongShortRatio> = from_str(
+            r#"[{"symbol":"BTCUSDT","longShortRatio":"1.8105","longAccount":"0.6442","shortAccount":"0.3558","timestamp":"1583139600000"}]"#,
+        )?;
+        assert!((ratios[0].long_account - 0.6442).abs() < f64::EPSILON);
+
+        let taker: Vec<TakerLongShortRatio> = from_str(
+            r#"[{"buySellRatio":"1.5586","buyVol":"387.3300","sellVol":"248.5030","timestamp":"1585614900000"}]"#,
+        )?;
+        assert!((taker[0].buy_sell_ratio - 1.5586).abs() < f64::EPSILON);
+        Ok(())
+    }
+
+    #[test]
+    fn index_composition() -> Result<()> {
+        let info: Vec<IndexInfo> = from_str(
+            r#"[{"symbol":"DEFIUSDT","time":1589437530011,"component":"baseAsset","baseAssetList":[{"baseAsset":"BAL","quoteAsset":"USDT","weightInQuantity":"1.04406228","weightInPercentage":"0.02783900"},{"baseAsset":"BAND","quoteAsset":"USDT","weightInQuantity":"3.53782729","weightInPercentage":"0.03935200"}]}]"#,
+        )?;
+        assert_eq!(info[0].base_asset_list.len(), 2);
+        assert!((info[0].base_asset_list[1].weight_in_percentage - 0.039_352).abs() < 1e-12);
+
+        let constituents: IndexConstituents = from_str(
+            r#"{"symbol":"BTCUSDT","time":1697421272043,"constituents":[{"exchange":"binance","symbol":"BTCUSDT","price":"27000.1","weight":"0.5"},{"exchange":"okex","symbol":"BTC-USDT","price":"27000.3","weight":"0.5"}]}"#,
+        )?;
+        assert_eq!(constituents.constituents[1].exchange, "okex");
+        assert_eq!(constituents.constituents[1].extra["weight"], "0.5");
+        Ok(())
+    }
+
+    #[test]
+    fn futures_account() -> Result<()> {
+        let account: FuturesAccount = from_str(
+            r#"{"feeTier":0,"canTrade":true,"totalInitialMargin":"0.00000000","totalWalletBalance":"103.12345678","totalUnrealizedProfit":"-1.20000000","totalMarginBalance":"101.92345678","availableBalance":"100.92345678","maxWithdrawAmount":"100.92345678","assets":[{"asset":"USDT","walletBalance":"103.12345678","unrealizedProfit":"-1.20000000","marginBalance":"101.92345678","maintMargin":"0.00000000","initialMargin":"1.00000000","availableBalance":"100.92345678","marginAvailable":true,"updateTime":1625474304765}],"positions":[{"symbol":"BTCUSDT","initialMargin":"1","maintMargin":"0","unrealizedProfit":"-1.20000000","positionInitialMargin":"1","openOrderInitialMargin":"0","leverage":"100","isolated":false,"entryPrice":"30000.0","maxNotional":"250000","bidNotional":"0","askNotional":"0","positionSide":"BOTH","positionAmt":"-0.003","updateTime":1625474304765}]}"#,
+        )?;
+        assert!((account.assets[0].margin_balance - 101.923_456_78).abs() < f64::EPSILON);
+        assert!((account.positions[0].position_amt + 0.003).abs() < f64::EPSILON);
+        assert_eq!(account.positions[0].position_side, "BOTH");
+        Ok(())
+    }
+}
@@ -0,0 +1,267 @@
+use super::{string_or_float, timestamp, OrderStatus, OrderType, Side, TimeInForce, Timestamp};
+use serde::{Deserialize, Serialize, Serializer};
+
+// Borrowing and repaying done by the exchange as part of a margin order
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SideEffectType {
+    NoSideEffect,
+    MarginBuy,
+    AutoRepay,
+    AutoBorrowRepay,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginOrderRequest {
+    pub symbol: String,
+    // Routes the order to the isolated margin account of `symbol`
+    #[serde(serialize_with = "upper_bool")]
+    pub is_isolated: bool,
+    pub side: Side,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    pub quantity: Option<f64>,
+    pub quote_order_qty: Option<f64>,
+    pub price: Option<f64>,
+    pub stop_price: Option<f64>,
+    pub time_in_force: Option<TimeInForce>,
+    pub side_effect_type: Option<SideEffectType>,
+    pub new_client_order_id: Option<String>,
+}
+
+impl MarginOrderRequest {
+    #[must_use]
+    pub fn market(symbol: &str, side: Side, quantity: f64) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            is_isolated: false,
+            side,
+            order_type: OrderType::Market,
+            quantity: Some(quantity),
+            quote_order_qty: None,
+            price: None,
+            stop_price: None,
+            time_in_force: None,
+            side_effect_type: None,
+            new_client_order_id: None,
+        }
+    }
+
+    #[must_use]
+    pub fn limit(symbol: &str, side: Side, quantity: f64, price: f64) -> Self {
+        Self {
+            order_type: OrderType::Limit,
+            price: Some(price),
+            time_in_force: Some(TimeInForce::GTC),
+            ..Self::market(symbol, side, quantity)
+        }
+    }
+
+    #[must_use]
+    pub const fn isolated(mut self) -> Self {
+        self.is_isolated = true;
+        self
+    }
+
+    #[must_use]
+    pub const fn side_effect(mut self, side_effect_type: SideEffectType) -> Self {
+        self.side_effect_type = Some(side_effect_type);
+        self
+    }
+}
+
+// One-cancels-the-other pair of a limit order and a stop-limit order
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginOcoOrderRequest {
+    pub symbol: String,
+    #[serde(serialize_with = "upper_bool")]
+    pub is_isolated: bool,
+    pub side: Side,
+    pub quantity: f64,
+    pub price: f64,
+    pub stop_price: f64,
+    pub stop_limit_price: Option<f64>,
+    pub stop_limit_time_in_force: Option<TimeInForce>,
+    pub list_client_order_id: Option<String>,
+    pub limit_client_order_id: Option<String>,
+    pub stop_client_order_id: Option<String>,
+    pub side_effect_type: Option<SideEffectType>,
+}
+
+impl MarginOcoOrderRequest {
+    // The stop leg triggers a market order unless `stop_limit_price` is set
+    #[must_use]
+    pub fn new(symbol: &str, side: Side, quantity: f64, price: f64, stop_price: f64) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            is_isolated: false,
+            side,
+            quantity,
+            price,
+            stop_price,
+            stop_limit_price: None,
+            stop_limit_time_in_force: None,
+            list_client_order_id: None,
+            limit_client_order_id: None,
+            stop_client_order_id: None,
+            side_effect_type: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn stop_limit(mut self, stop_limit_price: f64) -> Self {
+        self.stop_limit_price = Some(stop_limit_price);
+        self.stop_limit_time_in_force = Some(TimeInForce::GTC);
+        self
+    }
+
+    #[must_use]
+    pub const fn isolated(mut self) -> Self {
+        self.is_isolated = true;
+        self
+    }
+
+    #[must_use]
+    pub const fn side_effect(mut self, side_effect_type: SideEffectType) -> Self {
+        self.side_effect_type = Some(side_effect_type);
+        self
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginOrderResult {
+    pub symbol: String,
+    pub order_id: u64,
+    pub client_order_id: String,
+    #[serde(with = "timestamp")]
+    pub transact_time: Timestamp,
+    #[serde(default, with = "string_or_float")]
+    pub price: f64,
+    #[serde(default, with = "string_or_float")]
+    pub orig_qty: f64,
+    #[serde(default, with = "string_or_float")]
+    pub executed_qty: f64,
+    #[serde(default, with = "string_or_float")]
+    pub cummulative_quote_qty: f64,
+    pub status: Option<OrderStatus>,
+    pub time_in_force: Option<TimeInForce>,
+    #[serde(rename = "type")]
+    pub order_type: Option<OrderType>,
+    pub side: Option<Side>,
+    #[serde(default)]
+    pub is_isolated: bool,
+    #[serde(default, with = "string_or_float")]
+    pub margin_buy_borrow_amount: f64,
+    pub margin_buy_borrow_asset: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OcoOrder {
+    pub order_list_id: u64,
+    pub contingency_type: String,
+    pub list_status_type: String,
+    pub list_order_status: String,
+    pub list_client_order_id: String,
+    #[serde(with = "timestamp")]
+    pub transaction_time: Timestamp,
+    pub symbol: String,
+    #[serde(default)]
+    pub is_isolated: bool,
+    pub orders: Vec<OcoOrderLeg>,
+    #[serde(default)]
+    pub order_reports: Vec<MarginOrderResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OcoOrderLeg {
+    pub symbol: String,
+    pub order_id: u64,
+    pub client_order_id: String,
+}
+
+// Cross margin account, the values of the whole account are in BTC
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginAccount {
+    pub borrow_enabled: bool,
+    #[serde(with = "string_or_float")]
+    pub margin_level: f64,
+    #[serde(with = "string_or_float")]
+    pub total_asset_of_btc: f64,
+    #[serde(with = "string_or_float")]
+    pub total_liability_of_btc: f64,
+    #[serde(with = "string_or_float")]
+    pub total_net_asset_of_btc: f64,
+    pub trade_enabled: bool,
+    pub transfer_enabled: bool,
+    pub user_assets: Vec<MarginAsset>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginAsset {
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub borrowed: f64,
+    #[serde(with = "string_or_float")]
+    pub free: f64,
+    #[serde(with = "string_or_float")]
+    pub interest: f64,
+    #[serde(with = "string_or_float")]
+    pub locked: f64,
+    // Free and locked minus borrowed and interest
+    #[serde(with = "string_or_float")]
+    pub net_asset: f64,
+}
+
+// The margin endpoints expect "TRUE"/"FALSE" rather than JSON booleans
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn upper_bool<S: Serializer>(value: &bool, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(if *value { "TRUE" } else { "FALSE" })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MarginAccount, MarginOcoOrderRequest, OcoOrder, SideEffectType};
+    use crate::model::Side;
+    use anyhow::Result;
+    use serde_json::{from_str, json, to_value};
+
+    #[test]
+    fn margin_oco_request() -> Result<()> {
+        let order = MarginOcoOrderRequest::new("btcusdt", Side::Sell, 0.1, 70000.0, 60000.0)
+            .stop_limit(59900.0)
+            .isolated()
+            .side_effect(SideEffectType::AutoRepay);
+        let value = to_value(order)?;
+        assert_eq!(value["symbol"], json!("BTCUSDT"));
+        assert_eq!(value["isIsolated"], json!("TRUE"));
+        assert_eq!(value["sideEffectType"], json!("AUTO_REPAY"));
+        assert_eq!(value["stopLimitTimeInForce"], json!("GTC"));
+        Ok(())
+    }
+
+    #[test]
+    fn oco_order() -> Result<()> {
+        let json = r#"{"orderListId":0,"contingencyType":"OCO","listStatusType":"EXEC_STARTED","listOrderStatus":"EXECUTING","listClientOrderId":"JYVpp3F0f5CAG15DhtrqLp","transactionTime":1563417480525,"symbol":"LTCBTC","marginBuyBorrowAmount":"5","marginBuyBorrowAsset":"BTC","isIsolated":false,"orders":[{"symbol":"LTCBTC","orderId":2,"clientOrderId":"Kk7sqHb9J6mJWTMDVW7Vos"},{"symbol":"LTCBTC","orderId":3,"clientOrderId":"xTXKaGYd4bluPVp78IVRvl"}],"orderReports":[{"symbol":"LTCBTC","orderId":2,"orderListId":0,"clientOrderId":"Kk7sqHb9J6mJWTMDVW7Vos","transactTime":1563417480525,"price":"0.000000","origQty":"0.624363","executedQty":"0.000000","cummulativeQuoteQty":"0.000000","status":"NEW","timeInForce":"GTC","type":"STOP_LOSS","side":"BUY","stopPrice":"0.960664"}]}"#;
+        let oco: OcoOrder = from_str(json)?;
+        assert_eq!(oco.orders.len(), 2);
+        assert_eq!(oco.order_reports[0].order_id, 2);
+        Ok(())
+    }
+
+    #[test]
+    fn margin_account() -> Result<()> {
+        let json = r#"{"borrowEnabled":true,"marginLevel":"11.64405625","totalAssetOfBtc":"6.82728457","totalLiabilityOfBtc":"0.58633215","totalNetAssetOfBtc":"6.24095242","tradeEnabled":true,"transferEnabled":true,"userAssets":[{"asset":"BTC","borrowed":"0.00000000","free":"0.00499500","interest":"0.00000000","locked":"0.00000000","netAsset":"0.00499500"},{"asset":"BNB","borrowed":"201.66666672","free":"2346.50000000","interest":"0.00000000","locked":"0.00000000","netAsset":"2144.83333328"}]}"#;
+        let account: MarginAccount = from_str(json)?;
+        assert!((account.total_net_asset_of_btc - 6.240_952_42).abs() < f64::EPSILON);
+        assert_eq!(account.user_assets[1].asset, "BNB");
+        assert!((account.user_assets[1].net_asset - 2_144.833_333_28).abs() < f64::EPSILON);
+        Ok(())
+    }
+}
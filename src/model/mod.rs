@@ -1,34 +1,89 @@
+pub mod algo;
+pub mod blvt;
+pub mod dual_investment;
+pub mod futures;
+pub mod margin;
+pub mod pair;
+pub mod pay;
+pub mod portfolio_margin;
+pub mod rebate;
+pub mod request;
+pub mod ticks;
+pub mod vip_loan;
 pub mod websocket;
+pub mod websocket_ref;
+pub mod ws_api;
 
+use crate::error::Error;
 use chrono::prelude::*;
-use serde::{Deserialize, Serialize};
+use serde::{
+    de::{DeserializeOwned, IgnoredAny},
+    Deserialize, Deserializer, Serialize,
+};
+use serde_json::Value;
 use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::time::Duration;
+
+// Millisecond timestamp sent by the REST API, a `DateTime<Utc>` with the `chrono-timestamps` feature
+#[cfg(feature = "chrono-timestamps")]
+pub type Timestamp = DateTime<Utc>;
+#[cfg(not(feature = "chrono-timestamps"))]
+pub type Timestamp = u64;
+// Same for the fields that have always been `i64`, like the kline times
+#[cfg(feature = "chrono-timestamps")]
+pub type SignedTimestamp = DateTime<Utc>;
+#[cfg(not(feature = "chrono-timestamps"))]
+pub type SignedTimestamp = i64;
 
 #[derive(Serialize, Deserialize, Clone, PartialEq, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct ServerTime {
-    pub server_time: u64,
+    #[serde(with = "timestamp")]
+    pub server_time: Timestamp,
 }
 
+// `status` is 0 while the exchange operates normally and 1 during system maintenance
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct ExchangeInformation {
-    pub timezone: String,
-    pub server_time: u64,
-    pub rate_limits: Vec<RateLimit>,
-    pub symbols: Vec<Symbol>,
+pub struct SystemStatus {
+    pub status: u8,
+    pub msg: String,
+}
+
+impl SystemStatus {
+    #[must_use]
+    pub const fn is_maintenance(&self) -> bool {
+        self.status == 1
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct ExchangeInfo {
+pub struct ExchangeInformation {
     pub timezone: String,
-    pub server_time: u64,
+    #[serde(with = "timestamp")]
+    pub server_time: Timestamp,
     pub rate_limits: Vec<RateLimit>,
+    #[serde(default)]
     pub exchange_filters: Vec<ExchangeFilter>,
     pub symbols: Vec<Symbol>,
 }
 
+#[deprecated(note = "merged into `ExchangeInformation`")]
+pub type ExchangeInfo = ExchangeInformation;
+
+impl ExchangeInformation {
+    // Trading pair of `symbol` with its base and quote assets
+    pub fn trading_pair(&self, symbol: &str) -> Result<pair::TradingPair, Error> {
+        let symbol = symbol.to_uppercase();
+        self.symbols
+            .iter()
+            .find(|s| s.symbol == symbol)
+            .map(pair::TradingPair::from)
+            .ok_or(Error::SymbolNotFound)
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountInformation {
@@ -68,7 +123,11 @@ pub struct Order {
     #[serde(with = "string_or_float")]
     pub stop_price: f64,
     pub iceberg_qty: String,
-    pub time: u64,
+    #[serde(with = "timestamp")]
+    pub time: Timestamp,
+    // Fields the model does not cover yet, e.g. `workingTime` or `selfTradePreventionMode`
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -86,7 +145,285 @@ pub struct Transaction {
     pub symbol: String,
     pub order_id: u64,
     pub client_order_id: String,
-    pub transact_time: u64,
+    #[serde(with = "timestamp")]
+    pub transact_time: Timestamp,
+    // Trades the order was filled by, empty unless the response type is FULL
+    #[serde(default)]
+    pub fills: Vec<Fill>,
+    // Everything else of the ACK/RESULT/FULL response, e.g. `status` or `executedQty`
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
+}
+
+impl Transaction {
+    // Average price of the fills, `None` if nothing was filled yet
+    #[must_use]
+    pub fn avg_price(&self) -> Option<f64> {
+        avg_fill_price(&self.fills)
+    }
+}
+
+// How much of a new order the exchange reports back, `newOrderRespType`
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderResponseType {
+    Ack,
+    Result,
+    Full,
+}
+
+impl OrderResponseType {
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Ack => "ACK",
+            Self::Result => "RESULT",
+            Self::Full => "FULL",
+        }
+    }
+}
+
+// Response of a new order, selects the `newOrderRespType` to request
+pub trait OrderResponse: DeserializeOwned {
+    const RESPONSE_TYPE: OrderResponseType;
+
+    // When the matching engine accepted the order
+    fn transact_time(&self) -> &Timestamp;
+}
+
+// Where the time of an order request went, all in milliseconds of the local clock except
+// `server_transact_time`. Comparing both clocks needs them in sync, see `Latency`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RequestTiming {
+    // Right before the signed request was sent
+    pub sent_at: u64,
+    // Right after the response was parsed
+    pub received_at: u64,
+    // `transactTime` of the response
+    pub server_transact_time: u64,
+}
+
+impl RequestTiming {
+    #[must_use]
+    pub const fn round_trip(&self) -> Duration {
+        Duration::from_millis(self.received_at.saturating_sub(self.sent_at))
+    }
+
+    // Network latency to the exchange plus its processing time, negative if the clocks drift
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap)]
+    pub const fn to_exchange_ms(&self) -> i64 {
+        self.server_transact_time as i64 - self.sent_at as i64
+    }
+
+    // Network latency back from the exchange
+    #[must_use]
+    #[allow(clippy::cast_possible_wrap)]
+    pub const fn from_exchange_ms(&self) -> i64 {
+        self.received_at as i64 - self.server_transact_time as i64
+    }
+}
+
+// An order response with the timing of its request
+#[derive(Debug, Clone)]
+pub struct TimedOrder<R> {
+    pub order: R,
+    pub timing: RequestTiming,
+}
+
+// The order was accepted, returned as soon as it reached the matching engine
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderAck {
+    pub symbol: String,
+    pub order_id: u64,
+    pub order_list_id: i64,
+    pub client_order_id: String,
+    #[serde(with = "timestamp")]
+    pub transact_time: Timestamp,
+}
+
+impl OrderResponse for OrderAck {
+    const RESPONSE_TYPE: OrderResponseType = OrderResponseType::Ack;
+
+    fn transact_time(&self) -> &Timestamp {
+        &self.transact_time
+    }
+}
+
+// The order after matching
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderResult {
+    pub symbol: String,
+    pub order_id: u64,
+    pub order_list_id: i64,
+    pub client_order_id: String,
+    #[serde(with = "timestamp")]
+    pub transact_time: Timestamp,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    pub orig_qty: String,
+    pub executed_qty: String,
+    pub cummulative_quote_qty: String,
+    pub status: OrderStatus,
+    pub time_in_force: String,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub side: String,
+}
+
+impl OrderResponse for OrderResult {
+    const RESPONSE_TYPE: OrderResponseType = OrderResponseType::Result;
+
+    fn transact_time(&self) -> &Timestamp {
+        &self.transact_time
+    }
+}
+
+// The order after matching, along with the trades it was filled by
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrderFull {
+    #[serde(flatten)]
+    pub result: OrderResult,
+    pub fills: Vec<Fill>,
+}
+
+impl OrderResponse for OrderFull {
+    const RESPONSE_TYPE: OrderResponseType = OrderResponseType::Full;
+
+    fn transact_time(&self) -> &Timestamp {
+        &self.result.transact_time
+    }
+}
+
+impl OrderFull {
+    // Average price of the fills, `None` if nothing was filled yet
+    #[must_use]
+    pub fn avg_price(&self) -> Option<f64> {
+        avg_fill_price(&self.fills)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Fill {
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    #[serde(with = "string_or_float")]
+    pub qty: f64,
+    #[serde(with = "string_or_float")]
+    pub commission: f64,
+    pub commission_asset: String,
+    pub trade_id: u64,
+}
+
+fn avg_fill_price(fills: &[Fill]) -> Option<f64> {
+    let qty: f64 = fills.iter().map(|fill| fill.qty).sum();
+    if qty == 0.0 {
+        return None;
+    }
+    Some(fills.iter().map(|fill| fill.price * fill.qty).sum::<f64>() / qty)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BnbBurnStatus {
+    #[serde(rename = "spotBNBBurn")]
+    pub spot_bnb_burn: bool,
+    #[serde(rename = "interestBNBBurn")]
+    pub interest_bnb_burn: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AccountStatus {
+    pub data: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiTradingStatus {
+    pub data: ApiTradingStatusDetail,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTradingStatusDetail {
+    pub is_locked: bool,
+    pub planned_recover_time: u64,
+    pub trigger_condition: HashMap<String, u64>,
+    #[serde(default)]
+    pub indicators: HashMap<String, Vec<ApiTradingIndicator>>,
+    #[serde(with = "timestamp")]
+    pub update_time: Timestamp,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ApiTradingIndicator {
+    #[serde(rename = "i")]
+    pub indicator: String,
+    #[serde(rename = "c")]
+    pub count: u64,
+    #[serde(rename = "v")]
+    pub current_value: f64,
+    #[serde(rename = "t")]
+    pub trigger_value: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+#[allow(clippy::struct_excessive_bools)]
+pub struct ApiKeyPermissions {
+    pub ip_restrict: bool,
+    #[serde(with = "timestamp")]
+    pub create_time: Timestamp,
+    pub enable_reading: bool,
+    pub enable_spot_and_margin_trading: bool,
+    pub enable_withdrawals: bool,
+    pub enable_internal_transfer: bool,
+    pub permits_universal_transfer: bool,
+    pub enable_margin: bool,
+    pub enable_futures: bool,
+    pub enable_vanilla_options: bool,
+    #[serde(default)]
+    pub enable_portfolio_margin_trading: bool,
+    pub trading_authority_expiration_time: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ApiKeyPermission {
+    Reading,
+    SpotAndMarginTrading,
+    Withdrawals,
+    InternalTransfer,
+    UniversalTransfer,
+    Margin,
+    Futures,
+    VanillaOptions,
+    PortfolioMarginTrading,
+}
+
+impl ApiKeyPermissions {
+    #[must_use]
+    pub const fn has(&self, permission: ApiKeyPermission) -> bool {
+        match permission {
+            ApiKeyPermission::Reading => self.enable_reading,
+            ApiKeyPermission::SpotAndMarginTrading => self.enable_spot_and_margin_trading,
+            ApiKeyPermission::Withdrawals => self.enable_withdrawals,
+            ApiKeyPermission::InternalTransfer => self.enable_internal_transfer,
+            ApiKeyPermission::UniversalTransfer => self.permits_universal_transfer,
+            ApiKeyPermission::Margin => self.enable_margin,
+            ApiKeyPermission::Futures => self.enable_futures,
+            ApiKeyPermission::VanillaOptions => self.enable_vanilla_options,
+            ApiKeyPermission::PortfolioMarginTrading => self.enable_portfolio_margin_trading,
+        }
+    }
+
+    // Fails with the first permission in `required` that the key lacks
+    pub fn ensure(&self, required: &[ApiKeyPermission]) -> Result<(), Error> {
+        required
+            .iter()
+            .find(|p| !self.has(**p))
+            .map_or(Ok(()), |p| Err(Error::MissingApiKeyPermission(*p)))
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -113,6 +450,110 @@ pub struct Asks {
     ignore: Vec<String>,
 }
 
+// Round trips of repeated `/api/v3/time` requests. `clock_offset_ms` is server time minus
+// local time, taken from the fastest round trip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Latency {
+    pub samples: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub p99: Duration,
+    pub clock_offset_ms: i64,
+}
+
+impl Latency {
+    // `samples` are round trips with the clock offset measured in each, `None` if empty
+    #[must_use]
+    pub fn from_samples(samples: &[(Duration, i64)]) -> Option<Self> {
+        let fastest = samples.iter().min_by_key(|(rtt, _)| *rtt)?;
+        let mut rtts: Vec<Duration> = samples.iter().map(|(rtt, _)| *rtt).collect();
+        rtts.sort_unstable();
+        let p99 = (rtts.len() * 99).div_ceil(100).saturating_sub(1);
+        Some(Self {
+            samples: rtts.len(),
+            min: rtts[0],
+            median: rtts[rtts.len() / 2],
+            p99: rtts[p99],
+            clock_offset_ms: fastest.1,
+        })
+    }
+}
+
+// Result of `Binance::warm_up`: the spot API host connected to and the round trips of the
+// first ping, which set the connection up, and of the second over the open connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WarmUp {
+    pub host: String,
+    pub cold: Duration,
+    pub warm: Duration,
+}
+
+// A response together with the time its request took
+#[derive(Debug, Clone)]
+pub struct Timed<T> {
+    pub value: T,
+    pub elapsed: Duration,
+}
+
+// Order book sizes accepted by `/api/v3/depth`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DepthLimit {
+    L5,
+    L10,
+    L20,
+    L50,
+    #[default]
+    L100,
+    L500,
+    L1000,
+    L5000,
+}
+
+impl DepthLimit {
+    #[must_use]
+    pub const fn value(self) -> u64 {
+        match self {
+            Self::L5 => 5,
+            Self::L10 => 10,
+            Self::L20 => 20,
+            Self::L50 => 50,
+            Self::L100 => 100,
+            Self::L500 => 500,
+            Self::L1000 => 1000,
+            Self::L5000 => 5000,
+        }
+    }
+
+    // Request weight charged against the REQUEST_WEIGHT rate limit
+    #[must_use]
+    pub const fn weight(self) -> u32 {
+        match self {
+            Self::L5 | Self::L10 | Self::L20 | Self::L50 | Self::L100 => 5,
+            Self::L500 => 25,
+            Self::L1000 => 50,
+            Self::L5000 => 250,
+        }
+    }
+}
+
+impl TryFrom<u64> for DepthLimit {
+    type Error = Error;
+
+    fn try_from(limit: u64) -> Result<Self, Error> {
+        match limit {
+            5 => Ok(Self::L5),
+            10 => Ok(Self::L10),
+            20 => Ok(Self::L20),
+            50 => Ok(Self::L50),
+            100 => Ok(Self::L100),
+            500 => Ok(Self::L500),
+            1000 => Ok(Self::L1000),
+            5000 => Ok(Self::L5000),
+            _ => Err(Error::InvalidDepthLimit(limit)),
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UserDataStream {
@@ -175,7 +616,8 @@ pub struct TradeHistory {
     #[serde(with = "string_or_float")]
     pub commission: f64,
     pub commission_asset: String,
-    pub time: u64,
+    #[serde(with = "timestamp")]
+    pub time: Timestamp,
     pub is_buyer: bool,
     pub is_maker: bool,
     pub is_best_match: bool,
@@ -191,8 +633,30 @@ pub struct HistoricalTrade {
     pub qty: f64,
     #[serde(with = "string_or_float")]
     pub quote_qty: f64,
-    pub time: i64,
+    #[serde(with = "timestamp")]
+    pub time: SignedTimestamp,
+    pub is_buyer_maker: bool,
+    pub is_best_match: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AggTrade {
+    #[serde(rename = "a")]
+    pub aggregated_trade_id: u64,
+    #[serde(rename = "p", with = "string_or_float")]
+    pub price: f64,
+    #[serde(rename = "q", with = "string_or_float")]
+    pub qty: f64,
+    #[serde(rename = "f")]
+    pub first_trade_id: u64,
+    #[serde(rename = "l")]
+    pub last_trade_id: u64,
+    #[serde(rename = "T")]
+    #[serde(with = "timestamp")]
+    pub time: Timestamp,
+    #[serde(rename = "m")]
     pub is_buyer_maker: bool,
+    #[serde(rename = "M")]
     pub is_best_match: bool,
 }
 
@@ -222,16 +686,21 @@ pub struct PriceStats {
     pub low_price: f64,
     #[serde(with = "string_or_float")]
     pub volume: f64,
-    pub open_time: u64,
-    pub close_time: u64,
+    #[serde(with = "timestamp")]
+    pub open_time: Timestamp,
+    #[serde(with = "timestamp")]
+    pub close_time: Timestamp,
     pub first_id: i64, // For dummy symbol "123456", it is -1
     pub last_id: i64,  // Same as above
     pub count: u64,
+    // Fields the model does not cover yet, e.g. `bidQty` or `quoteVolume`
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
 #[derive(Debug, Clone)]
 pub struct KlineSummary {
-    pub open_time: i64,
+    pub open_time: SignedTimestamp,
 
     pub open: f64,
 
@@ -243,7 +712,7 @@ pub struct KlineSummary {
 
     pub volume: f64,
 
-    pub close_time: i64,
+    pub close_time: SignedTimestamp,
 
     pub quote_asset_volume: f64,
 
@@ -254,6 +723,55 @@ pub struct KlineSummary {
     pub taker_buy_quote_asset_volume: f64,
 }
 
+// A kline row as sent by `/klines`, `/uiKlines` and the futures klines. The 12th field is unused.
+#[derive(Deserialize)]
+struct KlineRow(
+    #[serde(with = "timestamp")] SignedTimestamp,
+    #[serde(with = "string_or_float")] f64,
+    #[serde(with = "string_or_float")] f64,
+    #[serde(with = "string_or_float")] f64,
+    #[serde(with = "string_or_float")] f64,
+    #[serde(with = "string_or_float")] f64,
+    #[serde(with = "timestamp")] SignedTimestamp,
+    #[serde(with = "string_or_float")] f64,
+    i64,
+    #[serde(with = "string_or_float")] f64,
+    #[serde(with = "string_or_float")] f64,
+    #[serde(default)] IgnoredAny,
+);
+
+impl<'de> Deserialize<'de> for KlineSummary {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let KlineRow(
+            open_time,
+            open,
+            high,
+            low,
+            close,
+            volume,
+            close_time,
+            quote_asset_volume,
+            number_of_trades,
+            taker_buy_base_asset_volume,
+            taker_buy_quote_asset_volume,
+            _,
+        ) = KlineRow::deserialize(deserializer)?;
+        Ok(Self {
+            open_time,
+            open,
+            high,
+            low,
+            close,
+            volume,
+            close_time,
+            quote_asset_volume,
+            number_of_trades,
+            taker_buy_base_asset_volume,
+            taker_buy_quote_asset_volume,
+        })
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Kline {
@@ -266,9 +784,9 @@ pub struct Kline {
     #[serde(rename = "i")]
     pub interval: String,
     #[serde(rename = "f")]
-    pub first_trade_id: i32,
+    pub first_trade_id: i64,
     #[serde(rename = "L")]
-    pub last_trade_id: i32,
+    pub last_trade_id: i64,
     #[serde(rename = "o")]
     pub open: String,
     #[serde(rename = "c")]
@@ -345,13 +863,13 @@ pub struct Kline {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct RateLimit {
-    rate_limit_type: RateLimitType,
-    interval: Interval,
-    limit: u64,
-    interval_num: u64,
+    pub rate_limit_type: RateLimitType,
+    pub interval: Interval,
+    pub limit: u64,
+    pub interval_num: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum RateLimitType {
     Orders,
@@ -359,7 +877,7 @@ pub enum RateLimitType {
     RawRequests,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Interval {
     Second,
@@ -395,11 +913,60 @@ pub enum SymbolFilter {
         avg_price_mins: u64,
     },
     #[serde(rename_all = "camelCase")]
+    Notional {
+        min_notional: String,
+        apply_min_to_market: bool,
+        max_notional: String,
+        apply_max_to_market: bool,
+        avg_price_mins: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    PercentPrice {
+        multiplier_up: String,
+        multiplier_down: String,
+        avg_price_mins: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    PercentPriceBySide {
+        bid_multiplier_up: String,
+        bid_multiplier_down: String,
+        ask_multiplier_up: String,
+        ask_multiplier_down: String,
+        avg_price_mins: u64,
+    },
+    // LOT_SIZE of MARKET orders
+    #[serde(rename_all = "camelCase")]
+    MarketLotSize {
+        min_qty: String,
+        max_qty: String,
+        step_size: String,
+    },
+    // Deltas in basis points
+    #[serde(rename_all = "camelCase")]
+    TrailingDelta {
+        min_trailing_above_delta: u64,
+        max_trailing_above_delta: u64,
+        min_trailing_below_delta: u64,
+        max_trailing_below_delta: u64,
+    },
+    #[serde(rename_all = "camelCase")]
     MaxNumAlgoOrders { max_num_algo_orders: u64 },
     #[serde(rename_all = "camelCase")]
-    MaxNumOrders { limit: u64 },
+    MaxNumOrders { max_num_orders: u64 },
+    #[serde(rename_all = "camelCase")]
+    MaxNumIcebergOrders { max_num_iceberg_orders: u64 },
+    #[serde(rename_all = "camelCase")]
+    MaxNumOrderLists { max_num_order_lists: u64 },
+    #[serde(rename_all = "camelCase")]
+    MaxNumOrderAmends { max_num_order_amends: u64 },
+    // Largest position in the base asset, open BUY orders included
+    #[serde(rename_all = "camelCase")]
+    MaxPosition { max_position: String },
     #[serde(rename_all = "camelCase")]
     IcebergParts { limit: u64 },
+    // Filter types added after this model, e.g. T_PLUS_SELL
+    #[serde(other)]
+    Other,
 }
 
 // {
@@ -429,8 +996,59 @@ pub enum SymbolFilter {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "filterType", rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum ExchangeFilter {
-    ExchangeMaxNumOrders { limit: u64 },
-    ExchangeMaxAlgoOrders { limit: u64 },
+    ExchangeMaxNumOrders {
+        #[serde(alias = "maxNumOrders")]
+        limit: u64,
+    },
+    ExchangeMaxAlgoOrders {
+        #[serde(alias = "maxNumAlgoOrders")]
+        limit: u64,
+    },
+    #[serde(rename_all = "camelCase")]
+    ExchangeMaxNumIcebergOrders { max_num_iceberg_orders: u64 },
+    #[serde(rename_all = "camelCase")]
+    ExchangeMaxNumOrderLists { max_num_order_lists: u64 },
+    #[serde(other)]
+    Other,
+}
+
+// Account permission a symbol may be traded with, e.g. "SPOT" or "TRD_GRP_004"
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Hash)]
+#[serde(from = "String", into = "String")]
+pub enum SymbolPermission {
+    Spot,
+    Margin,
+    Leveraged,
+    // Trading group, e.g. 4 for "TRD_GRP_004"
+    TradingGroup(u16),
+    // Permissions the model does not cover, kept as sent
+    Other(String),
+}
+
+impl From<String> for SymbolPermission {
+    fn from(permission: String) -> Self {
+        match permission.as_str() {
+            "SPOT" => Self::Spot,
+            "MARGIN" => Self::Margin,
+            "LEVERAGED" => Self::Leveraged,
+            _ => permission
+                .strip_prefix("TRD_GRP_")
+                .and_then(|group| group.parse().ok())
+                .map_or(Self::Other(permission), Self::TradingGroup),
+        }
+    }
+}
+
+impl From<SymbolPermission> for String {
+    fn from(permission: SymbolPermission) -> Self {
+        match permission {
+            SymbolPermission::Spot => "SPOT".into(),
+            SymbolPermission::Margin => "MARGIN".into(),
+            SymbolPermission::Leveraged => "LEVERAGED".into(),
+            SymbolPermission::TradingGroup(group) => format!("TRD_GRP_{group:03}"),
+            SymbolPermission::Other(permission) => permission,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -451,7 +1069,145 @@ pub struct Symbol {
     pub quote_order_qty_market_allowed: bool,
     pub is_spot_trading_allowed: bool,
     pub is_margin_trading_allowed: bool,
-    // pub filters: Vec<SymbolFilter>, // TODO work out why this isnt deserializing
+    #[serde(default)]
+    pub filters: Vec<SymbolFilter>,
+    // Legacy permission list, left empty by the exchange since `permission_sets` exist
+    #[serde(default)]
+    pub permissions: Vec<SymbolPermission>,
+    // An account may trade the symbol if it has at least one permission of every set
+    #[serde(default)]
+    pub permission_sets: Vec<Vec<SymbolPermission>>,
+}
+
+impl Symbol {
+    // Whether orders are accepted right now, not e.g. during a BREAK or before a listing
+    #[must_use]
+    pub fn is_trading(&self) -> bool {
+        self.status == "TRADING"
+    }
+
+    // Whether `permission` is in any permission set, or in the legacy list
+    #[must_use]
+    pub fn has_permission(&self, permission: &SymbolPermission) -> bool {
+        self.permission_sets
+            .iter()
+            .flatten()
+            .chain(&self.permissions)
+            .any(|p| p == permission)
+    }
+
+    // Whether an account with `account_permissions` passes every permission set
+    #[must_use]
+    pub fn permits(&self, account_permissions: &[SymbolPermission]) -> bool {
+        self.permission_sets
+            .iter()
+            .all(|set| set.iter().any(|p| account_permissions.contains(p)))
+    }
+
+    // Whether a spot order can be placed now. Symbols listed without any permissions rely on
+    // `is_spot_trading_allowed` alone.
+    #[must_use]
+    pub fn is_spot_tradable(&self) -> bool {
+        self.is_trading()
+            && self.is_spot_trading_allowed
+            && (self.has_no_permissions() || self.has_permission(&SymbolPermission::Spot))
+    }
+
+    // Whether a margin order can be placed now
+    #[must_use]
+    pub fn is_margin_tradable(&self) -> bool {
+        self.is_trading()
+            && self.is_margin_trading_allowed
+            && (self.has_no_permissions() || self.has_permission(&SymbolPermission::Margin))
+    }
+
+    fn has_no_permissions(&self) -> bool {
+        self.permissions.is_empty() && self.permission_sets.iter().all(Vec::is_empty)
+    }
+
+    // Smallest price increment, from the PRICE_FILTER
+    #[must_use]
+    pub fn tick_size(&self) -> Option<&str> {
+        self.filters.iter().find_map(|filter| match filter {
+            SymbolFilter::PriceFilter { tick_size, .. } => Some(tick_size.as_str()),
+            _ => None,
+        })
+    }
+
+    // Smallest quantity increment, from the LOT_SIZE filter
+    #[must_use]
+    pub fn step_size(&self) -> Option<&str> {
+        self.filters.iter().find_map(|filter| match filter {
+            SymbolFilter::LotSize { step_size, .. } => Some(step_size.as_str()),
+            _ => None,
+        })
+    }
+
+    // `price` rounded to the nearest tick, with exactly the decimals of the tick size
+    #[must_use]
+    pub fn format_price(&self, price: f64) -> String {
+        self.tick_size().map_or_else(
+            || format_decimals(price, self.quote_precision),
+            |tick_size| format_increment(price, tick_size, f64::round),
+        )
+    }
+
+    // `qty` rounded down to the step size, with exactly the decimals of the step size
+    #[must_use]
+    pub fn format_qty(&self, qty: f64) -> String {
+        self.step_size().map_or_else(
+            || format_decimals(qty, self.base_asset_precision),
+            |step_size| format_increment(qty, step_size, f64::floor),
+        )
+    }
+
+    // `quote_qty` rounded down to the quote asset precision, the decimals a `quoteOrderQty`
+    // may have
+    #[must_use]
+    pub fn format_quote_qty(&self, quote_qty: f64) -> String {
+        let decimals = self.quote_asset_precision.min(16);
+        format_decimals(floor_decimals(quote_qty, decimals), decimals)
+    }
+}
+
+// A multiple of `increment` ("0.00100000"), printed with the decimals it has left without
+// trailing zeros. A zero increment means the filter is disabled.
+fn format_increment(value: f64, increment: &str, round: fn(f64) -> f64) -> String {
+    let decimals = increment
+        .trim_end_matches('0')
+        .split_once('.')
+        .map_or(0, |(_, fraction)| fraction.len());
+    match increment.parse::<f64>() {
+        Ok(step) if step > 0.0 => {
+            // Absorbs the error of the division, 0.3 / 0.1 is 2.9999999999999996
+            let steps = round(value / step + 1e-9);
+            format!("{:.*}", decimals, steps * step)
+        }
+        _ => format!("{value:.decimals$}"),
+    }
+}
+
+// `value` rounded down to `decimals`, at most 16
+pub(crate) fn floor_decimals(value: f64, decimals: u64) -> f64 {
+    let scale = 10_f64.powi(i32::try_from(decimals.min(16)).unwrap_or(16));
+    value.mul_add(scale, 1e-9).floor() / scale
+}
+
+fn format_decimals(value: f64, decimals: u64) -> String {
+    format!(
+        "{:.*}",
+        usize::try_from(decimals).unwrap_or(usize::MAX),
+        value
+    )
+}
+
+// Symbols to be delisted together at `delist_time`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DelistSchedule {
+    #[serde(with = "timestamp")]
+    pub delist_time: Timestamp,
+    pub symbols: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -462,7 +1218,7 @@ pub struct OrderBook {
     pub asks: Vec<Asks>,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum Side {
     Buy,
@@ -481,36 +1237,57 @@ pub enum OrderType {
     LimitMaker,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+// How long an order stays on the book. Spot LIMIT, STOP_LOSS_LIMIT and TAKE_PROFIT_LIMIT orders
+// take GTC, IOC and FOK, the spot post-only order is `OrderType::LimitMaker` without a time in
+// force. Futures LIMIT orders also take GTX and GTD. The variant names are the wire format as
+// is, `rename_all = "SCREAMING_SNAKE_CASE"` would send "G_T_C".
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 pub enum TimeInForce {
+    // Good till canceled
     GTC,
+    // Immediate or cancel: fills what it can right away, the rest is canceled
     IOC,
+    // Fill or kill: fills completely right away or is canceled
     FOK,
+    // Good till crossing, post-only on futures: canceled instead of filling as a taker
+    GTX,
+    // Good till date, futures only: canceled at the order's `good_till_date`
+    GTD,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderExecType {
     New,
+    Canceled,
+    Replaced,
+    Rejected,
+    Trade,
+    Expired,
+    TradePrevention,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderStatus {
     New,
+    PendingNew,
     PartiallyFilled,
     Filled,
     Canceled,
     PendingCancel,
     Rejected,
     Expired,
+    ExpiredInMatch,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderRejectReason {
     None,
+    // Reject reasons are only informational, new ones must not break the user data stream
+    #[serde(other)]
+    Other,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -520,6 +1297,29 @@ pub struct DepositAddressData {
     pub address_tag: String,
 }
 
+// Deposit address of one coin on one network. Deposits to an address with a tag (memo) are lost
+// unless the tag is given as well.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositAddress {
+    pub coin: String,
+    pub address: String,
+    // Empty for networks without tags
+    pub tag: String,
+    // Block explorer link, only sent for a single address
+    pub url: Option<String>,
+    // 1 for the address deposits go to by default, only sent for a list of addresses
+    pub is_default: Option<u8>,
+}
+
+impl DepositAddress {
+    // The tag or memo that must accompany a deposit, `None` if the network has none
+    #[must_use]
+    pub fn memo(&self) -> Option<&str> {
+        Some(self.tag.as_str()).filter(|tag| !tag.is_empty())
+    }
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct DepositHistoryEntry {
@@ -539,6 +1339,183 @@ pub struct DepositHistory {
     pub deposit_list: Vec<DepositHistoryEntry>,
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositRecord {
+    pub id: String,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    pub coin: String,
+    pub network: String,
+    pub status: u8,
+    pub address: String,
+    pub address_tag: Option<String>,
+    pub tx_id: String,
+    #[serde(with = "timestamp")]
+    pub insert_time: Timestamp,
+    pub transfer_type: u8,
+    pub confirm_times: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawRecord {
+    pub id: String,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    #[serde(with = "string_or_float")]
+    pub transaction_fee: f64,
+    pub coin: String,
+    pub status: u8,
+    pub address: String,
+    pub tx_id: Option<String>,
+    pub apply_time: String,
+    pub network: String,
+    pub transfer_type: u8,
+    pub withdraw_order_id: Option<String>,
+    pub info: Option<String>,
+    pub confirm_no: Option<u64>,
+    pub complete_time: Option<String>,
+}
+
+// Deposit and withdraw settings of one coin, from `/capital/config/getall`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinInfo {
+    pub coin: String,
+    pub name: String,
+    #[serde(with = "string_or_float")]
+    pub free: f64,
+    pub deposit_all_enable: bool,
+    pub withdraw_all_enable: bool,
+    pub network_list: Vec<CoinNetwork>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinNetwork {
+    pub network: String,
+    pub coin: String,
+    pub name: String,
+    pub is_default: bool,
+    pub deposit_enable: bool,
+    pub withdraw_enable: bool,
+    #[serde(with = "string_or_float")]
+    pub withdraw_fee: f64,
+    #[serde(with = "string_or_float")]
+    pub withdraw_min: f64,
+    #[serde(with = "string_or_float")]
+    pub withdraw_max: f64,
+    // Withdrawn amounts must be a multiple of this
+    #[serde(with = "string_or_float")]
+    pub withdraw_integer_multiple: f64,
+    // Set for networks that need a tag (memo) besides the address
+    #[serde(default)]
+    pub memo_regex: String,
+    pub withdraw_desc: Option<String>,
+}
+
+// What a withdrawal of `amount` costs, checked against the limits of the network
+#[derive(Debug, Clone, PartialEq)]
+pub struct WithdrawQuote {
+    pub coin: String,
+    pub network: String,
+    pub amount: f64,
+    pub fee: f64,
+    // `amount` minus the fee, what arrives at the address
+    pub received: f64,
+    // Whether the address needs a tag (memo) as well
+    pub requires_memo: bool,
+}
+
+impl CoinInfo {
+    // The network of `network`, the coin's default one if `None`
+    #[must_use]
+    pub fn network(&self, network: Option<&str>) -> Option<&CoinNetwork> {
+        self.network_list.iter().find(|candidate| {
+            network.map_or(candidate.is_default, |network| {
+                candidate.network.eq_ignore_ascii_case(network)
+            })
+        })
+    }
+
+    // `Error::InvalidWithdrawal` if the network is unknown or suspended, or `amount` is out of
+    // its limits or no multiple of its increment
+    pub fn withdraw_quote(
+        &self,
+        network: Option<&str>,
+        amount: f64,
+    ) -> Result<WithdrawQuote, Error> {
+        let invalid = |reason: String| Err(Error::InvalidWithdrawal(reason));
+        let Some(info) = self.network(network) else {
+            return invalid(format!(
+                "{} has no network {}",
+                self.coin,
+                network.unwrap_or("marked as default")
+            ));
+        };
+        if !self.withdraw_all_enable || !info.withdraw_enable {
+            return invalid(format!(
+                "withdrawals of {} on {} are suspended{}",
+                self.coin,
+                info.network,
+                info.withdraw_desc
+                    .as_deref()
+                    .filter(|desc| !desc.is_empty())
+                    .map_or_else(String::new, |desc| format!(": {desc}"))
+            ));
+        }
+        if amount < info.withdraw_min || amount > info.withdraw_max {
+            return invalid(format!(
+                "{amount} {} is outside of {}..={} on {}",
+                self.coin, info.withdraw_min, info.withdraw_max, info.network
+            ));
+        }
+        let multiple = info.withdraw_integer_multiple;
+        if multiple > 0.0
+            && (amount / multiple).round().mul_add(multiple, -amount).abs() > multiple * 1e-6
+        {
+            return invalid(format!(
+                "{amount} {} is no multiple of {multiple}",
+                self.coin
+            ));
+        }
+        Ok(WithdrawQuote {
+            coin: self.coin.clone(),
+            network: info.network.clone(),
+            amount,
+            fee: info.withdraw_fee,
+            received: amount - info.withdraw_fee,
+            requires_memo: !info.memo_regex.is_empty(),
+        })
+    }
+}
+
+// Wallet a withdrawal is paid from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum WalletType {
+    Spot = 0,
+    Funding = 1,
+}
+
+// Answer to `withdraw`, `id` matches `WithdrawRecord::id`
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Withdrawal {
+    pub id: String,
+}
+
+// Answer to `travel_rule_withdraw`. A rejected questionnaire is no error, check `accepted`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct TravelRuleWithdrawal {
+    pub tr_id: u64,
+    // Misspelled by the API
+    #[serde(rename = "accpted")]
+    pub accepted: bool,
+    pub info: String,
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "camelCase")]
 pub struct AssetDetailEntry {
@@ -555,6 +1532,149 @@ pub struct AssetDetail {
     pub asset_detail: HashMap<String, AssetDetailEntry>,
 }
 
+// Balance of the Funding wallet, where P2P and Pay funds are held
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingAsset {
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub free: f64,
+    #[serde(with = "string_or_float")]
+    pub locked: f64,
+    #[serde(with = "string_or_float")]
+    pub freeze: f64,
+    #[serde(with = "string_or_float")]
+    pub withdrawing: f64,
+    #[serde(with = "string_or_float")]
+    pub btc_valuation: f64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserAsset {
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub free: f64,
+    #[serde(with = "string_or_float")]
+    pub locked: f64,
+    #[serde(with = "string_or_float")]
+    pub freeze: f64,
+    #[serde(with = "string_or_float")]
+    pub withdrawing: f64,
+    #[serde(with = "string_or_float")]
+    pub ipoable: f64,
+    #[serde(with = "string_or_float")]
+    pub btc_valuation: f64,
+}
+
+// The `{"rows": [...], "total": n}` envelope of paged SAPI endpoints. `total` counts the rows
+// of all pages, and `rows` is left out when there are none.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    #[serde(default = "Vec::new")]
+    pub rows: Vec<T>,
+    pub total: u64,
+}
+
+impl<T> Page<T> {
+    // Whether the rows seen so far, this page included, are all there are
+    #[must_use]
+    pub const fn is_last(&self, seen: u64) -> bool {
+        self.rows.is_empty() || seen >= self.total
+    }
+}
+
+impl<T> IntoIterator for Page<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rows.into_iter()
+    }
+}
+
+pub mod timestamp {
+    use crate::error::Error;
+    use chrono::{DateTime, TimeZone, Utc};
+    use serde::{de, Deserialize, Deserializer, Serializer};
+    use std::convert::TryFrom;
+
+    // Representations of a millisecond timestamp, `Timestamp` and `SignedTimestamp` are one of
+    // them with or without the `chrono-timestamps` feature
+    pub trait Millis: Sized {
+        // `None` if `millis` is out of range
+        fn from_millis(millis: u64) -> Option<Self>;
+        // Times before the epoch are 0
+        fn to_millis(&self) -> u64;
+    }
+
+    impl Millis for u64 {
+        fn from_millis(millis: u64) -> Option<Self> {
+            Some(millis)
+        }
+
+        fn to_millis(&self) -> u64 {
+            *self
+        }
+    }
+
+    impl Millis for i64 {
+        fn from_millis(millis: u64) -> Option<Self> {
+            Self::try_from(millis).ok()
+        }
+
+        fn to_millis(&self) -> u64 {
+            u64::try_from(*self).unwrap_or_default()
+        }
+    }
+
+    impl Millis for DateTime<Utc> {
+        fn from_millis(millis: u64) -> Option<Self> {
+            Utc.timestamp_millis_opt(i64::from_millis(millis)?).single()
+        }
+
+        fn to_millis(&self) -> u64 {
+            self.timestamp_millis().to_millis()
+        }
+    }
+
+    pub fn from_millis<T: Millis>(millis: u64) -> Result<T, Error> {
+        T::from_millis(millis).ok_or(Error::InvalidTimestamp(millis))
+    }
+
+    pub fn to_millis<T: Millis>(timestamp: &T) -> u64 {
+        timestamp.to_millis()
+    }
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: Millis,
+        S: Serializer,
+    {
+        serializer.serialize_u64(value.to_millis())
+    }
+
+    // Some endpoints, e.g. the `/futures/data` statistics, send the milliseconds as a string
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+    where
+        T: Millis,
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Number(u64),
+            String(String),
+        }
+
+        let millis = match Raw::deserialize(deserializer)? {
+            Raw::Number(millis) => millis,
+            Raw::String(millis) => millis.parse().map_err(de::Error::custom)?,
+        };
+        from_millis(millis).map_err(de::Error::custom)
+    }
+}
+
 mod string_or_float {
     use std::fmt;
 
@@ -585,3 +1705,295 @@ mod string_or_float {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::{
+        timestamp, CoinInfo, DepositAddress, DepthLimit, ExchangeFilter, ExchangeInformation,
+        FundingAsset, KlineSummary, Latency, Order, OrderAck, OrderFull, OrderResponse,
+        OrderResult, OrderStatus, Page, RateLimitType, RequestTiming, ServerTime, Symbol,
+        SymbolFilter, SymbolPermission, TimeInForce, Transaction,
+    };
+    use anyhow::Result;
+    use chrono::{DateTime, Utc};
+    use serde_json::{from_str, to_string};
+    use std::convert::TryFrom;
+    use std::time::Duration;
+
+    #[test]
+    fn time_in_force() -> Result<()> {
+        assert_eq!(to_string(&TimeInForce::GTC)?, r#""GTC""#);
+        assert_eq!(from_str::<TimeInForce>(r#""GTX""#)?, TimeInForce::GTX);
+        Ok(())
+    }
+
+    #[test]
+    fn timestamp_roundtrip() -> Result<()> {
+        let time: ServerTime = from_str(r#"{"serverTime":1499827319559}"#)?;
+        assert_eq!(timestamp::to_millis(&time.server_time), 1_499_827_319_559);
+        assert_eq!(to_string(&time)?, r#"{"serverTime":1499827319559}"#);
+
+        // Out of range for `i64` and `DateTime`
+        assert!(timestamp::from_millis::<i64>(u64::MAX).is_err());
+        assert!(timestamp::from_millis::<DateTime<Utc>>(u64::MAX).is_err());
+        assert_eq!(timestamp::from_millis::<u64>(u64::MAX).ok(), Some(u64::MAX));
+        Ok(())
+    }
+
+    #[test]
+    fn symbol_formatting() -> Result<()> {
+        let symbol: Symbol = from_str(
+            r#"{"symbol":"ETHBTC","status":"TRADING","baseAsset":"ETH","baseAssetPrecision":8,"quoteAsset":"BTC","quotePrecision":8,"quoteAssetPrecision":8,"baseCommissionPrecision":8,"quoteCommissionPrecision":8,"orderTypes":["LIMIT","MARKET"],"icebergAllowed":true,"ocoAllowed":true,"quoteOrderQtyMarketAllowed":true,"isSpotTradingAllowed":true,"isMarginTradingAllowed":true,"filters":[{"filterType":"PRICE_FILTER","minPrice":"0.00001000","maxPrice":"922327.00000000","tickSize":"0.00001000"},{"filterType":"LOT_SIZE","minQty":"0.00010000","maxQty":"100000.00000000","stepSize":"0.00010000"},{"filterType":"ICEBERG_PARTS","limit":10},{"filterType":"TRAILING_DELTA","minTrailingAboveDelta":10,"maxTrailingAboveDelta":2000,"minTrailingBelowDelta":10,"maxTrailingBelowDelta":2000},{"filterType":"PERCENT_PRICE_BY_SIDE","bidMultiplierUp":"5","bidMultiplierDown":"0.2","askMultiplierUp":"5","askMultiplierDown":"0.2","avgPriceMins":5},{"filterType":"MAX_NUM_ORDERS","maxNumOrders":200}]}"#,
+        )?;
+        assert_eq!(symbol.filters.len(), 6);
+        assert_eq!(symbol.tick_size(), Some("0.00001000"));
+        assert_eq!(symbol.format_price(0.1 + 0.2), "0.30000");
+        assert_eq!(symbol.format_price(0.054_326_8), "0.05433");
+        assert_eq!(symbol.format_price(0.000_000_7), "0.00000");
+        assert_eq!(symbol.format_qty(1.234_56), "1.2345");
+        assert_eq!(symbol.format_qty(0.3), "0.3000");
+        assert_eq!(symbol.format_qty(12_000_000.0), "12000000.0000");
+        assert_eq!(symbol.format_quote_qty(0.123_456_789), "0.12345678");
+        assert_eq!(symbol.format_quote_qty(0.3), "0.30000000");
+        Ok(())
+    }
+
+    #[test]
+    fn exchange_information() -> Result<()> {
+        let info: ExchangeInformation = from_str(
+            r#"{"timezone":"UTC","serverTime":1565246363776,"rateLimits":[{"rateLimitType":"REQUEST_WEIGHT","interval":"MINUTE","intervalNum":1,"limit":6000}],"exchangeFilters":[{"filterType":"EXCHANGE_MAX_NUM_ORDERS","maxNumOrders":1000}],"symbols":[]}"#,
+        )?;
+        assert_eq!(
+            info.rate_limits[0].rate_limit_type,
+            RateLimitType::RequestWeight
+        );
+        assert_eq!(info.rate_limits[0].limit, 6000);
+        assert_eq!(info.exchange_filters.len(), 1);
+        let info: ExchangeInformation = from_str(
+            r#"{"timezone":"UTC","serverTime":1565246363776,"rateLimits":[],"symbols":[]}"#,
+        )?;
+        assert!(info.exchange_filters.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn symbol_filters() -> Result<()> {
+        let filters: Vec<SymbolFilter> = from_str(
+            r#"[{"filterType":"PRICE_FILTER","minPrice":"0.01","maxPrice":"1000000","tickSize":"0.01"},{"filterType":"PERCENT_PRICE","multiplierUp":"1.3","multiplierDown":"0.7","avgPriceMins":5},{"filterType":"PERCENT_PRICE_BY_SIDE","bidMultiplierUp":"1.2","bidMultiplierDown":"0.2","askMultiplierUp":"5","askMultiplierDown":"0.8","avgPriceMins":1},{"filterType":"LOT_SIZE","minQty":"0.00001","maxQty":"9000","stepSize":"0.00001"},{"filterType":"MIN_NOTIONAL","minNotional":"0.00100000","applyToMarket":true,"avgPriceMins":5},{"filterType":"NOTIONAL","minNotional":"10.00000000","applyMinToMarket":false,"maxNotional":"10000.00000000","applyMaxToMarket":false,"avgPriceMins":5},{"filterType":"ICEBERG_PARTS","limit":10},{"filterType":"MARKET_LOT_SIZE","minQty":"0","maxQty":"120","stepSize":"0"},{"filterType":"MAX_NUM_ORDERS","maxNumOrders":200},{"filterType":"MAX_NUM_ALGO_ORDERS","maxNumAlgoOrders":5},{"filterType":"MAX_NUM_ICEBERG_ORDERS","maxNumIcebergOrders":5},{"filterType":"MAX_POSITION","maxPosition":"10.00000000"},{"filterType":"TRAILING_DELTA","minTrailingAboveDelta":10,"maxTrailingAboveDelta":2000,"minTrailingBelowDelta":10,"maxTrailingBelowDelta":2000},{"filterType":"MAX_NUM_ORDER_AMENDS","maxNumOrderAmends":10},{"filterType":"MAX_NUM_ORDER_LISTS","maxNumOrderLists":20},{"filterType":"T_PLUS_SELL","endTime":1709161200000}]"#,
+        )?;
+        assert_eq!(filters.len(), 16);
+        assert!(matches!(
+            &filters[5],
+            SymbolFilter::Notional { max_notional, .. } if max_notional == "10000.00000000"
+        ));
+        assert!(matches!(
+            filters[12],
+            SymbolFilter::TrailingDelta {
+                max_trailing_below_delta: 2000,
+                ..
+            }
+        ));
+        assert!(matches!(filters[15], SymbolFilter::Other));
+
+        let filters: Vec<ExchangeFilter> = from_str(
+            r#"[{"filterType":"EXCHANGE_MAX_NUM_ORDERS","maxNumOrders":1000},{"filterType":"EXCHANGE_MAX_NUM_ORDER_LISTS","maxNumOrderLists":20},{"filterType":"EXCHANGE_MAX_SOMETHING_NEW","limit":1}]"#,
+        )?;
+        assert!(matches!(
+            filters[0],
+            ExchangeFilter::ExchangeMaxNumOrders { limit: 1000 }
+        ));
+        assert!(matches!(filters[2], ExchangeFilter::Other));
+        Ok(())
+    }
+
+    #[test]
+    fn symbol_permissions() -> Result<()> {
+        let symbol: Symbol = from_str(
+            r#"{"symbol":"BNBBTC","status":"TRADING","baseAsset":"BNB","baseAssetPrecision":8,"quoteAsset":"BTC","quotePrecision":8,"quoteAssetPrecision":8,"baseCommissionPrecision":8,"quoteCommissionPrecision":8,"orderTypes":["LIMIT","MARKET"],"icebergAllowed":true,"ocoAllowed":true,"quoteOrderQtyMarketAllowed":true,"isSpotTradingAllowed":true,"isMarginTradingAllowed":true,"filters":[],"permissions":[],"permissionSets":[["SPOT","TRD_GRP_004","PRE_MARKET"],["TRD_GRP_004","TRD_GRP_005"]]}"#,
+        )?;
+        assert_eq!(
+            symbol.permission_sets[0][1],
+            SymbolPermission::TradingGroup(4)
+        );
+        assert_eq!(
+            symbol.permission_sets[0][2],
+            SymbolPermission::Other("PRE_MARKET".into())
+        );
+        assert!(symbol.is_spot_tradable());
+        // Margin trading is allowed, but not by the permission sets
+        assert!(!symbol.is_margin_tradable());
+        assert!(symbol.permits(&[SymbolPermission::Spot, SymbolPermission::TradingGroup(5)]));
+        assert!(!symbol.permits(&[SymbolPermission::Spot]));
+        assert!(to_string(&symbol)?.contains(r#"["TRD_GRP_004","TRD_GRP_005"]"#));
+
+        let break_symbol = Symbol {
+            status: "BREAK".into(),
+            ..symbol
+        };
+        assert!(!break_symbol.is_spot_tradable());
+        Ok(())
+    }
+
+    #[test]
+    fn depth_limit() {
+        assert_eq!(DepthLimit::try_from(500).ok(), Some(DepthLimit::L500));
+        assert_eq!(DepthLimit::L5000.value(), 5000);
+        assert_eq!(DepthLimit::L5000.weight(), 250);
+        assert_eq!(DepthLimit::default().weight(), 5);
+        assert!(DepthLimit::try_from(42).is_err());
+    }
+
+    #[test]
+    fn order_extra_fields() -> Result<()> {
+        let json = r#"{"symbol":"LTCBTC","orderId":1,"orderListId":-1,"clientOrderId":"myOrder1","price":"0.1","origQty":"1.0","executedQty":"0.0","cummulativeQuoteQty":"0.0","status":"NEW","timeInForce":"GTC","type":"LIMIT","side":"BUY","stopPrice":"0.0","icebergQty":"0.0","time":1499827319559,"updateTime":1499827319559,"isWorking":true,"workingTime":1499827319559,"origQuoteOrderQty":"0.000000","selfTradePreventionMode":"NONE"}"#;
+        let order: Order = from_str(json)?;
+        assert_eq!(order.order_id, 1);
+        assert_eq!(order.extra["selfTradePreventionMode"], "NONE");
+        assert_eq!(order.extra["workingTime"], 1_499_827_319_559_u64);
+        assert!(!order.extra.contains_key("symbol"));
+        Ok(())
+    }
+
+    #[test]
+    fn order_responses() -> Result<()> {
+        let ack: OrderAck = from_str(
+            r#"{"symbol":"BTCUSDT","orderId":28,"orderListId":-1,"clientOrderId":"6gCrw2kRUAF9CvJDGP16IP","transactTime":1507725176595}"#,
+        )?;
+        assert_eq!(ack.order_id, 28);
+        assert_eq!(ack.order_list_id, -1);
+
+        let result: OrderResult = from_str(
+            r#"{"symbol":"BTCUSDT","orderId":28,"orderListId":-1,"clientOrderId":"6gCrw2kRUAF9CvJDGP16IP","transactTime":1507725176595,"price":"0.00000000","origQty":"10.00000000","executedQty":"10.00000000","cummulativeQuoteQty":"10.00000000","status":"FILLED","timeInForce":"GTC","type":"MARKET","side":"SELL","workingTime":1507725176595,"selfTradePreventionMode":"NONE"}"#,
+        )?;
+        assert_eq!(result.status, OrderStatus::Filled);
+
+        let full: OrderFull = from_str(
+            r#"{"symbol":"BTCUSDT","orderId":28,"orderListId":-1,"clientOrderId":"6gCrw2kRUAF9CvJDGP16IP","transactTime":1507725176595,"price":"0.00000000","origQty":"10.00000000","executedQty":"10.00000000","cummulativeQuoteQty":"10.00000000","status":"FILLED","timeInForce":"GTC","type":"MARKET","side":"SELL","workingTime":1507725176595,"selfTradePreventionMode":"NONE","fills":[{"price":"4000.00000000","qty":"1.00000000","commission":"4.00000000","commissionAsset":"USDT","tradeId":56},{"price":"3999.00000000","qty":"5.00000000","commission":"19.99500000","commissionAsset":"USDT","tradeId":57}]}"#,
+        )?;
+        assert_eq!(full.result.order_id, 28);
+        assert_eq!(full.fills.len(), 2);
+        assert_eq!(full.fills[1].trade_id, 57);
+        assert!((full.fills[1].qty - 5.0).abs() < f64::EPSILON);
+        assert_eq!(
+            timestamp::to_millis(full.transact_time()),
+            1_507_725_176_595
+        );
+
+        let timing = RequestTiming {
+            sent_at: 1_507_725_176_590,
+            received_at: 1_507_725_176_601,
+            server_transact_time: timestamp::to_millis(ack.transact_time()),
+        };
+        assert_eq!(timing.round_trip(), Duration::from_millis(11));
+        assert_eq!(timing.to_exchange_ms(), 5);
+        assert_eq!(timing.from_exchange_ms(), 6);
+        Ok(())
+    }
+
+    #[test]
+    fn transaction_fills() -> Result<()> {
+        let json = r#"{"symbol":"BTCUSDT","orderId":28,"orderListId":-1,"clientOrderId":"6gCrw2kRUAF9CvJDGP16IP","transactTime":1507725176595,"price":"0.00000000","origQty":"10.00000000","executedQty":"10.00000000","cummulativeQuoteQty":"10.00000000","status":"FILLED","timeInForce":"GTC","type":"MARKET","side":"SELL","fills":[{"price":"4000.00000000","qty":"1.00000000","commission":"4.00000000","commissionAsset":"USDT","tradeId":56},{"price":"3999.00000000","qty":"3.00000000","commission":"11.99700000","commissionAsset":"USDT","tradeId":57}]}"#;
+        let transaction: Transaction = from_str(json)?;
+        assert_eq!(transaction.fills.len(), 2);
+        assert_eq!(transaction.fills[0].commission_asset, "USDT");
+        assert!(!transaction.extra.contains_key("fills"));
+        let avg_price = transaction.avg_price().unwrap_or_default();
+        assert!((avg_price - 3999.25).abs() < 1e-9);
+
+        let ack: Transaction = from_str(
+            r#"{"symbol":"BTCUSDT","orderId":28,"orderListId":-1,"clientOrderId":"6gCrw2kRUAF9CvJDGP16IP","transactTime":1507725176595}"#,
+        )?;
+        assert!(ack.fills.is_empty());
+        assert_eq!(ack.avg_price(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn funding_asset() -> Result<()> {
+        let json = r#"[{"asset":"USDT","free":"1","locked":"0","freeze":"0","withdrawing":"0","btcValuation":"0.00000091"}]"#;
+        let assets: Vec<FundingAsset> = from_str(json)?;
+        assert_eq!(assets[0].asset, "USDT");
+        assert!((assets[0].btc_valuation - 0.000_000_91).abs() < f64::EPSILON);
+        Ok(())
+    }
+
+    #[test]
+    fn deposit_address() -> Result<()> {
+        let address: DepositAddress = from_str(
+            r#"{"address":"1HPn8Rx2y6nNSfagQBKy27GB99Vbzg89wv","coin":"BTC","tag":"","url":"https://btc.com/1HPn8Rx2y6nNSfagQBKy27GB99Vbzg89wv"}"#,
+        )?;
+        assert!(address.memo().is_none());
+        let addresses: Vec<DepositAddress> = from_str(
+            r#"[{"coin":"XRP","address":"rEb8TK3gBgk5auZkwc6sHnwrGVJH8DuaLh","tag":"101791652","isDefault":1}]"#,
+        )?;
+        assert_eq!(addresses[0].memo(), Some("101791652"));
+        assert_eq!(addresses[0].is_default, Some(1));
+        Ok(())
+    }
+
+    #[test]
+    fn withdraw_quote() -> Result<()> {
+        let coins: Vec<CoinInfo> = from_str(
+            r#"[{"coin":"BTC","depositAllEnable":true,"free":"0.08074558","freeze":"0","ipoable":"0","ipoing":"0","isLegalMoney":false,"locked":"0","name":"Bitcoin","networkList":[{"addressRegex":"^(bnb1)[0-9a-z]{38}$","coin":"BTC","depositDesc":"Wallet Maintenance, Deposit Suspended","depositEnable":false,"isDefault":false,"memoRegex":"^[0-9A-Za-z\\-_]{1,120}$","minConfirm":1,"name":"BEP2","network":"BNB","specialTips":"Both a MEMO and an Address are required to successfully deposit your BEP2-BTCB tokens to Binance.","unLockConfirm":0,"withdrawDesc":"Wallet Maintenance, Withdrawal Suspended","withdrawEnable":false,"withdrawFee":"0.00000220","withdrawIntegerMultiple":"0.00000001","withdrawMax":"9999999999.99999999","withdrawMin":"0.00000440","sameAddress":true,"estimatedArrivalTime":25,"busy":false},{"addressRegex":"^[13][a-km-zA-HJ-NP-Z1-9]{25,34}$|^(bc1)[0-9A-Za-z]{39,59}$","coin":"BTC","depositEnable":true,"isDefault":true,"memoRegex":"","minConfirm":1,"name":"BTC","network":"BTC","specialTips":"","unLockConfirm":2,"withdrawEnable":true,"withdrawFee":"0.00050000","withdrawIntegerMultiple":"0.00000001","withdrawMax":"750","withdrawMin":"0.00100000","sameAddress":false,"estimatedArrivalTime":25,"busy":false}],"storage":"0","trading":true,"withdrawAllEnable":true,"withdrawing":"0"}]"#,
+        )?;
+        let btc = &coins[0];
+        let quote = btc.withdraw_quote(None, 0.05)?;
+        assert_eq!(quote.network, "BTC");
+        assert!((quote.received - 0.0495).abs() < 1e-12);
+        assert!(!quote.requires_memo);
+        assert!(btc.withdraw_quote(Some("btc"), 0.0005).is_err());
+        assert!(btc.withdraw_quote(None, 0.001_000_005).is_err());
+        assert!(btc.withdraw_quote(Some("BNB"), 0.01).is_err());
+        assert!(btc.withdraw_quote(Some("ETH"), 0.01).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn kline_summary() -> Result<()> {
+        let klines: Vec<KlineSummary> = from_str(
+            r#"[[1499040000000,"0.01634790","0.80000000","0.01575800","0.01577100","148976.11427815",1499644799999,"2434.19055334",308,"1756.87402397","28.46694368","0"],[1499040000000,"1","2","0.5","1.5","10",1499644799999,"15",3,"5","7.5"]]"#,
+        )?;
+        assert_eq!(
+            timestamp::to_millis(&klines[0].open_time),
+            1_499_040_000_000
+        );
+        assert!((klines[0].high - 0.8).abs() < f64::EPSILON);
+        assert_eq!(klines[0].number_of_trades, 308);
+        assert!((klines[1].taker_buy_quote_asset_volume - 7.5).abs() < f64::EPSILON);
+        assert!(from_str::<KlineSummary>(r#"[1499040000000,"0.01634790"]"#).is_err());
+        assert!(from_str::<KlineSummary>(
+            r#"[1499040000000,"x","2","0.5","1.5","10",1499644799999,"15",3,"5","7.5"]"#
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn page() -> Result<()> {
+        let page: Page<DepositAddress> = from_str(
+            r#"{"rows":[{"coin":"BTC","address":"1HPn8Rx2y6nNSfagQBKy27GB99Vbzg89wv","tag":""}],"total":3}"#,
+        )?;
+        assert!(!page.is_last(1));
+        assert!(page.is_last(3));
+        assert_eq!(page.into_iter().next().unwrap().coin, "BTC");
+        let empty: Page<DepositAddress> = from_str(r#"{"total":0}"#)?;
+        assert!(empty.rows.is_empty());
+        assert!(empty.is_last(0));
+        Ok(())
+    }
+
+    #[test]
+    fn latency_from_samples() {
+        let samples: Vec<(Duration, i64)> = (1..=100)
+            .rev()
+            .map(|ms| (Duration::from_millis(ms), i64::try_from(ms).unwrap() - 50))
+            .collect();
+        let latency = Latency::from_samples(&samples).unwrap();
+        assert_eq!(latency.samples, 100);
+        assert_eq!(latency.min, Duration::from_millis(1));
+        assert_eq!(latency.median, Duration::from_millis(51));
+        assert_eq!(latency.p99, Duration::from_millis(99));
+        assert_eq!(latency.clock_offset_ms, -49);
+        assert!(Latency::from_samples(&[]).is_none());
+    }
+}
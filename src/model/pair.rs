@@ -0,0 +1,232 @@
+use super::Symbol;
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+use std::{
+    convert::TryFrom,
+    fmt,
+    hash::{Hash, Hasher},
+    ops::Deref,
+    str::FromStr,
+};
+
+// Quote assets tried, longest first, when splitting a symbol without exchange info
+const KNOWN_QUOTE_ASSETS: &[&str] = &[
+    "FDUSD", "USDT", "USDC", "BUSD", "TUSD", "USDP", "DOGE", "DAI", "BTC", "ETH", "BNB", "EUR",
+    "GBP", "TRY", "BRL", "JPY", "AUD", "XRP", "TRX",
+];
+
+// Normalized, validated symbol such as `BTCUSDT`, taken by every client method taking a
+// symbol. Pairs compare by symbol only, whether or not the assets are known.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TradingPair {
+    symbol: String,
+    assets: Option<(String, String)>,
+}
+
+impl TradingPair {
+    pub fn new(symbol: &str) -> Result<Self, Error> {
+        let symbol = symbol.trim().to_uppercase();
+        if symbol.is_empty() || !symbol.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(Error::InvalidSymbol(symbol));
+        }
+        Ok(Self {
+            symbol,
+            assets: None,
+        })
+    }
+
+    pub fn with_assets(base_asset: &str, quote_asset: &str) -> Result<Self, Error> {
+        let base_asset = base_asset.trim().to_uppercase();
+        let quote_asset = quote_asset.trim().to_uppercase();
+        let mut pair = Self::new(&format!("{base_asset}{quote_asset}"))?;
+        pair.assets = Some((base_asset, quote_asset));
+        Ok(pair)
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.symbol
+    }
+
+    #[must_use]
+    pub fn to_lowercase(&self) -> String {
+        self.symbol.to_lowercase()
+    }
+
+    // Base asset as listed in exchange info, or guessed from well-known quote assets
+    #[must_use]
+    pub fn base_asset(&self) -> Option<&str> {
+        self.split().map(|(base, _)| base)
+    }
+
+    // Quote asset as listed in exchange info, or guessed from well-known quote assets
+    #[must_use]
+    pub fn quote_asset(&self) -> Option<&str> {
+        self.split().map(|(_, quote)| quote)
+    }
+
+    fn split(&self) -> Option<(&str, &str)> {
+        if let Some((base, quote)) = &self.assets {
+            return Some((base, quote));
+        }
+        KNOWN_QUOTE_ASSETS.iter().find_map(|quote| {
+            self.symbol
+                .strip_suffix(quote)
+                .filter(|base| !base.is_empty())
+                .map(|base| (base, &self.symbol[base.len()..]))
+        })
+    }
+}
+
+impl From<&Symbol> for TradingPair {
+    fn from(symbol: &Symbol) -> Self {
+        Self {
+            symbol: symbol.symbol.clone(),
+            assets: Some((symbol.base_asset.clone(), symbol.quote_asset.clone())),
+        }
+    }
+}
+
+impl PartialEq for TradingPair {
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+    }
+}
+
+impl Eq for TradingPair {}
+
+impl Hash for TradingPair {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.symbol.hash(state);
+    }
+}
+
+// Symbol argument of the client methods: a `TradingPair`, or a `&str` or `String` validated and
+// normalized into one before the request is built
+pub trait IntoTradingPair {
+    fn into_trading_pair(self) -> Result<TradingPair, Error>;
+}
+
+impl IntoTradingPair for TradingPair {
+    fn into_trading_pair(self) -> Result<TradingPair, Error> {
+        Ok(self)
+    }
+}
+
+impl IntoTradingPair for &TradingPair {
+    fn into_trading_pair(self) -> Result<TradingPair, Error> {
+        Ok(self.clone())
+    }
+}
+
+impl IntoTradingPair for &str {
+    fn into_trading_pair(self) -> Result<TradingPair, Error> {
+        TradingPair::new(self)
+    }
+}
+
+impl IntoTradingPair for String {
+    fn into_trading_pair(self) -> Result<TradingPair, Error> {
+        TradingPair::new(&self)
+    }
+}
+
+impl IntoTradingPair for &String {
+    fn into_trading_pair(self) -> Result<TradingPair, Error> {
+        TradingPair::new(self)
+    }
+}
+
+impl FromStr for TradingPair {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl TryFrom<String> for TradingPair {
+    type Error = Error;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::new(&s)
+    }
+}
+
+impl From<TradingPair> for String {
+    fn from(pair: TradingPair) -> Self {
+        pair.symbol
+    }
+}
+
+impl Deref for TradingPair {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.symbol
+    }
+}
+
+impl AsRef<str> for TradingPair {
+    fn as_ref(&self) -> &str {
+        &self.symbol
+    }
+}
+
+impl fmt::Display for TradingPair {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.symbol)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{IntoTradingPair, TradingPair, KNOWN_QUOTE_ASSETS};
+    use anyhow::Result;
+    use std::collections::HashSet;
+
+    #[test]
+    fn normalizes_case() -> Result<()> {
+        let pair: TradingPair = " btcusdt".parse()?;
+        assert_eq!(pair.as_str(), "BTCUSDT");
+        assert_eq!(pair.to_lowercase(), "btcusdt");
+        assert!(TradingPair::new("btc-usdt").is_err());
+        assert!(TradingPair::new("").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn splits_assets() -> Result<()> {
+        let pair = TradingPair::new("ethbtc")?;
+        assert_eq!(pair.base_asset(), Some("ETH"));
+        assert_eq!(pair.quote_asset(), Some("BTC"));
+        assert_eq!(TradingPair::new("btcfdusd")?.base_asset(), Some("BTC"));
+        assert_eq!(TradingPair::new("USDT")?.base_asset(), None);
+
+        let pair = TradingPair::with_assets("1000sats", "usdt")?;
+        assert_eq!(pair.as_str(), "1000SATSUSDT");
+        assert_eq!(pair.base_asset(), Some("1000SATS"));
+        Ok(())
+    }
+
+    #[test]
+    fn converts_arguments() -> Result<()> {
+        let pair = TradingPair::new("ethbtc")?;
+        assert_eq!(" ethbtc".into_trading_pair()?, pair);
+        assert_eq!(String::from("EthBtc").into_trading_pair()?, pair);
+        assert_eq!((&pair).into_trading_pair()?.as_str(), "ETHBTC");
+        assert!("eth/btc".into_trading_pair().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn compares_symbols() -> Result<()> {
+        let pairs = HashSet::from([TradingPair::with_assets("eth", "btc")?]);
+        assert!(pairs.contains(&TradingPair::new("ETHBTC")?));
+        assert!(KNOWN_QUOTE_ASSETS
+            .windows(2)
+            .all(|quotes| quotes[0].len() >= quotes[1].len()));
+        Ok(())
+    }
+}
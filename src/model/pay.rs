@@ -0,0 +1,119 @@
+use super::{string_or_float, timestamp, Timestamp};
+use crate::error::Error;
+use serde::{Deserialize, Serialize};
+
+// Pay and Gift Card endpoints wrap their payload as `{"code": "000000", "data": ...}`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PayResponse<T> {
+    pub code: String,
+    pub message: String,
+    pub data: Option<T>,
+    #[serde(default)]
+    pub success: bool,
+}
+
+impl<T> PayResponse<T> {
+    pub fn into_data(self) -> Result<T, Error> {
+        match self.data {
+            Some(data) if self.success => Ok(data),
+            _ => Err(Error::BinanceError {
+                code: self.code.parse().unwrap_or_default(),
+                msg: self.message,
+            }),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PayTransaction {
+    pub order_type: String,
+    pub transaction_id: String,
+    #[serde(with = "timestamp")]
+    pub transaction_time: Timestamp,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    pub currency: String,
+    pub wallet_type: Option<u32>,
+    #[serde(default)]
+    pub wallet_types: Vec<u32>,
+    #[serde(default)]
+    pub funds_detail: Vec<PayFundsDetail>,
+    pub payer_info: Option<PayParty>,
+    pub receiver_info: Option<PayParty>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PayFundsDetail {
+    pub currency: String,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PayParty {
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub party_type: Option<String>,
+    pub binance_id: Option<u64>,
+    pub account_id: Option<u64>,
+    pub email: Option<String>,
+    pub country_code: Option<u32>,
+    pub phone_number: Option<String>,
+    pub mobile_code: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GiftCard {
+    pub reference_no: String,
+    pub code: String,
+    #[serde(with = "timestamp")]
+    pub expired_time: Timestamp,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RedeemedGiftCard {
+    pub token: String,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    pub reference_no: String,
+    pub identity_no: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct GiftCardVerification {
+    pub valid: bool,
+    pub token: String,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{GiftCard, PayResponse, PayTransaction};
+    use anyhow::Result;
+    use serde_json::from_str;
+
+    #[test]
+    fn pay_transactions() -> Result<()> {
+        let json = r#"{"code":"000000","message":"success","data":[{"orderType":"C2C","transactionId":"M_P_71505104267788288","transactionTime":1610090460133,"amount":"23.72469206","currency":"BNB","walletType":1,"walletTypes":[1,2],"fundsDetail":[{"currency":"USDT","amount":"1.2"}],"payerInfo":{"name":"Jack","type":"USER","binanceId":12345678,"accountId":67736251},"receiverInfo":{"name":"Alan","type":"MERCHANT","email":"alan@binance.com","binanceId":34355667,"accountId":21326891,"countryCode":1,"phoneNumber":"8057651210","mobileCode":"US"}}],"success":true}"#;
+        let transactions = from_str::<PayResponse<Vec<PayTransaction>>>(json)?.into_data()?;
+        assert_eq!(transactions[0].transaction_id, "M_P_71505104267788288");
+        assert!((transactions[0].funds_detail[0].amount - 1.2).abs() < f64::EPSILON);
+        Ok(())
+    }
+
+    #[test]
+    fn pay_response_error() {
+        let json =
+            r#"{"code":"12001","message":"Gift card code not found","data":null,"success":false}"#;
+        let response: PayResponse<GiftCard> = from_str(json).unwrap();
+        assert!(response.into_data().is_err());
+    }
+}
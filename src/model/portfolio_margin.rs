@@ -0,0 +1,129 @@
+use super::{string_or_float, timestamp, OrderType, Side, TimeInForce, Timestamp};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioMarginAccount {
+    #[serde(rename = "uniMMR", with = "string_or_float")]
+    pub uni_mmr: f64,
+    #[serde(with = "string_or_float")]
+    pub account_equity: f64,
+    #[serde(with = "string_or_float")]
+    pub actual_equity: f64,
+    #[serde(with = "string_or_float")]
+    pub account_initial_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub account_maint_margin: f64,
+    pub account_status: String,
+    #[serde(with = "string_or_float")]
+    pub virtual_max_withdraw_amount: f64,
+    #[serde(with = "string_or_float")]
+    pub total_available_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub total_margin_open_loss: f64,
+    #[serde(with = "timestamp")]
+    pub update_time: Timestamp,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioMarginBalance {
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub total_wallet_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub cross_margin_asset: f64,
+    #[serde(with = "string_or_float")]
+    pub cross_margin_borrowed: f64,
+    #[serde(with = "string_or_float")]
+    pub cross_margin_free: f64,
+    #[serde(with = "string_or_float")]
+    pub cross_margin_interest: f64,
+    #[serde(with = "string_or_float")]
+    pub cross_margin_locked: f64,
+    #[serde(with = "string_or_float")]
+    pub um_wallet_balance: f64,
+    #[serde(rename = "umUnrealizedPNL", with = "string_or_float")]
+    pub um_unrealized_pnl: f64,
+    #[serde(with = "string_or_float")]
+    pub cm_wallet_balance: f64,
+    #[serde(rename = "cmUnrealizedPNL", with = "string_or_float")]
+    pub cm_unrealized_pnl: f64,
+    #[serde(with = "timestamp")]
+    pub update_time: Timestamp,
+    #[serde(with = "string_or_float")]
+    pub negative_balance: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CollateralRate {
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub collateral_rate: f64,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioMarginOrderRequest {
+    pub symbol: String,
+    pub side: Side,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    pub quantity: Option<f64>,
+    pub price: Option<f64>,
+    pub time_in_force: Option<TimeInForce>,
+    pub reduce_only: Option<bool>,
+    pub new_client_order_id: Option<String>,
+}
+
+impl PortfolioMarginOrderRequest {
+    #[must_use]
+    pub fn market(symbol: &str, side: Side, quantity: f64) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            side,
+            order_type: OrderType::Market,
+            quantity: Some(quantity),
+            price: None,
+            time_in_force: None,
+            reduce_only: None,
+            new_client_order_id: None,
+        }
+    }
+
+    #[must_use]
+    pub fn limit(symbol: &str, side: Side, quantity: f64, price: f64) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            side,
+            order_type: OrderType::Limit,
+            quantity: Some(quantity),
+            price: Some(price),
+            time_in_force: Some(TimeInForce::GTC),
+            reduce_only: None,
+            new_client_order_id: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioMarginOrder {
+    pub symbol: String,
+    pub order_id: u64,
+    pub client_order_id: String,
+    #[serde(default, with = "string_or_float")]
+    pub price: f64,
+    #[serde(default, with = "string_or_float")]
+    pub orig_qty: f64,
+    #[serde(default, with = "string_or_float")]
+    pub executed_qty: f64,
+    pub status: String,
+    pub time_in_force: Option<String>,
+    #[serde(rename = "type")]
+    pub type_name: String,
+    pub side: Side,
+    #[serde(with = "timestamp")]
+    pub update_time: Timestamp,
+}
@@ -0,0 +1,82 @@
+use super::{string_or_float, timestamp, Timestamp};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RebateHistory {
+    pub status: String,
+    #[serde(rename = "type")]
+    pub response_type: String,
+    pub code: String,
+    pub data: RebatePage,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RebatePage {
+    pub page: u32,
+    pub total_records: u64,
+    pub total_page_num: u32,
+    pub data: Vec<Rebate>,
+}
+
+// `rebate_type` 1 is a commission rebate, 2 a referral kickback
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Rebate {
+    pub asset: String,
+    #[serde(rename = "type")]
+    pub rebate_type: u32,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    #[serde(with = "timestamp")]
+    pub update_time: Timestamp,
+}
+
+// Commission earned on a trade of a user registered with the API referral program
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ReferralRebate {
+    pub customer_id: Option<String>,
+    pub email: Option<String>,
+    #[serde(with = "string_or_float")]
+    pub income: f64,
+    pub asset: String,
+    pub symbol: String,
+    #[serde(with = "timestamp")]
+    pub time: Timestamp,
+    pub order_id: u64,
+    pub trade_id: u64,
+}
+
+// Commission earned on a trade of a broker sub-account
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerRebate {
+    #[serde(rename = "subaccountId")]
+    pub sub_account_id: String,
+    #[serde(with = "string_or_float")]
+    pub income: f64,
+    pub asset: String,
+    pub symbol: String,
+    pub trade_id: u64,
+    #[serde(with = "timestamp")]
+    pub time: Timestamp,
+    pub status: u32,
+}
+
+#[cfg(test)]
+mod test {
+    use super::RebateHistory;
+    use anyhow::Result;
+    use serde_json::from_str;
+
+    #[test]
+    fn rebate_history() -> Result<()> {
+        let json = r#"{"status":"OK","type":"GENERAL","code":"000000000","data":{"page":1,"totalRecords":2,"totalPageNum":1,"data":[{"asset":"USDT","type":1,"amount":"0.0001126","updateTime":1637651320000},{"asset":"ETH","type":1,"amount":"0.00000056","updateTime":1637928379000}]}}"#;
+        let history: RebateHistory = from_str(json)?;
+        assert_eq!(history.data.total_records, 2);
+        assert_eq!(history.data.data[1].asset, "ETH");
+        Ok(())
+    }
+}
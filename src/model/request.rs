@@ -0,0 +1,965 @@
+//! Query parameters of the REST endpoints. `None` fields are left out of the request.
+
+use super::dual_investment::{AutoCompoundPlan, DualInvestmentOptionType};
+use super::futures::MarginType;
+use super::{OrderResponseType, OrderType, Side, Symbol, TimeInForce, WalletType};
+use serde::{Serialize, Serializer};
+use serde_json::Number;
+
+// Chainable setters of the optional fields of a request, `field(value)` sets `Some(value)`.
+// String fields take a `&str`. Decimal fields take an `f64` and hold it as a plain decimal
+// string.
+macro_rules! setters {
+    (
+        $request:ident { $($field:ident: $value:ty),* $(,)? }
+        $(strings { $($string:ident),* $(,)? })?
+        $(decimals { $($decimal:ident),* $(,)? })?
+    ) => {
+        impl $request {
+            $(
+                #[must_use]
+                pub const fn $field(mut self, $field: $value) -> Self {
+                    self.$field = Some($field);
+                    self
+                }
+            )*
+            $($(
+                #[must_use]
+                pub fn $string(mut self, $string: &str) -> Self {
+                    self.$string = Some($string.into());
+                    self
+                }
+            )*)?
+            $($(
+                #[must_use]
+                pub fn $decimal(mut self, $decimal: f64) -> Self {
+                    self.$decimal = Some($decimal.to_string());
+                    self
+                }
+            )*)?
+        }
+    };
+}
+
+// A JSON number as a query parameter. Floats are printed in full, serde_json writes 0.00000812
+// as "8.12e-6" and 1e16 as "1e16", which the exchange rejects.
+pub(crate) fn query_number(number: &Number) -> String {
+    match number.as_f64() {
+        Some(float) if number.is_f64() => float.to_string(),
+        _ => number.to_string(),
+    }
+}
+
+// Asset, coin and network codes go out in upper case, whatever case they were passed in
+fn upper_case<S: Serializer>(code: &str, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&code.to_uppercase())
+}
+
+#[allow(clippy::ref_option)]
+fn upper_case_opt<S: Serializer>(code: &Option<String>, serializer: S) -> Result<S::Ok, S::Error> {
+    match code {
+        Some(code) => serializer.serialize_some(&code.to_uppercase()),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolRequest {
+    pub symbol: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenOrdersRequest {
+    pub symbol: Option<String>,
+}
+
+setters!(OpenOrdersRequest {} strings {
+    symbol,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderQueryRequest {
+    pub symbol: String,
+    pub order_id: Option<u64>,
+    pub orig_client_order_id: Option<String>,
+}
+
+setters!(OrderQueryRequest {
+    order_id: u64,
+} strings {
+    orig_client_order_id,
+});
+
+// A spot order, `/api/v3/order` and the websocket API's `order.place`. Amounts and prices are
+// decimal strings as sent, never in exponent notation.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct NewOrderRequest {
+    pub symbol: String,
+    pub side: Side,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    pub quantity: Option<String>,
+    // MARKET orders only, the amount of the quote asset to spend or receive instead of `quantity`
+    pub quote_order_qty: Option<String>,
+    pub price: Option<String>,
+    // GTC, IOC or FOK for the limit order types
+    pub time_in_force: Option<TimeInForce>,
+    // Price that triggers STOP_LOSS and TAKE_PROFIT orders, with `trailing_delta` the price
+    // the trailing starts at
+    pub stop_price: Option<String>,
+    // Trailing stop distance in basis points of the best price since the order was placed
+    pub trailing_delta: Option<u32>,
+    pub new_client_order_id: Option<String>,
+    pub new_order_resp_type: Option<OrderResponseType>,
+}
+
+impl NewOrderRequest {
+    #[must_use]
+    pub fn market(symbol: &str, side: Side, quantity: f64) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            side,
+            order_type: OrderType::Market,
+            quantity: Some(quantity.to_string()),
+            quote_order_qty: None,
+            price: None,
+            time_in_force: None,
+            stop_price: None,
+            trailing_delta: None,
+            new_client_order_id: None,
+            new_order_resp_type: None,
+        }
+    }
+
+    #[must_use]
+    pub fn market_quote(symbol: &str, side: Side, quote_order_qty: f64) -> Self {
+        Self {
+            quantity: None,
+            quote_order_qty: Some(quote_order_qty.to_string()),
+            ..Self::market(symbol, side, 0.0)
+        }
+    }
+
+    #[must_use]
+    pub fn limit(symbol: &str, side: Side, quantity: f64, price: f64) -> Self {
+        Self {
+            symbol: symbol.to_uppercase(),
+            side,
+            order_type: OrderType::Limit,
+            quantity: Some(quantity.to_string()),
+            quote_order_qty: None,
+            price: Some(price.to_string()),
+            time_in_force: Some(TimeInForce::GTC),
+            stop_price: None,
+            trailing_delta: None,
+            new_client_order_id: None,
+            new_order_resp_type: None,
+        }
+    }
+
+    // MARKET order once the price reaches `stop_price`, `OrderType::StopLoss` or `TakeProfit`
+    #[must_use]
+    pub fn triggered(
+        symbol: &str,
+        side: Side,
+        order_type: OrderType,
+        quantity: f64,
+        stop_price: f64,
+    ) -> Self {
+        Self {
+            order_type,
+            stop_price: Some(stop_price.to_string()),
+            ..Self::market(symbol, side, quantity)
+        }
+    }
+
+    // LIMIT order at `price` once the price reaches `stop_price`, `OrderType::StopLossLimit` or
+    // `TakeProfitLimit`
+    #[must_use]
+    pub fn triggered_limit(
+        symbol: &str,
+        side: Side,
+        order_type: OrderType,
+        quantity: f64,
+        price: f64,
+        stop_price: f64,
+    ) -> Self {
+        Self {
+            order_type,
+            stop_price: Some(stop_price.to_string()),
+            ..Self::limit(symbol, side, quantity, price)
+        }
+    }
+
+    // LIMIT order that is rejected instead of taking liquidity, the spot post-only order
+    #[must_use]
+    pub fn limit_maker(symbol: &str, side: Side, quantity: f64, price: f64) -> Self {
+        Self {
+            order_type: OrderType::LimitMaker,
+            time_in_force: None,
+            ..Self::limit(symbol, side, quantity, price)
+        }
+    }
+
+    #[must_use]
+    pub fn client_order_id(mut self, id: &str) -> Self {
+        self.new_client_order_id = Some(id.into());
+        self
+    }
+
+    #[must_use]
+    pub const fn response_type(mut self, response_type: OrderResponseType) -> Self {
+        self.new_order_resp_type = Some(response_type);
+        self
+    }
+
+    // Prices rounded to the tick size, the quantity down to the step size and the quote
+    // quantity down to the quote precision of `symbol`, so they carry the decimals the
+    // exchange accepts
+    #[must_use]
+    pub fn rounded_to(mut self, symbol: &Symbol) -> Self {
+        let snap =
+            |value: String, format: &dyn Fn(f64) -> String| value.parse().map_or(value, format);
+        self.quantity = self
+            .quantity
+            .map(|qty| snap(qty, &|qty| symbol.format_qty(qty)));
+        self.quote_order_qty = self
+            .quote_order_qty
+            .map(|qty| snap(qty, &|qty| symbol.format_quote_qty(qty)));
+        self.price = self
+            .price
+            .map(|price| snap(price, &|price| symbol.format_price(price)));
+        self.stop_price = self
+            .stop_price
+            .map(|price| snap(price, &|price| symbol.format_price(price)));
+        self
+    }
+}
+
+setters!(NewOrderRequest {
+    time_in_force: TimeInForce,
+    trailing_delta: u32,
+} decimals {
+    quantity,
+    quote_order_qty,
+    price,
+    stop_price,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AllOrdersRequest {
+    pub symbol: String,
+    pub order_id: Option<u64>,
+    pub limit: Option<u16>,
+}
+
+setters!(AllOrdersRequest {
+    order_id: u64,
+    limit: u16,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MyTradesRequest {
+    pub symbol: String,
+    pub from_id: Option<u64>,
+    pub limit: Option<u16>,
+}
+
+setters!(MyTradesRequest {
+    from_id: u64,
+    limit: u16,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct BnbBurnRequest {
+    #[serde(rename = "spotBNBBurn")]
+    pub spot_bnb_burn: Option<bool>,
+    #[serde(rename = "interestBNBBurn")]
+    pub interest_bnb_burn: Option<bool>,
+}
+
+setters!(BnbBurnRequest {
+    spot_bnb_burn: bool,
+    interest_bnb_burn: bool,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DepthRequest {
+    pub symbol: String,
+    pub limit: Option<u64>,
+}
+
+setters!(DepthRequest { limit: u64 });
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoricalTradesRequest {
+    pub symbol: String,
+    pub limit: Option<u16>,
+    pub from_id: Option<u64>,
+}
+
+setters!(HistoricalTradesRequest {
+    limit: u16,
+    from_id: u64,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AggTradesRequest {
+    pub symbol: String,
+    pub from_id: Option<u64>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub limit: Option<u16>,
+}
+
+setters!(AggTradesRequest {
+    from_id: u64,
+    start_time: u64,
+    end_time: u64,
+    limit: u16,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct KlinesRequest {
+    pub symbol: String,
+    pub interval: String,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub limit: Option<u16>,
+}
+
+setters!(KlinesRequest {
+    start_time: u64,
+    end_time: u64,
+    limit: u16,
+});
+
+// `symbols` and `permissions` are JSON arrays, e.g. `["BTCUSDT","BNBBTC"]`
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeInfoRequest {
+    pub symbol: Option<String>,
+    pub symbols: Option<String>,
+    pub permissions: Option<String>,
+}
+
+impl ExchangeInfoRequest {
+    pub fn for_symbols<S: AsRef<str>>(symbols: &[S]) -> Self {
+        match symbols {
+            [symbol] => Self {
+                symbol: Some(symbol.as_ref().to_uppercase()),
+                ..Self::default()
+            },
+            _ => Self {
+                symbols: Some(json_array(
+                    symbols.iter().map(|s| s.as_ref().to_uppercase()),
+                )),
+                ..Self::default()
+            },
+        }
+    }
+
+    pub fn for_permissions<S: AsRef<str>>(permissions: &[S]) -> Self {
+        Self {
+            permissions: Some(json_array(
+                permissions.iter().map(|p| p.as_ref().to_uppercase()),
+            )),
+            ..Self::default()
+        }
+    }
+}
+
+setters!(ExchangeInfoRequest {} strings {
+    symbol,
+});
+
+fn json_array(items: impl Iterator<Item = String>) -> String {
+    serde_json::to_string(&items.collect::<Vec<_>>()).unwrap_or_default()
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PayTransactionsRequest {
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub limit: Option<u16>,
+}
+
+setters!(PayTransactionsRequest {
+    start_time: u64,
+    end_time: u64,
+    limit: u16,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGiftCardRequest {
+    #[serde(serialize_with = "upper_case")]
+    pub token: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RedeemGiftCardRequest {
+    pub code: String,
+    pub external_uid: Option<String>,
+}
+
+setters!(RedeemGiftCardRequest {} strings {
+    external_uid,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VerifyGiftCardRequest {
+    pub reference_no: String,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VipLoanOrdersRequest {
+    pub order_id: Option<u64>,
+    pub collateral_account_id: Option<u64>,
+    pub loan_coin: Option<String>,
+    pub collateral_coin: Option<String>,
+    pub current: Option<u32>,
+    pub limit: Option<u16>,
+}
+
+setters!(VipLoanOrdersRequest {
+    order_id: u64,
+    collateral_account_id: u64,
+    current: u32,
+    limit: u16,
+} strings {
+    loan_coin,
+    collateral_coin,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VipLoanRepayRequest {
+    pub order_id: u64,
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct VipCollateralAccountRequest {
+    pub order_id: Option<u64>,
+    pub collateral_account_id: Option<u64>,
+}
+
+setters!(VipCollateralAccountRequest {
+    order_id: u64,
+    collateral_account_id: u64,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RebateHistoryRequest {
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub page: Option<u32>,
+}
+
+setters!(RebateHistoryRequest {
+    start_time: u64,
+    end_time: u64,
+    page: u32,
+});
+
+// Referral records take `limit`, broker records `page` and `size`
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RebateRecordsRequest {
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub limit: Option<u16>,
+    pub page: Option<u32>,
+    pub size: Option<u16>,
+}
+
+setters!(RebateRecordsRequest {
+    start_time: u64,
+    end_time: u64,
+    limit: u16,
+    page: u32,
+    size: u16,
+});
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DualInvestmentProductsRequest {
+    pub option_type: DualInvestmentOptionType,
+    #[serde(serialize_with = "upper_case")]
+    pub exercised_coin: String,
+    #[serde(serialize_with = "upper_case")]
+    pub invest_coin: String,
+    pub page_size: Option<u16>,
+    pub page_index: Option<u32>,
+}
+
+setters!(DualInvestmentProductsRequest {
+    page_size: u16,
+    page_index: u32,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DualInvestmentSubscribeRequest {
+    pub id: String,
+    pub order_id: u64,
+    pub deposit_amount: f64,
+    pub auto_compound_plan: AutoCompoundPlan,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DualInvestmentPositionsRequest {
+    #[serde(serialize_with = "upper_case_opt")]
+    pub status: Option<String>,
+    pub page_size: Option<u16>,
+    pub page_index: Option<u32>,
+}
+
+setters!(DualInvestmentPositionsRequest {
+    page_size: u16,
+    page_index: u32,
+} strings {
+    status,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+pub struct AutoCompoundRequest {
+    #[serde(rename = "positionId")]
+    pub position_id: String,
+    #[serde(rename = "AutoCompoundPlan")]
+    pub auto_compound_plan: AutoCompoundPlan,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BlvtTokenRequest {
+    #[serde(serialize_with = "upper_case_opt")]
+    pub token_name: Option<String>,
+}
+
+setters!(BlvtTokenRequest {} strings {
+    token_name,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BlvtSubscribeRequest {
+    #[serde(serialize_with = "upper_case")]
+    pub token_name: String,
+    pub cost: f64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BlvtRedeemRequest {
+    #[serde(serialize_with = "upper_case")]
+    pub token_name: String,
+    pub amount: f64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgoIdRequest {
+    pub algo_id: u64,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgoOrdersRequest {
+    pub symbol: Option<String>,
+    pub side: Option<Side>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub page: Option<u32>,
+    pub page_size: Option<u16>,
+}
+
+setters!(AlgoOrdersRequest {
+    side: Side,
+    start_time: u64,
+    end_time: u64,
+    page: u32,
+    page_size: u16,
+} strings {
+    symbol,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AlgoSubOrdersRequest {
+    pub algo_id: u64,
+    pub page: Option<u32>,
+    pub page_size: Option<u16>,
+}
+
+setters!(AlgoSubOrdersRequest {
+    page: u32,
+    page_size: u16,
+});
+
+// Deposit and withdraw history
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct CapitalHistoryRequest {
+    #[serde(serialize_with = "upper_case_opt")]
+    pub coin: Option<String>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub offset: Option<u32>,
+    pub limit: Option<u16>,
+}
+
+setters!(CapitalHistoryRequest {
+    start_time: u64,
+    end_time: u64,
+    offset: u32,
+    limit: u16,
+} strings {
+    coin,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DepositAddressRequest {
+    #[serde(serialize_with = "upper_case")]
+    pub coin: String,
+    #[serde(serialize_with = "upper_case_opt")]
+    pub network: Option<String>,
+}
+
+setters!(DepositAddressRequest {} strings {
+    network,
+});
+
+// A withdrawal, `/capital/withdraw/apply`, or with a questionnaire
+// `/localentity/withdraw/apply` for accounts of regions enforcing the travel rule
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct WithdrawRequest {
+    #[serde(serialize_with = "upper_case")]
+    pub coin: String,
+    pub address: String,
+    pub amount: f64,
+    // The coin's default network if `None`
+    #[serde(serialize_with = "upper_case_opt")]
+    pub network: Option<String>,
+    // Tag or memo, required by some networks, see `WithdrawQuote::requires_memo`
+    pub address_tag: Option<String>,
+    // Client id of the withdrawal
+    pub withdraw_order_id: Option<String>,
+    // Description of the address in the address book
+    pub name: Option<String>,
+    // Internal transfers only: whether the fee is paid by the recipient
+    pub transaction_fee_flag: Option<bool>,
+    // 0 for the spot wallet, 1 for the funding wallet, see `WalletType`
+    pub wallet_type: Option<u8>,
+    // JSON encoded answers to the travel rule questionnaire of the account's region
+    pub questionnaire: Option<String>,
+}
+
+impl WithdrawRequest {
+    #[must_use]
+    pub fn new(coin: &str, address: &str, amount: f64) -> Self {
+        Self {
+            coin: coin.into(),
+            address: address.into(),
+            amount,
+            network: None,
+            address_tag: None,
+            withdraw_order_id: None,
+            name: None,
+            transaction_fee_flag: None,
+            wallet_type: None,
+            questionnaire: None,
+        }
+    }
+
+    #[must_use]
+    pub const fn wallet_type(mut self, wallet_type: WalletType) -> Self {
+        self.wallet_type = Some(wallet_type as u8);
+        self
+    }
+
+    // Answers as documented for the region, e.g. `{"isAddressOwner": 1, "sendTo": 1}`
+    #[must_use]
+    pub fn questionnaire(mut self, questionnaire: &serde_json::Value) -> Self {
+        self.questionnaire = Some(questionnaire.to_string());
+        self
+    }
+}
+
+setters!(WithdrawRequest {
+    transaction_fee_flag: bool,
+} strings {
+    network,
+    address_tag,
+    withdraw_order_id,
+    name,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UserAssetRequest {
+    #[serde(serialize_with = "upper_case_opt")]
+    pub asset: Option<String>,
+    pub need_btc_valuation: Option<bool>,
+}
+
+setters!(UserAssetRequest {
+    need_btc_valuation: bool,
+} strings {
+    asset,
+});
+
+// ONE asset, or ALL without `asset`
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetRequest {
+    #[serde(
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "upper_case_opt"
+    )]
+    pub asset: Option<String>,
+}
+
+setters!(AssetRequest {} strings {
+    asset,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FundingRateRequest {
+    pub symbol: String,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub limit: Option<u16>,
+}
+
+setters!(FundingRateRequest {
+    start_time: u64,
+    end_time: u64,
+    limit: u16,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionalSymbolRequest {
+    pub symbol: Option<String>,
+}
+
+setters!(OptionalSymbolRequest {} strings {
+    symbol,
+});
+
+// Parameters of the `/futures/data` statistics
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesDataRequest {
+    pub symbol: String,
+    pub period: String,
+    pub limit: Option<u16>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+}
+
+setters!(FuturesDataRequest {
+    limit: u16,
+    start_time: u64,
+    end_time: u64,
+});
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionModeRequest {
+    pub dual_side_position: bool,
+}
+
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageRequest {
+    pub symbol: String,
+    pub leverage: u8,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginTypeRequest {
+    pub symbol: String,
+    pub margin_type: MarginType,
+}
+
+// `session.logon` of the websocket API, `signature` signs `apiKey=...&timestamp=...`
+#[derive(Debug, Serialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionLogonRequest {
+    pub api_key: String,
+    pub timestamp: u64,
+    pub signature: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        AssetRequest, BnbBurnRequest, CapitalHistoryRequest, ExchangeInfoRequest, KlinesRequest,
+        NewOrderRequest, WithdrawRequest,
+    };
+    use crate::model::{OrderResponseType, OrderType, Side, Symbol, TimeInForce, WalletType};
+    use anyhow::Result;
+    use serde_json::{from_str, json, to_value};
+
+    #[test]
+    fn request_field_names() {
+        let klines = KlinesRequest {
+            symbol: "BTCUSDT".into(),
+            interval: "1m".into(),
+            start_time: Some(1_600_000_000_000),
+            ..KlinesRequest::default()
+        };
+        assert_eq!(
+            to_value(klines).unwrap(),
+            json!({"symbol": "BTCUSDT", "interval": "1m", "startTime": 1_600_000_000_000_u64, "endTime": null, "limit": null})
+        );
+
+        let burn = BnbBurnRequest {
+            spot_bnb_burn: Some(true),
+            interest_bnb_burn: None,
+        };
+        assert_eq!(
+            to_value(burn).unwrap(),
+            json!({"spotBNBBurn": true, "interestBNBBurn": null})
+        );
+
+        assert_eq!(to_value(AssetRequest::default()).unwrap(), json!({}));
+        let balance = AssetRequest::default().asset("bnb");
+        assert_eq!(to_value(balance).unwrap(), json!({"asset": "BNB"}));
+
+        let history = CapitalHistoryRequest::default().coin("usdt").limit(1000);
+        assert_eq!(
+            to_value(history).unwrap(),
+            json!({"coin": "USDT", "startTime": null, "endTime": null, "offset": null, "limit": 1000})
+        );
+    }
+
+    #[test]
+    fn withdraw_request() {
+        let request = WithdrawRequest::new("usdt", "TQrZ9wBzhLEPdUJ8NZ7f3N1Cd9p6gR4fNb", 25.0)
+            .network("trx")
+            .wallet_type(WalletType::Funding)
+            .questionnaire(&json!({"isAddressOwner": 1, "sendTo": 1}));
+        let value = to_value(request).unwrap();
+        assert_eq!(value["coin"], "USDT");
+        assert_eq!(value["network"], "TRX");
+        assert_eq!(value["walletType"], 1);
+        assert_eq!(value["questionnaire"], r#"{"isAddressOwner":1,"sendTo":1}"#);
+        assert!(value["addressTag"].is_null());
+    }
+
+    #[test]
+    fn new_order_request() {
+        let market =
+            NewOrderRequest::market("btcusdt", Side::Buy, 1.0).client_order_id("my-order-1");
+        assert_eq!(
+            to_value(market).unwrap(),
+            json!({"symbol": "BTCUSDT", "side": "BUY", "type": "MARKET", "quantity": "1", "quoteOrderQty": null, "price": null, "timeInForce": null, "stopPrice": null, "trailingDelta": null, "newClientOrderId": "my-order-1", "newOrderRespType": null})
+        );
+
+        let limit = NewOrderRequest::limit("BTCUSDT", Side::Sell, 0.5, 70_000.5)
+            .response_type(OrderResponseType::Full);
+        let value = to_value(limit).unwrap();
+        assert_eq!(value["price"], json!("70000.5"));
+        assert_eq!(value["timeInForce"], json!("GTC"));
+        assert_eq!(value["newOrderRespType"], json!("FULL"));
+
+        let quote = to_value(NewOrderRequest::market_quote("BTCUSDT", Side::Buy, 100.0)).unwrap();
+        assert_eq!(quote["quoteOrderQty"], json!("100"));
+        assert_eq!(quote["quantity"], json!(null));
+
+        let stop = to_value(NewOrderRequest::triggered_limit(
+            "BTCUSDT",
+            Side::Sell,
+            OrderType::StopLossLimit,
+            0.5,
+            59_900.0,
+            60_000.0,
+        ))
+        .unwrap();
+        assert_eq!(stop["type"], json!("STOP_LOSS_LIMIT"));
+        assert_eq!(stop["stopPrice"], json!("60000"));
+        assert_eq!(stop["timeInForce"], json!("GTC"));
+
+        let trailing =
+            to_value(NewOrderRequest::market("BTCUSDT", Side::Sell, 0.5).trailing_delta(200))
+                .unwrap();
+        assert_eq!(trailing["trailingDelta"], json!(200));
+
+        let maker = to_value(NewOrderRequest::limit_maker(
+            "BTCUSDT",
+            Side::Buy,
+            0.5,
+            59_000.0,
+        ))
+        .unwrap();
+        assert_eq!(maker["type"], json!("LIMIT_MAKER"));
+        assert_eq!(maker["timeInForce"], json!(null));
+
+        let small = NewOrderRequest::market("SHIBUSDT", Side::Buy, 1.0)
+            .quantity(0.000_008_12)
+            .stop_price(1e16)
+            .time_in_force(TimeInForce::IOC);
+        let small = to_value(small).unwrap();
+        assert_eq!(small["quantity"], json!("0.00000812"));
+        assert_eq!(small["stopPrice"], json!("10000000000000000"));
+        assert_eq!(small["timeInForce"], json!("IOC"));
+    }
+
+    #[test]
+    fn rounded_order() -> Result<()> {
+        let symbol: Symbol = from_str(
+            r#"{"symbol":"ETHBTC","status":"TRADING","baseAsset":"ETH","baseAssetPrecision":8,"quoteAsset":"BTC","quotePrecision":8,"quoteAssetPrecision":8,"baseCommissionPrecision":8,"quoteCommissionPrecision":8,"orderTypes":["LIMIT","MARKET"],"icebergAllowed":true,"ocoAllowed":true,"quoteOrderQtyMarketAllowed":true,"isSpotTradingAllowed":true,"isMarginTradingAllowed":true,"filters":[{"filterType":"PRICE_FILTER","minPrice":"0.00001000","maxPrice":"922327.00000000","tickSize":"0.00001000"},{"filterType":"LOT_SIZE","minQty":"0.00010000","maxQty":"100000.00000000","stepSize":"0.00010000"}]}"#,
+        )?;
+        let limit = NewOrderRequest::limit("ETHBTC", Side::Buy, 1.234_56, 0.000_012_345)
+            .rounded_to(&symbol);
+        assert_eq!(limit.quantity.as_deref(), Some("1.2345"));
+        assert_eq!(limit.price.as_deref(), Some("0.00001"));
+
+        let quote =
+            NewOrderRequest::market_quote("ETHBTC", Side::Buy, 0.123_456_789).rounded_to(&symbol);
+        assert_eq!(quote.quote_order_qty.as_deref(), Some("0.12345678"));
+        Ok(())
+    }
+
+    #[test]
+    fn exchange_info_request() {
+        let one = ExchangeInfoRequest::for_symbols(&["btcusdt"]);
+        assert_eq!(one.symbol.as_deref(), Some("BTCUSDT"));
+        assert!(one.symbols.is_none());
+
+        let many = ExchangeInfoRequest::for_symbols(&["btcusdt", "BNBBTC"]);
+        assert_eq!(many.symbols.as_deref(), Some(r#"["BTCUSDT","BNBBTC"]"#));
+
+        let permissions = ExchangeInfoRequest::for_permissions(&["spot", "MARGIN"]);
+        assert_eq!(
+            permissions.permissions.as_deref(),
+            Some(r#"["SPOT","MARGIN"]"#)
+        );
+    }
+}
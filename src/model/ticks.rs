@@ -0,0 +1,169 @@
+//! Prices and quantities as whole multiples of a symbol's tick and step size.
+//!
+//! Comparing and adding `PriceTicks` and `QtyLots` is integer arithmetic, and converting them
+//! back to a `Decimal` gives exactly the value the exchange sent.
+
+use super::Symbol;
+use crate::error::Error;
+use rust_decimal::{
+    prelude::{FromPrimitive, ToPrimitive},
+    Decimal,
+};
+use serde::{Deserialize, Serialize};
+use std::{ops::Add, str::FromStr};
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct PriceTicks(pub u64);
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+)]
+pub struct QtyLots(pub u64);
+
+impl Add for PriceTicks {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl Add for QtyLots {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self(self.0 + rhs.0)
+    }
+}
+
+impl QtyLots {
+    #[must_use]
+    pub const fn saturating_sub(self, rhs: Self) -> Self {
+        Self(self.0.saturating_sub(rhs.0))
+    }
+}
+
+// Tick and step size of one symbol, converting between decimals and whole increments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SymbolScale {
+    pub tick_size: Decimal,
+    pub step_size: Decimal,
+}
+
+impl SymbolScale {
+    #[must_use]
+    pub const fn new(tick_size: Decimal, step_size: Decimal) -> Self {
+        Self {
+            tick_size,
+            step_size,
+        }
+    }
+
+    // From the PRICE_FILTER and LOT_SIZE filters, `Error::InvalidSymbol` without either
+    pub fn from_symbol(symbol: &Symbol) -> Result<Self, Error> {
+        let increment = |value: Option<&str>| {
+            value
+                .and_then(|value| Decimal::from_str(value).ok())
+                .filter(|value| value.is_sign_positive() && !value.is_zero())
+                .ok_or_else(|| Error::InvalidSymbol(symbol.symbol.clone()))
+        };
+        Ok(Self::new(
+            increment(symbol.tick_size())?,
+            increment(symbol.step_size())?,
+        ))
+    }
+
+    // `None` unless `price` is a non-negative multiple of the tick size
+    #[must_use]
+    pub fn price_ticks(&self, price: Decimal) -> Option<PriceTicks> {
+        exact_multiple(price, self.tick_size).map(PriceTicks)
+    }
+
+    // Nearest tick to a float price, e.g. from a parsed stream event
+    #[must_use]
+    pub fn price_ticks_rounded(&self, price: f64) -> Option<PriceTicks> {
+        rounded_multiple(price, self.tick_size).map(PriceTicks)
+    }
+
+    // `price` as sent by the exchange, e.g. "67321.01000000"
+    #[must_use]
+    pub fn parse_price(&self, price: &str) -> Option<PriceTicks> {
+        self.price_ticks(Decimal::from_str(price).ok()?)
+    }
+
+    #[must_use]
+    pub fn price(&self, ticks: PriceTicks) -> Decimal {
+        Decimal::from(ticks.0) * self.tick_size
+    }
+
+    // `None` unless `qty` is a non-negative multiple of the step size
+    #[must_use]
+    pub fn qty_lots(&self, qty: Decimal) -> Option<QtyLots> {
+        exact_multiple(qty, self.step_size).map(QtyLots)
+    }
+
+    #[must_use]
+    pub fn qty_lots_rounded(&self, qty: f64) -> Option<QtyLots> {
+        rounded_multiple(qty, self.step_size).map(QtyLots)
+    }
+
+    #[must_use]
+    pub fn parse_qty(&self, qty: &str) -> Option<QtyLots> {
+        self.qty_lots(Decimal::from_str(qty).ok()?)
+    }
+
+    #[must_use]
+    pub fn qty(&self, lots: QtyLots) -> Decimal {
+        Decimal::from(lots.0) * self.step_size
+    }
+}
+
+fn exact_multiple(value: Decimal, increment: Decimal) -> Option<u64> {
+    let multiple = value.checked_div(increment)?;
+    if multiple.fract().is_zero() {
+        multiple.to_u64()
+    } else {
+        None
+    }
+}
+
+fn rounded_multiple(value: f64, increment: Decimal) -> Option<u64> {
+    Decimal::from_f64(value)?
+        .checked_div(increment)?
+        .round()
+        .to_u64()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{PriceTicks, QtyLots, SymbolScale};
+    use crate::model::Symbol;
+    use anyhow::Result;
+    use rust_decimal::Decimal;
+    use serde_json::from_str;
+    use std::str::FromStr;
+
+    #[test]
+    fn symbol_scale() -> Result<()> {
+        let symbol: Symbol = from_str(
+            r#"{"symbol":"BTCUSDT","status":"TRADING","baseAsset":"BTC","baseAssetPrecision":8,"quoteAsset":"USDT","quotePrecision":8,"quoteAssetPrecision":8,"baseCommissionPrecision":8,"quoteCommissionPrecision":8,"orderTypes":["LIMIT","MARKET"],"icebergAllowed":true,"ocoAllowed":true,"quoteOrderQtyMarketAllowed":true,"isSpotTradingAllowed":true,"isMarginTradingAllowed":true,"filters":[{"filterType":"PRICE_FILTER","minPrice":"0.01000000","maxPrice":"1000000.00000000","tickSize":"0.01000000"},{"filterType":"LOT_SIZE","minQty":"0.00001000","maxQty":"9000.00000000","stepSize":"0.00001000"}]}"#,
+        )?;
+        let scale = SymbolScale::from_symbol(&symbol)?;
+
+        let price = scale.parse_price("67321.01000000").unwrap();
+        assert_eq!(price, PriceTicks(6_732_101));
+        assert_eq!(scale.price(price), Decimal::from_str("67321.01")?);
+        assert!(scale.parse_price("67321.015").is_none());
+        assert_eq!(scale.price_ticks_rounded(0.3), Some(PriceTicks(30)));
+        assert!(price > PriceTicks(6_732_100));
+
+        let qty = scale.parse_qty("0.00200000").unwrap();
+        assert_eq!(qty, QtyLots(200));
+        assert_eq!(scale.qty(qty + QtyLots(1)), Decimal::from_str("0.00201")?);
+        assert_eq!(qty.saturating_sub(QtyLots(300)), QtyLots(0));
+        assert!(scale.qty_lots(Decimal::from_str("-1")?).is_none());
+        Ok(())
+    }
+}
@@ -0,0 +1,68 @@
+use super::{string_or_float, timestamp, Page, Timestamp};
+use serde::{Deserialize, Serialize};
+
+// Paged VIP Loan response
+pub type VipLoanRows<T> = Page<T>;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VipLoanOrder {
+    pub order_id: u64,
+    pub loan_coin: String,
+    #[serde(with = "string_or_float")]
+    pub total_debt: f64,
+    #[serde(with = "string_or_float")]
+    pub residual_interest: f64,
+    pub collateral_account_id: String,
+    pub collateral_coin: String,
+    #[serde(with = "string_or_float")]
+    pub total_collateral_value_after_haircut: f64,
+    #[serde(with = "string_or_float")]
+    pub locked_collateral_value: f64,
+    #[serde(rename = "currentLTV", with = "string_or_float")]
+    pub current_ltv: f64,
+    #[serde(with = "timestamp")]
+    pub expiration_time: Timestamp,
+    #[serde(with = "timestamp")]
+    pub loan_date: Timestamp,
+    pub loan_term: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VipLoanRepayment {
+    pub loan_coin: String,
+    #[serde(with = "string_or_float")]
+    pub repay_amount: f64,
+    #[serde(with = "string_or_float")]
+    pub remaining_principal: f64,
+    #[serde(with = "string_or_float")]
+    pub remaining_interest: f64,
+    pub collateral_coin: String,
+    #[serde(rename = "currentLTV", with = "string_or_float")]
+    pub current_ltv: f64,
+    pub repay_status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct VipCollateralAccount {
+    pub collateral_account_id: String,
+    pub collateral_coin: String,
+}
+
+#[cfg(test)]
+mod test {
+    use super::{VipLoanOrder, VipLoanRows};
+    use anyhow::Result;
+    use serde_json::from_str;
+
+    #[test]
+    fn vip_loan_orders() -> Result<()> {
+        let json = r#"{"rows":[{"orderId":100000001,"loanCoin":"BUSD","totalDebt":"10000","residualInterest":"10.27687500","collateralAccountId":"12345678,23456789","collateralCoin":"BNB,BTC,ETH","totalCollateralValueAfterHaircut":"5000.27565492","lockedCollateralValue":"25000.27565492","currentLTV":"0.57","expirationTime":1575018510000,"loanDate":1676851200000,"loanTerm":"30"}],"total":1}"#;
+        let orders: VipLoanRows<VipLoanOrder> = from_str(json)?;
+        assert_eq!(orders.total, 1);
+        assert!((orders.rows[0].current_ltv - 0.57).abs() < f64::EPSILON);
+        Ok(())
+    }
+}
@@ -1,10 +1,46 @@
 use super::{
-    string_or_float, Asks, Bids, Kline, OrderBook, OrderExecType, OrderRejectReason, OrderStatus,
-    OrderType, Side, TimeInForce,
+    string_or_float, timestamp, Asks, Bids, Kline, OrderBook, OrderExecType, OrderRejectReason,
+    OrderStatus, OrderType, Side, TimeInForce, Timestamp,
 };
-use serde::{Deserialize, Serialize};
+use crate::error::Error;
+use chrono::{DateTime, TimeZone, Utc};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::{from_str, from_value, value::RawValue, Value};
+use std::collections::{HashMap, HashSet};
+use std::convert::TryFrom;
+use std::fmt;
+use std::iter::FromIterator;
+use std::str::FromStr;
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+// Unit of the timestamps carried by stream events
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimeUnit {
+    #[default]
+    Millisecond,
+    Microsecond,
+}
+
+impl TimeUnit {
+    #[must_use]
+    pub const fn query_param(self) -> Option<&'static str> {
+        match self {
+            Self::Millisecond => None,
+            Self::Microsecond => Some("timeUnit=MICROSECOND"),
+        }
+    }
+
+    // Converts an event timestamp expressed in this unit
+    #[must_use]
+    pub fn to_datetime(self, timestamp: u64) -> Option<DateTime<Utc>> {
+        let timestamp = i64::try_from(timestamp).ok()?;
+        match self {
+            Self::Millisecond => Utc.timestamp_millis_opt(timestamp).single(),
+            Self::Microsecond => Utc.timestamp_micros(timestamp).single(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize)]
 pub enum Subscription {
     UserData(String),            // listen key
     AggregateTrade(String),      //symbol
@@ -16,6 +52,358 @@ pub enum Subscription {
     TickerAll,
     OrderBook(String, i64), //symbol, depth
     Depth(String),          //symbol
+    BookTicker(String),     //symbol
+    BookTickerAll,
+    AveragePrice(String),                // symbol
+    RollingWindowTicker(String, String), // symbol, window size
+    RollingWindowTickerAll(String),      // window size
+    TokenNav(String),                    // leveraged token name, e.g. BTCDOWN
+    ForceOrder(String),                  // futures symbol
+    ForceOrderAll,
+    MarkPrice(String), // futures symbol
+    MarkPriceAll,
+    ContinuousKline(String, String, String), // pair, contract type, interval
+    CandlestickUtc8(String, String),         // symbol, interval, klines opening in UTC+8
+    DepthWithSpeed(String, u16),             // symbol, update speed in ms
+    OrderBookWithSpeed(String, i64, u16),    // symbol, depth, update speed in ms
+    AssetIndex(String),                      // multi-assets mode asset pair, e.g. ADAUSD
+    AssetIndexAll,
+}
+
+// Leveraged token NAV streams are served from their own host
+const BLVT_WS_URL: &str = "wss://nbstream.binance.com/lvt-p/ws";
+// USDⓈ-M futures market streams
+const FUTURES_WS_URL: &str = "wss://fstream.binance.com/ws";
+
+impl Subscription {
+    // Base url of the streams not served by the spot stream host
+    pub(crate) const fn dedicated_base(&self) -> Option<&'static str> {
+        match self {
+            Self::TokenNav(..) => Some(BLVT_WS_URL),
+            Self::ForceOrder(..)
+            | Self::ForceOrderAll
+            | Self::MarkPrice(..)
+            | Self::MarkPriceAll
+            | Self::ContinuousKline(..)
+            | Self::AssetIndex(..)
+            | Self::AssetIndexAll => Some(FUTURES_WS_URL),
+            _ => None,
+        }
+    }
+
+    // Parses a stream name such as "btcusdt@depth5@100ms". Mark prices are always subscribed at
+    // 1s, their speed suffix is dropped. Names without '@' are taken as listen keys.
+    pub fn from_stream_name(name: &str) -> Result<Self, Error> {
+        let unknown = || Error::UnknownStream(name.to_string());
+
+        if let Some(all) = name.strip_prefix('!') {
+            return match all {
+                "miniTicker@arr" => Ok(Self::MiniTickerAll),
+                "ticker@arr" => Ok(Self::TickerAll),
+                "bookTicker" => Ok(Self::BookTickerAll),
+                "forceOrder@arr" => Ok(Self::ForceOrderAll),
+                "markPrice@arr" | "markPrice@arr@1s" => Ok(Self::MarkPriceAll),
+                "assetIndex@arr" => Ok(Self::AssetIndexAll),
+                _ => all
+                    .strip_prefix("ticker_")
+                    .and_then(|window| window.strip_suffix("@arr"))
+                    .map(|window| Self::RollingWindowTickerAll(window.to_string()))
+                    .ok_or_else(unknown),
+            };
+        }
+
+        let mut parts = name.split('@');
+        let symbol = parts.next().unwrap_or_default().to_string();
+        let Some(stream) = parts.next() else {
+            return Ok(Self::UserData(symbol));
+        };
+        let suffix = parts.next();
+        if symbol.is_empty() || parts.next().is_some() {
+            return Err(unknown());
+        }
+        let millis = |speed: &str| {
+            speed
+                .strip_suffix("ms")
+                .and_then(|ms| ms.parse().ok())
+                .ok_or_else(unknown)
+        };
+
+        let subscription = match stream {
+            "aggTrade" => Self::AggregateTrade(symbol),
+            "trade" => Self::Trade(symbol),
+            "miniTicker" => Self::MiniTicker(symbol),
+            "ticker" => Self::Ticker(symbol),
+            "depth" => Self::Depth(symbol),
+            "bookTicker" => Self::BookTicker(symbol),
+            "avgPrice" => Self::AveragePrice(symbol),
+            "tokenNav" => Self::TokenNav(symbol),
+            "forceOrder" => Self::ForceOrder(symbol),
+            "markPrice" => Self::MarkPrice(symbol),
+            "assetIndex" => Self::AssetIndex(symbol),
+            _ => {
+                if let Some(interval) = stream.strip_prefix("kline_") {
+                    Self::Candlestick(symbol, interval.to_string())
+                } else if let Some(interval) = stream.strip_prefix("continuousKline_") {
+                    let (pair, contract_type) = symbol.split_once('_').ok_or_else(unknown)?;
+                    Self::ContinuousKline(pair.into(), contract_type.into(), interval.into())
+                } else if let Some(window) = stream.strip_prefix("ticker_") {
+                    Self::RollingWindowTicker(symbol, window.to_string())
+                } else if let Some(depth) = stream.strip_prefix("depth") {
+                    Self::OrderBook(symbol, depth.parse().map_err(|_| unknown())?)
+                } else {
+                    return Err(unknown());
+                }
+            }
+        };
+        let subscription = match (subscription, suffix) {
+            (subscription, None) => subscription,
+            (Self::MarkPrice(symbol), Some("1s")) => Self::MarkPrice(symbol),
+            (Self::Candlestick(symbol, interval), Some("+08:00")) => {
+                Self::CandlestickUtc8(symbol, interval)
+            }
+            (Self::Depth(symbol), Some(speed)) => Self::DepthWithSpeed(symbol, millis(speed)?),
+            (Self::OrderBook(symbol, depth), Some(speed)) => {
+                Self::OrderBookWithSpeed(symbol, depth, millis(speed)?)
+            }
+            _ => return Err(unknown()),
+        };
+        Ok(subscription)
+    }
+}
+
+impl FromStr for Subscription {
+    type Err = Error;
+
+    fn from_str(name: &str) -> Result<Self, Error> {
+        Self::from_stream_name(name)
+    }
+}
+
+// The stream name, as used in the endpoint path and in combined stream payloads
+impl fmt::Display for Subscription {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AggregateTrade(symbol) => write!(f, "{symbol}@aggTrade"),
+            Self::Candlestick(symbol, interval) => write!(f, "{symbol}@kline_{interval}"),
+            Self::Depth(symbol) => write!(f, "{symbol}@depth"),
+            Self::MiniTicker(symbol) => write!(f, "{symbol}@miniTicker"),
+            Self::MiniTickerAll => write!(f, "!miniTicker@arr"),
+            Self::OrderBook(symbol, depth) => write!(f, "{symbol}@depth{depth}"),
+            Self::Ticker(symbol) => write!(f, "{symbol}@ticker"),
+            Self::TickerAll => write!(f, "!ticker@arr"),
+            Self::Trade(symbol) => write!(f, "{symbol}@trade"),
+            Self::BookTicker(symbol) => write!(f, "{symbol}@bookTicker"),
+            Self::BookTickerAll => write!(f, "!bookTicker"),
+            Self::AveragePrice(symbol) => write!(f, "{symbol}@avgPrice"),
+            Self::RollingWindowTicker(symbol, window) => write!(f, "{symbol}@ticker_{window}"),
+            Self::RollingWindowTickerAll(window) => write!(f, "!ticker_{window}@arr"),
+            Self::TokenNav(token) => write!(f, "{}@tokenNav", token.to_uppercase()),
+            Self::ForceOrder(symbol) => write!(f, "{symbol}@forceOrder"),
+            Self::ForceOrderAll => write!(f, "!forceOrder@arr"),
+            Self::MarkPrice(symbol) => write!(f, "{symbol}@markPrice@1s"),
+            Self::MarkPriceAll => write!(f, "!markPrice@arr"),
+            Self::ContinuousKline(pair, contract_type, interval) => {
+                write!(f, "{pair}_{contract_type}@continuousKline_{interval}")
+            }
+            Self::CandlestickUtc8(symbol, interval) => {
+                write!(f, "{symbol}@kline_{interval}@+08:00")
+            }
+            Self::DepthWithSpeed(symbol, speed) => write!(f, "{symbol}@depth@{speed}ms"),
+            Self::OrderBookWithSpeed(symbol, depth, speed) => {
+                write!(f, "{symbol}@depth{depth}@{speed}ms")
+            }
+            Self::AssetIndex(pair) => write!(f, "{pair}@assetIndex"),
+            Self::AssetIndexAll => write!(f, "!assetIndex@arr"),
+            Self::UserData(key) => write!(f, "{key}"),
+        }
+    }
+}
+
+// Symbols to keep of the all-market ticker arrays, e.g. `!ticker@arr`, matched
+// case-insensitively
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SymbolAllowlist(HashSet<String>);
+
+impl SymbolAllowlist {
+    #[must_use]
+    pub fn contains(&self, symbol: &str) -> bool {
+        self.0.contains(&symbol.to_uppercase())
+    }
+
+    // The entries of an array payload whose `s` is allowed, see `LazyEntries`
+    pub fn select<T: DeserializeOwned>(&self, payload: &str) -> serde_json::Result<Vec<T>> {
+        LazyEntries::parse(payload)?.select(|symbol| self.0.contains(symbol))
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<S> for SymbolAllowlist {
+    fn from_iter<I: IntoIterator<Item = S>>(symbols: I) -> Self {
+        Self(
+            symbols
+                .into_iter()
+                .map(|symbol| symbol.as_ref().to_uppercase())
+                .collect(),
+        )
+    }
+}
+
+// Entries of an all-market array payload, split but not deserialized. Reading the symbol of
+// an entry skips over its other fields, so only the entries picked are parsed in full.
+#[derive(Debug)]
+pub struct LazyEntries<'a>(Vec<&'a RawValue>);
+
+impl<'a> LazyEntries<'a> {
+    pub fn parse(payload: &'a str) -> serde_json::Result<Self> {
+        Ok(Self(serde_json::from_str(payload)?))
+    }
+
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    // The `s` of every entry, in order
+    pub fn symbols(&self) -> serde_json::Result<Vec<&'a str>> {
+        self.0.iter().map(|entry| symbol_of(entry)).collect()
+    }
+
+    // The entries whose symbol `pick` accepts, deserialized
+    pub fn select<T, F>(&self, mut pick: F) -> serde_json::Result<Vec<T>>
+    where
+        T: DeserializeOwned,
+        F: FnMut(&str) -> bool,
+    {
+        let mut selected = Vec::new();
+        for entry in &self.0 {
+            if pick(symbol_of(entry)?) {
+                selected.push(serde_json::from_str(entry.get())?);
+            }
+        }
+        Ok(selected)
+    }
+}
+
+fn symbol_of(entry: &RawValue) -> serde_json::Result<&str> {
+    #[derive(Deserialize)]
+    struct Entry<'a> {
+        s: &'a str,
+    }
+
+    serde_json::from_str::<Entry>(entry.get()).map(|entry| entry.s)
+}
+
+// Parses the payload of ONE stream, as sent on its own connection or unwrapped from a combined
+// stream frame
+pub fn parse_stream_text(sub: &Subscription, msg: &str) -> anyhow::Result<BinanceWebsocketMessage> {
+    let message = match sub {
+        Subscription::AggregateTrade(..) => BinanceWebsocketMessage::AggregateTrade(from_str(msg)?),
+        Subscription::Candlestick(..) | Subscription::CandlestickUtc8(..) => {
+            BinanceWebsocketMessage::Candlestick(from_str(msg)?)
+        }
+        Subscription::Depth(..) | Subscription::DepthWithSpeed(..) => {
+            BinanceWebsocketMessage::Depth(from_str(msg)?)
+        }
+        Subscription::MiniTicker(..) => BinanceWebsocketMessage::MiniTicker(from_str(msg)?),
+        Subscription::MiniTickerAll => BinanceWebsocketMessage::MiniTickerAll(from_str(msg)?),
+        Subscription::OrderBook(..) | Subscription::OrderBookWithSpeed(..) => {
+            BinanceWebsocketMessage::OrderBook(from_str(msg)?)
+        }
+        Subscription::Ticker(..) => BinanceWebsocketMessage::Ticker(from_str(msg)?),
+        Subscription::TickerAll => BinanceWebsocketMessage::TickerAll(from_str(msg)?),
+        Subscription::Trade(..) => BinanceWebsocketMessage::Trade(from_str(msg)?),
+        Subscription::BookTicker(..) | Subscription::BookTickerAll => {
+            BinanceWebsocketMessage::BookTicker(from_str(msg)?)
+        }
+        Subscription::AveragePrice(..) => BinanceWebsocketMessage::AveragePrice(from_str(msg)?),
+        Subscription::RollingWindowTicker(..) => {
+            BinanceWebsocketMessage::RollingWindowTicker(from_str(msg)?)
+        }
+        Subscription::RollingWindowTickerAll(..) => {
+            BinanceWebsocketMessage::RollingWindowTickerAll(from_str(msg)?)
+        }
+        Subscription::TokenNav(..) => BinanceWebsocketMessage::TokenNav(from_str(msg)?),
+        Subscription::ForceOrder(..) | Subscription::ForceOrderAll => {
+            BinanceWebsocketMessage::ForceOrder(from_str(msg)?)
+        }
+        Subscription::MarkPrice(..) => BinanceWebsocketMessage::MarkPrice(from_str(msg)?),
+        Subscription::MarkPriceAll => BinanceWebsocketMessage::MarkPriceAll(from_str(msg)?),
+        Subscription::ContinuousKline(..) => {
+            BinanceWebsocketMessage::ContinuousKline(from_str(msg)?)
+        }
+        Subscription::AssetIndex(..) => BinanceWebsocketMessage::AssetIndex(from_str(msg)?),
+        Subscription::AssetIndexAll => BinanceWebsocketMessage::AssetIndexAll(from_str(msg)?),
+        // Account, balance and order events share the user data stream
+        Subscription::UserData(..) => parse_event(msg)?,
+    };
+    Ok(message)
+}
+
+// Parses a raw stream payload without knowing the subscription it came from. Payloads of
+// combined streams (`{"stream": ..., "data": ...}`) are unwrapped first.
+pub fn parse_event(payload: &str) -> anyhow::Result<BinanceWebsocketMessage> {
+    let mut value: Value = serde_json::from_str(payload)?;
+    if value.get("stream").is_some() && value.get("data").is_some() {
+        value = value["data"].take();
+    }
+
+    let event_type = match value {
+        Value::Array(ref events) => events.first().and_then(|e| e.get("e")),
+        _ => value.get("e"),
+    }
+    .and_then(Value::as_str)
+    .map(str::to_string);
+
+    if value.is_array() {
+        let message = match event_type.as_deref() {
+            Some("24hrMiniTicker") => BinanceWebsocketMessage::MiniTickerAll(from_value(value)?),
+            Some("24hrTicker") => BinanceWebsocketMessage::TickerAll(from_value(value)?),
+            Some("markPriceUpdate") => BinanceWebsocketMessage::MarkPriceAll(from_value(value)?),
+            Some("assetIndexUpdate") => BinanceWebsocketMessage::AssetIndexAll(from_value(value)?),
+            Some(e) if e.ends_with("Ticker") => {
+                BinanceWebsocketMessage::RollingWindowTickerAll(from_value(value)?)
+            }
+            _ => return Err(Error::UnknownEvent(payload.to_string()).into()),
+        };
+        return Ok(message);
+    }
+
+    let message = match event_type.as_deref() {
+        Some("aggTrade") => BinanceWebsocketMessage::AggregateTrade(from_value(value)?),
+        Some("trade") => BinanceWebsocketMessage::Trade(from_value(value)?),
+        Some("kline") => BinanceWebsocketMessage::Candlestick(from_value(value)?),
+        Some("continuous_kline") => BinanceWebsocketMessage::ContinuousKline(from_value(value)?),
+        Some("24hrMiniTicker") => BinanceWebsocketMessage::MiniTicker(from_value(value)?),
+        Some("24hrTicker") => BinanceWebsocketMessage::Ticker(from_value(value)?),
+        Some("depthUpdate") => BinanceWebsocketMessage::Depth(from_value(value)?),
+        Some("avgPrice") => BinanceWebsocketMessage::AveragePrice(from_value(value)?),
+        Some("nav") => BinanceWebsocketMessage::TokenNav(from_value(value)?),
+        Some("forceOrder") => BinanceWebsocketMessage::ForceOrder(from_value(value)?),
+        Some("markPriceUpdate") => BinanceWebsocketMessage::MarkPrice(from_value(value)?),
+        Some("assetIndexUpdate") => BinanceWebsocketMessage::AssetIndex(from_value(value)?),
+        Some("outboundAccountPosition" | "outboundAccountInfo") => {
+            BinanceWebsocketMessage::UserAccountUpdate(from_value(value)?)
+        }
+        Some("balanceUpdate") => BinanceWebsocketMessage::UserBalanceUpdate(from_value(value)?),
+        Some("executionReport") => BinanceWebsocketMessage::UserOrderUpdate(from_value(value)?),
+        Some("listenKeyExpired") => BinanceWebsocketMessage::ListenKeyExpired(from_value(value)?),
+        Some(e) if e.ends_with("Ticker") => {
+            BinanceWebsocketMessage::RollingWindowTicker(from_value(value)?)
+        }
+        // Partial book depth and book ticker payloads carry no event type
+        None if value.get("lastUpdateId").is_some() => {
+            BinanceWebsocketMessage::OrderBook(from_value(value)?)
+        }
+        None if value.get("u").is_some() && value.get("b").is_some() => {
+            BinanceWebsocketMessage::BookTicker(from_value(value)?)
+        }
+        _ => return Err(Error::UnknownEvent(payload.to_string()).into()),
+    };
+    Ok(message)
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -23,6 +411,10 @@ pub enum Subscription {
 pub enum BinanceWebsocketMessage {
     UserOrderUpdate(UserOrderUpdate),
     UserAccountUpdate(AccountUpdate),
+    UserBalanceUpdate(BalanceUpdate),
+    ListenKeyExpired(ListenKeyExpired),
+    // A new listen key replaced an expired one, events in between were missed
+    UserStreamRestarted(String),
     AggregateTrade(AggregateTrade),
     Trade(TradeMessage),
     Candlestick(CandelStickMessage),
@@ -32,9 +424,35 @@ pub enum BinanceWebsocketMessage {
     TickerAll(Vec<Ticker>),
     OrderBook(OrderBook),
     Depth(Depth),
+    BookTicker(BookTickerEvent),
+    AveragePrice(AveragePriceEvent),
+    RollingWindowTicker(RollingWindowTicker),
+    RollingWindowTickerAll(Vec<RollingWindowTicker>),
+    TokenNav(TokenNavEvent),
+    ForceOrder(ForceOrderEvent),
+    MarkPrice(MarkPriceEvent),
+    MarkPriceAll(Vec<MarkPriceEvent>),
+    ContinuousKline(ContinuousKlineEvent),
+    AssetIndex(AssetIndexEvent),
+    AssetIndexAll(Vec<AssetIndexEvent>),
+    StaleStream(Subscription), // no message within the stale timeout
     Ping,
     Pong,
     Binary(Vec<u8>), // Unexpected, unparsed
+    // A frame that failed to parse, under `ParseErrorPolicy::EmitUnknown`
+    Unknown(Value),
+}
+
+// The listen key of a user data stream expired, no events follow on it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenKeyExpired {
+    #[serde(rename = "e")]
+    pub event_type: String,
+    // Sent as a string
+    #[serde(rename = "E", with = "timestamp")]
+    pub event_time: Timestamp,
+    pub listen_key: String,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -150,6 +568,9 @@ pub struct UserOrderUpdate {
     pub order_creation_time: u64,
     #[serde(skip_serializing, rename = "Z", with = "string_or_float")]
     pub cumulative_quote_asset_transacted_qty: f64,
+    // Fields the model does not cover yet, e.g. `Y` (last quote qty) or `V` (STP mode)
+    #[serde(flatten)]
+    pub extra: HashMap<String, Value>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -171,6 +592,26 @@ pub struct Depth {
     pub asks: Vec<Asks>,
 }
 
+impl Depth {
+    // Whether this is the event to apply first on top of a REST snapshot with `last_update_id`
+    #[must_use]
+    pub const fn bridges_snapshot(&self, last_update_id: u64) -> bool {
+        self.first_update_id <= last_update_id + 1 && self.final_update_id > last_update_id
+    }
+
+    // Whether this event directly follows `previous` with no update missing in between
+    #[must_use]
+    pub const fn follows(&self, previous: &Self) -> bool {
+        self.first_update_id == previous.final_update_id + 1
+    }
+
+    // Whether the event is entirely covered by a snapshot with `last_update_id` and must be dropped
+    #[must_use]
+    pub const fn is_outdated(&self, last_update_id: u64) -> bool {
+        self.final_update_id <= last_update_id
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Ticker {
@@ -222,6 +663,201 @@ pub struct Ticker {
     pub num_trades: u64,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BookTickerEvent {
+    #[serde(rename = "u")]
+    pub update_id: u64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "b", with = "string_or_float")]
+    pub best_bid: f64,
+    #[serde(rename = "B", with = "string_or_float")]
+    pub best_bid_qty: f64,
+    #[serde(rename = "a", with = "string_or_float")]
+    pub best_ask: f64,
+    #[serde(rename = "A", with = "string_or_float")]
+    pub best_ask_qty: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AveragePriceEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "i")]
+    pub interval: String,
+    #[serde(rename = "w", with = "string_or_float")]
+    pub average_price: f64,
+    #[serde(rename = "T")]
+    pub last_trade_time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RollingWindowTicker {
+    #[serde(rename = "e")]
+    pub event_type: String,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "p", with = "string_or_float")]
+    pub price_change: f64,
+    #[serde(rename = "P", with = "string_or_float")]
+    pub price_change_percent: f64,
+    #[serde(rename = "o", with = "string_or_float")]
+    pub open: f64,
+    #[serde(rename = "h", with = "string_or_float")]
+    pub high: f64,
+    #[serde(rename = "l", with = "string_or_float")]
+    pub low: f64,
+    #[serde(rename = "c", with = "string_or_float")]
+    pub last_price: f64,
+    #[serde(rename = "w", with = "string_or_float")]
+    pub weighted_average_price: f64,
+    #[serde(rename = "v", with = "string_or_float")]
+    pub volume: f64,
+    #[serde(rename = "q", with = "string_or_float")]
+    pub quote_volume: f64,
+    #[serde(rename = "O")]
+    pub open_time: u64,
+    #[serde(rename = "C")]
+    pub close_time: u64,
+    #[serde(rename = "F")]
+    pub first_trade_id: i64,
+    #[serde(rename = "L")]
+    pub last_trade_id: i64,
+    #[serde(rename = "n")]
+    pub num_trades: u64,
+}
+
+// Net asset value of a leveraged token, pushed on every NAV update
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TokenNavEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "s")]
+    pub token_name: String,
+    #[serde(rename = "m")]
+    pub tokens_issued: f64,
+    #[serde(rename = "b")]
+    pub baskets: Vec<TokenNavBasket>,
+    #[serde(rename = "n")]
+    pub nav: f64,
+    #[serde(rename = "l")]
+    pub real_leverage: f64,
+    #[serde(rename = "t")]
+    pub target_leverage: f64,
+    #[serde(rename = "f")]
+    pub funding_ratio: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TokenNavBasket {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "n")]
+    pub position: f64,
+}
+
+// Liquidation of a futures position. `!forceOrder@arr` pushes the events one by one, at
+// most the latest liquidation of every symbol per second.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ForceOrderEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "o")]
+    pub order: LiquidationOrder,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct LiquidationOrder {
+    #[serde(rename = "s")]
+    pub symbol: String,
+    // SELL liquidates a long position, BUY a short one
+    #[serde(rename = "S")]
+    pub side: Side,
+    #[serde(rename = "o")]
+    pub order_type: String,
+    #[serde(rename = "f")]
+    pub time_in_force: String,
+    #[serde(rename = "q", with = "string_or_float")]
+    pub qty: f64,
+    #[serde(rename = "p", with = "string_or_float")]
+    pub price: f64,
+    #[serde(rename = "ap", with = "string_or_float")]
+    pub avg_price: f64,
+    #[serde(rename = "X")]
+    pub order_status: OrderStatus,
+    #[serde(rename = "l", with = "string_or_float")]
+    pub last_filled_qty: f64,
+    #[serde(rename = "z", with = "string_or_float")]
+    pub accumulated_filled_qty: f64,
+    #[serde(rename = "T")]
+    pub trade_time: u64,
+}
+
+// Mark price and funding of a futures symbol, `!markPrice@arr` pushes ALL symbols at once
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MarkPriceEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "p", with = "string_or_float")]
+    pub mark_price: f64,
+    #[serde(rename = "i", with = "string_or_float")]
+    pub index_price: f64,
+    // Only meaningful in the last hour before the settlement
+    #[serde(rename = "P", with = "string_or_float")]
+    pub estimated_settle_price: f64,
+    #[serde(rename = "r", with = "string_or_float")]
+    pub funding_rate: f64,
+    #[serde(rename = "T")]
+    pub next_funding_time: u64,
+}
+
+// Index price of an asset pair in multi-assets margin mode, with the buffers and rates of the
+// margin asset conversion
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct AssetIndexEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "i", with = "string_or_float")]
+    pub index_price: f64,
+    #[serde(rename = "b", with = "string_or_float")]
+    pub bid_buffer: f64,
+    #[serde(rename = "a", with = "string_or_float")]
+    pub ask_buffer: f64,
+    #[serde(rename = "B", with = "string_or_float")]
+    pub bid_rate: f64,
+    #[serde(rename = "A", with = "string_or_float")]
+    pub ask_rate: f64,
+    #[serde(rename = "q", with = "string_or_float")]
+    pub auto_exchange_bid_buffer: f64,
+    #[serde(rename = "g", with = "string_or_float")]
+    pub auto_exchange_ask_buffer: f64,
+    #[serde(rename = "Q", with = "string_or_float")]
+    pub auto_exchange_bid_rate: f64,
+    #[serde(rename = "G", with = "string_or_float")]
+    pub auto_exchange_ask_rate: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CandelStickMessage {
@@ -235,6 +871,56 @@ pub struct CandelStickMessage {
     pub kline: Kline,
 }
 
+// Candle of a futures pair across its successive contracts, e.g. ALL quarterly contracts
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContinuousKlineEvent {
+    #[serde(rename = "e")]
+    pub event_type: String,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "ps")]
+    pub pair: String,
+    // PERPETUAL, CURRENT_QUARTER or NEXT_QUARTER
+    #[serde(rename = "ct")]
+    pub contract_type: String,
+    #[serde(rename = "k")]
+    pub kline: ContinuousKline,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ContinuousKline {
+    #[serde(rename = "t")]
+    pub start_time: u64,
+    #[serde(rename = "T")]
+    pub end_time: u64,
+    #[serde(rename = "i")]
+    pub interval: String,
+    #[serde(rename = "f")]
+    pub first_update_id: u64,
+    #[serde(rename = "L")]
+    pub last_update_id: u64,
+    #[serde(rename = "o", with = "string_or_float")]
+    pub open: f64,
+    #[serde(rename = "c", with = "string_or_float")]
+    pub close: f64,
+    #[serde(rename = "h", with = "string_or_float")]
+    pub high: f64,
+    #[serde(rename = "l", with = "string_or_float")]
+    pub low: f64,
+    #[serde(rename = "v", with = "string_or_float")]
+    pub volume: f64,
+    #[serde(rename = "n")]
+    pub number_of_trades: u64,
+    #[serde(rename = "x")]
+    pub is_final_bar: bool,
+    #[serde(rename = "q", with = "string_or_float")]
+    pub quote_volume: f64,
+    #[serde(rename = "V", with = "string_or_float")]
+    pub active_buy_volume: f64,
+    #[serde(rename = "Q", with = "string_or_float")]
+    pub active_volume_buy_quote: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountUpdate {
@@ -243,18 +929,25 @@ pub struct AccountUpdate {
     #[serde(rename = "E")]
     pub event_time: u64,
     #[serde(rename = "m")]
+    #[serde(default)]
     pub maker_commision_rate: u64,
     #[serde(rename = "t")]
+    #[serde(default)]
     pub taker_commision_rate: u64,
     #[serde(rename = "b")]
+    #[serde(default)]
     pub buyer_commision_rate: u64,
     #[serde(rename = "s")]
+    #[serde(default)]
     pub seller_commision_rate: u64,
     #[serde(rename = "T")]
+    #[serde(default)]
     pub can_trade: bool,
     #[serde(rename = "W")]
+    #[serde(default)]
     pub can_withdraw: bool,
     #[serde(rename = "D")]
+    #[serde(default)]
     pub can_deposit: bool,
     #[serde(rename = "u")]
     pub last_account_update: u64,
@@ -273,6 +966,21 @@ pub struct AccountUpdateBalance {
     pub locked: f64,
 }
 
+// Deposit, withdrawal or transfer of an asset, `balance_delta` is added to the free balance
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BalanceUpdate {
+    #[serde(rename = "e")]
+    pub event_type: String,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "a")]
+    pub asset: String,
+    #[serde(rename = "d", with = "string_or_float")]
+    pub balance_delta: f64,
+    #[serde(rename = "T")]
+    pub clear_time: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MiniTicker {
@@ -295,3 +1003,266 @@ pub struct MiniTicker {
     #[serde(rename = "q", with = "string_or_float")]
     pub quote_volume: f64,
 }
+
+#[cfg(test)]
+mod test {
+    use super::{
+        parse_event, AveragePriceEvent, BinanceWebsocketMessage, BookTickerEvent,
+        ContinuousKlineEvent, Depth, ForceOrderEvent, LazyEntries, MarkPriceEvent, MiniTicker,
+        RollingWindowTicker, Subscription, SymbolAllowlist, TimeUnit, TokenNavEvent,
+    };
+    use crate::model::{timestamp, OrderStatus, Side};
+    use anyhow::Result;
+    use serde_json::from_str;
+
+    #[test]
+    fn symbol_allowlist() -> Result<()> {
+        let msg = r#"[{"e":"24hrMiniTicker","E":1718100000123,"s":"BTCUSDT","c":"67321.01","o":"66800.00","h":"67500.00","l":"66500.00","v":"12000.5","q":"805000000.0"},{"e":"24hrMiniTicker","E":1718100000123,"s":"ETHUSDT","c":"3500.10","o":"3450.00","h":"3550.00","l":"3400.00","v":"90000.0","q":"315000000.0"},{"e":"24hrMiniTicker","E":1718100000123,"s":"DOGEUSDT","c":"not a number"}]"#;
+        let allowlist: SymbolAllowlist = ["ethusdt", "BNBUSDT"].iter().collect();
+        assert!(allowlist.contains("EthUsdt"));
+
+        // The malformed entry is never deserialized
+        let tickers: Vec<MiniTicker> = allowlist.select(msg)?;
+        assert_eq!(tickers.len(), 1);
+        assert_eq!(tickers[0].symbol, "ETHUSDT");
+        assert!((tickers[0].close - 3500.1).abs() < 1e-9);
+
+        let none: Vec<MiniTicker> = SymbolAllowlist::default().select(msg)?;
+        assert!(none.is_empty());
+        assert!(allowlist.select::<MiniTicker>("{}").is_err());
+
+        let entries = LazyEntries::parse(msg)?;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries.symbols()?, ["BTCUSDT", "ETHUSDT", "DOGEUSDT"]);
+        let tickers: Vec<MiniTicker> = entries.select(|symbol| symbol.starts_with("BTC"))?;
+        assert_eq!(tickers[0].symbol, "BTCUSDT");
+        assert!(entries.select::<MiniTicker, _>(|_| true).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn book_ticker_event() -> Result<()> {
+        let msg = r#"{
+            "u": 400900217,
+            "s": "BNBUSDT",
+            "b": "25.35190000",
+            "B": "31.21000000",
+            "a": "25.36520000",
+            "A": "40.66000000"
+        }"#;
+        let event: BookTickerEvent = from_str(msg)?;
+        assert_eq!(event.update_id, 400_900_217);
+        assert_eq!(event.symbol, "BNBUSDT");
+        assert!((event.best_bid - 25.3519).abs() < f64::EPSILON);
+        assert!((event.best_ask_qty - 40.66).abs() < f64::EPSILON);
+        Ok(())
+    }
+
+    #[test]
+    fn average_price_event() -> Result<()> {
+        let msg = r#"{
+            "e": "avgPrice",
+            "E": 1693907033000,
+            "s": "BTCUSDT",
+            "i": "5m",
+            "w": "25776.86000000",
+            "T": 1693907032213
+        }"#;
+        let event: AveragePriceEvent = from_str(msg)?;
+        assert_eq!(event.interval, "5m");
+        assert!((event.average_price - 25776.86).abs() < f64::EPSILON);
+        Ok(())
+    }
+
+    #[test]
+    fn rolling_window_ticker() -> Result<()> {
+        let msg = r#"[{
+            "e": "1hTicker",
+            "E": 1672515782136,
+            "s": "BNBBTC",
+            "p": "0.0015",
+            "P": "250.00",
+            "o": "0.0010",
+            "h": "0.0025",
+            "l": "0.0010",
+            "c": "0.0025",
+            "w": "0.0018",
+            "v": "10000",
+            "q": "18",
+            "O": 0,
+            "C": 1675216573749,
+            "F": 0,
+            "L": 18150,
+            "n": 18151
+        }]"#;
+        let tickers: Vec<RollingWindowTicker> = from_str(msg)?;
+        assert_eq!(tickers[0].event_type, "1hTicker");
+        assert_eq!(tickers[0].num_trades, 18151);
+        Ok(())
+    }
+
+    #[test]
+    fn time_unit_to_datetime() {
+        let millis = TimeUnit::Millisecond
+            .to_datetime(1_693_907_033_000)
+            .unwrap();
+        let micros = TimeUnit::Microsecond
+            .to_datetime(1_693_907_033_000_123)
+            .unwrap();
+        assert_eq!(micros.timestamp_millis(), millis.timestamp_millis());
+        assert_eq!(micros.timestamp_subsec_micros(), 123);
+    }
+
+    #[test]
+    fn depth_sequence() -> Result<()> {
+        let first: Depth = from_str(
+            r#"{"e":"depthUpdate","E":1672515782136,"s":"BNBBTC","U":157,"u":160,"b":[["0.0024","10"]],"a":[]}"#,
+        )?;
+        let second: Depth = from_str(
+            r#"{"e":"depthUpdate","E":1672515782236,"s":"BNBBTC","U":161,"u":165,"b":[],"a":[["0.0026","100"]]}"#,
+        )?;
+        assert_eq!(first.event_time, 1_672_515_782_136);
+        assert!(first.bridges_snapshot(158));
+        assert!(!first.bridges_snapshot(160));
+        assert!(first.is_outdated(160));
+        assert!(second.follows(&first));
+        assert!(!first.follows(&second));
+        Ok(())
+    }
+
+    #[test]
+    fn listen_key_expired() -> Result<()> {
+        let message = parse_event(
+            r#"{"e":"listenKeyExpired","E":"1699596037418","listenKey":"OfYGbUzi3PraNagEkdKuFwUHn48brFsItTdsuiIXrucEvD0rhRXZ7I6URWfE8YE8"}"#,
+        )?;
+        let BinanceWebsocketMessage::ListenKeyExpired(expired) = message else {
+            panic!("{message:?}");
+        };
+        assert_eq!(timestamp::to_millis(&expired.event_time), 1_699_596_037_418);
+        assert!(expired.listen_key.starts_with("OfYGbUzi"));
+        Ok(())
+    }
+
+    #[test]
+    fn token_nav_event() -> Result<()> {
+        let msg = r#"{"e":"nav","E":1600246986000,"s":"BTCDOWN","m":10984.96722,"b":[{"s":"BTCUSDT","n":-242.7}],"n":4.26795155,"l":3.14279291,"t":3,"f":0.00174}"#;
+        let nav: TokenNavEvent = from_str(msg)?;
+        assert_eq!(nav.token_name, "BTCDOWN");
+        assert_eq!(nav.baskets[0].symbol, "BTCUSDT");
+        assert!((nav.target_leverage - 3.0).abs() < f64::EPSILON);
+        Ok(())
+    }
+
+    #[test]
+    fn force_order_event() -> Result<()> {
+        let msg = r#"{"e":"forceOrder","E":1568014460893,"o":{"s":"BTCUSDT","S":"SELL","o":"LIMIT","f":"IOC","q":"0.014","p":"9910","ap":"9910","X":"FILLED","l":"0.014","z":"0.014","T":1568014460893}}"#;
+        let event: ForceOrderEvent = from_str(msg)?;
+        assert_eq!(event.order.symbol, "BTCUSDT");
+        assert!(matches!(event.order.side, Side::Sell));
+        assert!(matches!(event.order.order_status, OrderStatus::Filled));
+        assert!((event.order.avg_price - 9910.0).abs() < f64::EPSILON);
+        assert!((event.order.accumulated_filled_qty - 0.014).abs() < f64::EPSILON);
+        Ok(())
+    }
+
+    #[test]
+    fn mark_price_events() -> Result<()> {
+        let msg = r#"[{"e":"markPriceUpdate","E":1562305380000,"s":"BTCUSDT","p":"11794.15000000","i":"11784.62659091","P":"11784.25641265","r":"0.00038167","T":1562306400000}]"#;
+        let events: Vec<MarkPriceEvent> = from_str(msg)?;
+        assert_eq!(events[0].symbol, "BTCUSDT");
+        assert!((events[0].mark_price - 11_794.15).abs() < f64::EPSILON);
+        assert!((events[0].index_price - 11_784.626_590_91).abs() < f64::EPSILON);
+        assert!((events[0].estimated_settle_price - 11_784.256_412_65).abs() < f64::EPSILON);
+        assert!((events[0].funding_rate - 0.000_381_67).abs() < f64::EPSILON);
+        assert_eq!(events[0].next_funding_time, 1_562_306_400_000);
+        Ok(())
+    }
+
+    #[test]
+    fn asset_index_events() -> Result<()> {
+        let msg = r#"[{"e":"assetIndexUpdate","E":1686749230000,"s":"ADAUSD","i":"0.27462452","b":"0.10000000","a":"0.10000000","B":"0.24716207","A":"0.30208698","q":"0.05000000","g":"0.05000000","Q":"0.26089330","G":"0.28835575"}]"#;
+        let BinanceWebsocketMessage::AssetIndexAll(events) = parse_event(msg)? else {
+            panic!("expected asset index updates");
+        };
+        assert_eq!(events[0].symbol, "ADAUSD");
+        assert!((events[0].index_price - 0.274_624_52).abs() < f64::EPSILON);
+        assert!((events[0].auto_exchange_ask_rate - 0.288_355_75).abs() < f64::EPSILON);
+        assert!(matches!(
+            parse_event(&msg[1..msg.len() - 1])?,
+            BinanceWebsocketMessage::AssetIndex(..)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn continuous_kline_event() -> Result<()> {
+        let msg = r#"{"e":"continuous_kline","E":1607443058651,"ps":"BTCUSDT","ct":"PERPETUAL","k":{"t":1607443020000,"T":1607443079999,"i":"1m","f":116467658886,"L":116468012423,"o":"18787.00","c":"18804.04","h":"18804.04","l":"18786.54","v":"197.664","n":543,"x":false,"q":"3715253.19494","V":"184.769","Q":"3472925.84746","B":"0"}}"#;
+        let event: ContinuousKlineEvent = from_str(msg)?;
+        assert_eq!(event.pair, "BTCUSDT");
+        assert_eq!(event.contract_type, "PERPETUAL");
+        assert_eq!(event.kline.interval, "1m");
+        assert!((event.kline.close - 18_804.04).abs() < f64::EPSILON);
+        assert!(!event.kline.is_final_bar);
+        Ok(())
+    }
+
+    #[test]
+    fn subscription_stream_name() -> Result<()> {
+        let names = [
+            "btcusdt@aggTrade",
+            "btcusdt@trade",
+            "btcusdt@kline_1m",
+            "btcusdt@miniTicker",
+            "!miniTicker@arr",
+            "btcusdt@ticker",
+            "!ticker@arr",
+            "btcusdt@depth5",
+            "btcusdt@depth",
+            "btcusdt@bookTicker",
+            "!bookTicker",
+            "btcusdt@avgPrice",
+            "btcusdt@ticker_1h",
+            "!ticker_4h@arr",
+            "BTCDOWN@tokenNav",
+            "btcusdt@forceOrder",
+            "!forceOrder@arr",
+            "btcusdt@markPrice@1s",
+            "!markPrice@arr",
+            "btcusdt_current_quarter@continuousKline_1h",
+            "btcusdt@kline_1d@+08:00",
+            "btcusdt@depth@100ms",
+            "btcusdt@depth20@100ms",
+            "adausd@assetIndex",
+            "!assetIndex@arr",
+            "pqia91ma19a5s61cv6a81va65sdf19v8a65a1a5s61cv6a81va65sdf19v8a65a1",
+        ];
+        for name in names {
+            assert_eq!(Subscription::from_stream_name(name)?.to_string(), name);
+        }
+
+        assert_eq!(
+            "btcusdt@depth5@100ms".parse::<Subscription>()?,
+            Subscription::OrderBookWithSpeed("btcusdt".into(), 5, 100)
+        );
+        assert_eq!(
+            "btcusdt@depth@1000ms".parse::<Subscription>()?,
+            Subscription::DepthWithSpeed("btcusdt".into(), 1000)
+        );
+        assert_eq!(
+            "btcusdt@markPrice".parse::<Subscription>()?,
+            Subscription::MarkPrice("btcusdt".into())
+        );
+        assert_eq!(
+            "btcusdt_perpetual@continuousKline_1m".parse::<Subscription>()?,
+            Subscription::ContinuousKline("btcusdt".into(), "perpetual".into(), "1m".into())
+        );
+        assert!(Subscription::from_stream_name("btcusdt@continuousKline_1m").is_err());
+        assert!(Subscription::from_stream_name("btcusdt@unknown").is_err());
+        assert!(Subscription::from_stream_name("!unknown").is_err());
+        assert!(Subscription::from_stream_name("btcusdt@kline_1m@+09:00").is_err());
+        assert!(Subscription::from_stream_name("btcusdt@depth@fast").is_err());
+        assert!(Subscription::from_stream_name("btcusdt@trade@100ms").is_err());
+        assert!(Subscription::from_stream_name("btcusdt@depth@100ms@1s").is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+//! Borrowed views of the most frequent stream events.
+//!
+//! A `TradeEventRef` and its siblings deserialize straight from the frame text: strings borrow
+//! from it and prices are parsed in place, so reading an event allocates nothing. `to_owned`
+//! converts a view into the owned model of `websocket` where an event has to outlive its frame.
+//! Strings only fall back to an owned copy if the exchange escaped a character in them.
+
+use super::websocket::{AggregateTrade, BookTickerEvent, MiniTicker, TradeMessage};
+use serde::{
+    de::{self, Visitor},
+    Deserialize, Deserializer,
+};
+use std::{borrow::Cow, fmt};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct TradeEventRef<'a> {
+    #[serde(rename = "e", borrow)]
+    pub event_type: Cow<'a, str>,
+    #[serde(rename = "E")]
+    pub event_time: i64,
+    #[serde(rename = "s", borrow)]
+    pub symbol: Cow<'a, str>,
+    #[serde(rename = "t")]
+    pub trade_id: i64,
+    #[serde(rename = "p", deserialize_with = "str_f64")]
+    pub price: f64,
+    #[serde(rename = "q", deserialize_with = "str_f64")]
+    pub qty: f64,
+    #[serde(rename = "T")]
+    pub trade_order_time: i64,
+    #[serde(rename = "m")]
+    pub is_buyer_maker: bool,
+    #[serde(rename = "M", default)]
+    pub m_ignore: bool,
+}
+
+impl TradeEventRef<'_> {
+    #[must_use]
+    pub fn to_owned(&self) -> TradeMessage {
+        TradeMessage {
+            event_type: self.event_type.to_string(),
+            event_time: self.event_time,
+            symbol: self.symbol.to_string(),
+            trade_id: self.trade_id,
+            price: self.price,
+            qty: self.qty,
+            trade_order_time: self.trade_order_time,
+            is_buyer_maker: self.is_buyer_maker,
+            m_ignore: self.m_ignore,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct AggregateTradeRef<'a> {
+    #[serde(rename = "e", borrow)]
+    pub event_type: Cow<'a, str>,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "s", borrow)]
+    pub symbol: Cow<'a, str>,
+    #[serde(rename = "a")]
+    pub aggregated_trade_id: u64,
+    #[serde(rename = "p", deserialize_with = "str_f64")]
+    pub price: f64,
+    #[serde(rename = "q", deserialize_with = "str_f64")]
+    pub qty: f64,
+    #[serde(rename = "f")]
+    pub first_break_trade_id: u64,
+    #[serde(rename = "l")]
+    pub last_break_trade_id: u64,
+    #[serde(rename = "T")]
+    pub trade_order_time: u64,
+    #[serde(rename = "m")]
+    pub is_buyer_maker: bool,
+    #[serde(rename = "M", default)]
+    pub m_ignore: bool,
+}
+
+impl AggregateTradeRef<'_> {
+    #[must_use]
+    pub fn to_owned(&self) -> AggregateTrade {
+        AggregateTrade {
+            event_type: self.event_type.to_string(),
+            event_time: self.event_time,
+            symbol: self.symbol.to_string(),
+            aggregated_trade_id: self.aggregated_trade_id,
+            price: self.price,
+            qty: self.qty,
+            first_break_trade_id: self.first_break_trade_id,
+            last_break_trade_id: self.last_break_trade_id,
+            trade_order_time: self.trade_order_time,
+            is_buyer_maker: self.is_buyer_maker,
+            m_ignore: self.m_ignore,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct BookTickerEventRef<'a> {
+    #[serde(rename = "u")]
+    pub update_id: u64,
+    #[serde(rename = "s", borrow)]
+    pub symbol: Cow<'a, str>,
+    #[serde(rename = "b", deserialize_with = "str_f64")]
+    pub best_bid: f64,
+    #[serde(rename = "B", deserialize_with = "str_f64")]
+    pub best_bid_qty: f64,
+    #[serde(rename = "a", deserialize_with = "str_f64")]
+    pub best_ask: f64,
+    #[serde(rename = "A", deserialize_with = "str_f64")]
+    pub best_ask_qty: f64,
+}
+
+impl BookTickerEventRef<'_> {
+    #[must_use]
+    pub fn to_owned(&self) -> BookTickerEvent {
+        BookTickerEvent {
+            update_id: self.update_id,
+            symbol: self.symbol.to_string(),
+            best_bid: self.best_bid,
+            best_bid_qty: self.best_bid_qty,
+            best_ask: self.best_ask,
+            best_ask_qty: self.best_ask_qty,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct MiniTickerRef<'a> {
+    #[serde(rename = "e", borrow)]
+    pub event_type: Cow<'a, str>,
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "s", borrow)]
+    pub symbol: Cow<'a, str>,
+    #[serde(rename = "c", deserialize_with = "str_f64")]
+    pub close: f64,
+    #[serde(rename = "o", deserialize_with = "str_f64")]
+    pub open: f64,
+    #[serde(rename = "l", deserialize_with = "str_f64")]
+    pub low: f64,
+    #[serde(rename = "h", deserialize_with = "str_f64")]
+    pub high: f64,
+    #[serde(rename = "v", deserialize_with = "str_f64")]
+    pub volume: f64,
+    #[serde(rename = "q", deserialize_with = "str_f64")]
+    pub quote_volume: f64,
+}
+
+impl MiniTickerRef<'_> {
+    #[must_use]
+    pub fn to_owned(&self) -> MiniTicker {
+        MiniTicker {
+            event_type: self.event_type.to_string(),
+            event_time: self.event_time,
+            symbol: self.symbol.to_string(),
+            close: self.close,
+            open: self.open,
+            low: self.low,
+            high: self.high,
+            volume: self.volume,
+            quote_volume: self.quote_volume,
+        }
+    }
+}
+
+// Like `string_or_float`, without buffering the value into an owned `String` first
+fn str_f64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<f64, D::Error> {
+    struct StrF64;
+
+    impl Visitor<'_> for StrF64 {
+        type Value = f64;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a number or a string holding one")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<f64, E> {
+            value.parse().map_err(E::custom)
+        }
+
+        fn visit_f64<E: de::Error>(self, value: f64) -> Result<f64, E> {
+            Ok(value)
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<f64, E> {
+            Ok(value as f64)
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<f64, E> {
+            Ok(value as f64)
+        }
+    }
+
+    deserializer.deserialize_any(StrF64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::{AggregateTradeRef, BookTickerEventRef, MiniTickerRef, TradeEventRef};
+    use anyhow::Result;
+    use serde_json::from_str;
+    use std::borrow::Cow;
+
+    #[test]
+    fn borrowed_events() -> Result<()> {
+        let msg = r#"{"e":"trade","E":1718100000123,"s":"BTCUSDT","t":3634212513,"p":"67321.01000000","q":"0.00200000","T":1718100000122,"m":false,"M":true}"#;
+        let trade: TradeEventRef = from_str(msg)?;
+        assert!(matches!(trade.symbol, Cow::Borrowed("BTCUSDT")));
+        assert!((trade.price - 67_321.01).abs() < 1e-9);
+        let owned = trade.to_owned();
+        assert_eq!(owned.symbol, "BTCUSDT");
+        assert_eq!(owned.trade_id, 3_634_212_513);
+
+        let msg = r#"{"e":"aggTrade","E":1718100000123,"s":"BNBBTC","a":12345,"p":"0.001","q":"100","f":100,"l":105,"T":1718100000120,"m":true,"M":true}"#;
+        let trade: AggregateTradeRef = from_str(msg)?;
+        assert_eq!(trade.to_owned().last_break_trade_id, 105);
+
+        let msg = r#"{"u":400900217,"s":"BNBUSDT","b":"25.35190000","B":"31.21000000","a":"25.36520000","A":40.66}"#;
+        let ticker: BookTickerEventRef = from_str(msg)?;
+        assert!((ticker.to_owned().best_ask_qty - 40.66).abs() < 1e-9);
+
+        // Escaped strings cannot be borrowed
+        let msg = r#"{"e":"24hrMiniTicker","E":1718100000123,"s":"ETH\u0055SDT","c":"3500.10","o":"3450.00","h":"3550.00","l":"3400.00","v":"90000.0","q":"315000000.0"}"#;
+        let ticker: MiniTickerRef = from_str(msg)?;
+        assert!(matches!(ticker.symbol, Cow::Owned(..)));
+        assert_eq!(ticker.to_owned().symbol, "ETHUSDT");
+
+        assert!(from_str::<TradeEventRef>(r#"{"e":"trade","p":"abc"}"#).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::error::{BinanceErrorData, Error};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// State of a websocket API connection, answer to `session.logon`, `session.status` and
+// `session.logout`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStatus {
+    // `None` unless the session is logged on
+    pub api_key: Option<String>,
+    // Millisecond timestamp of the logon
+    pub authorized_since: Option<u64>,
+    pub connected_since: u64,
+    pub return_rate_limits: bool,
+    pub server_time: u64,
+    // Whether user data events are subscribed on this connection
+    #[serde(default)]
+    pub user_data_stream: bool,
+}
+
+// Every message of the websocket API connection: an answer to a request, or a user data event
+// once `userDataStream.subscribe` succeeded
+#[derive(Debug, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum WsApiMessage {
+    Response(WsApiResponse),
+    Event { event: Value },
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct WsApiResponse {
+    // `id` of the request
+    pub id: u64,
+    pub status: u16,
+    pub result: Option<Value>,
+    pub error: Option<BinanceErrorData>,
+}
+
+impl WsApiResponse {
+    // `Error::BinanceError` for a rejected request
+    pub fn into_result(self) -> Result<Value, Error> {
+        match self.error {
+            Some(BinanceErrorData { code, msg }) => Err(Error::BinanceError { code, msg }),
+            None => Ok(self.result.unwrap_or(Value::Null)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SessionStatus, WsApiMessage};
+    use anyhow::Result;
+    use serde_json::from_str;
+
+    #[test]
+    fn session_status() -> Result<()> {
+        let status: SessionStatus = from_str(
+            r#"{"apiKey":"vmPUZE6mv9SD5VNHk4HlWFsOr6aKE2zvsw0MuIgwCIPy6utIco14y7Ju91duEh8A","authorizedSince":1649729878532,"connectedSince":1649729873021,"returnRateLimits":false,"serverTime":1649729878630,"userDataStream":false}"#,
+        )?;
+        assert_eq!(status.authorized_since, Some(1_649_729_878_532));
+        let logged_out: SessionStatus = from_str(
+            r#"{"apiKey":null,"authorizedSince":null,"connectedSince":1649729873021,"returnRateLimits":false,"serverTime":1649730611671}"#,
+        )?;
+        assert!(logged_out.api_key.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn messages() -> Result<()> {
+        let rejected: WsApiMessage = from_str(
+            r#"{"id":3,"status":400,"error":{"code":-1021,"msg":"Timestamp for this request is outside of the recvWindow."},"rateLimits":[]}"#,
+        )?;
+        let WsApiMessage::Response(response) = rejected else {
+            panic!("not a response");
+        };
+        assert_eq!(response.id, 3);
+        assert!(response.into_result().is_err());
+
+        let event: WsApiMessage = from_str(
+            r#"{"subscriptionId":0,"event":{"e":"outboundAccountPosition","E":1728972148778,"u":1728972148778,"B":[{"a":"BTC","f":"11818.00000000","l":"0.00000000"}]}}"#,
+        )?;
+        assert!(matches!(event, WsApiMessage::Event { .. }));
+        Ok(())
+    }
+}
@@ -0,0 +1,174 @@
+//! Warnings about symbols a bot still has open orders on, but which are about to be delisted
+//! or stopped trading.
+
+use crate::{
+    client::Binance,
+    model::{timestamp, DelistSchedule, Order, Symbol},
+};
+use anyhow::Result;
+use chrono::Utc;
+use log::warn;
+use std::{collections::HashMap, convert::TryFrom, time::Duration};
+
+const TRADING: &str = "TRADING";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SymbolWarning {
+    // `symbol` will be delisted at `delist_time` (milliseconds)
+    Delisting {
+        symbol: String,
+        delist_time: u64,
+        open_orders: usize,
+    },
+    // `symbol` is in `status` ("BREAK", "HALT", ...) instead of "TRADING"
+    NotTrading {
+        symbol: String,
+        status: String,
+        open_orders: usize,
+    },
+    StatusChanged {
+        symbol: String,
+        from: String,
+        to: String,
+    },
+}
+
+// Remembers the last seen status of every symbol with open orders to report changes
+#[derive(Debug, Clone, Default)]
+pub struct SymbolMonitor {
+    statuses: HashMap<String, String>,
+}
+
+impl SymbolMonitor {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Checks every symbol with open orders against the delist schedule and its current status.
+    // Delistings further away than `horizon` are ignored. Warnings are logged and returned.
+    pub async fn check(
+        &mut self,
+        client: &Binance,
+        horizon: Duration,
+    ) -> Result<Vec<SymbolWarning>> {
+        let open_orders = client.get_all_open_orders().await?;
+        if open_orders.is_empty() {
+            return Ok(Vec::new());
+        }
+        let schedule = client.get_delist_schedule().await?;
+        let mut symbols: Vec<&str> = open_orders.iter().map(|o| o.symbol.as_str()).collect();
+        symbols.sort_unstable();
+        symbols.dedup();
+        let info = client.get_exchange_info_for(&symbols).await?;
+
+        let now = u64::try_from(Utc::now().timestamp_millis()).unwrap_or_default();
+        let until = now.saturating_add(u64::try_from(horizon.as_millis()).unwrap_or(u64::MAX));
+        let warnings = self.warnings(until, &open_orders, &schedule, &info.symbols);
+        for warning in &warnings {
+            warn!("{warning:?}");
+        }
+        Ok(warnings)
+    }
+
+    // Warnings for the symbols of `open_orders`, `until` is the end of the delisting horizon
+    pub fn warnings(
+        &mut self,
+        until: u64,
+        open_orders: &[Order],
+        schedule: &[DelistSchedule],
+        symbols: &[Symbol],
+    ) -> Vec<SymbolWarning> {
+        let mut order_counts: HashMap<&str, usize> = HashMap::new();
+        for order in open_orders {
+            *order_counts.entry(order.symbol.as_str()).or_default() += 1;
+        }
+
+        let mut warnings = Vec::new();
+        for delisting in schedule {
+            let delist_time = timestamp::to_millis(&delisting.delist_time);
+            if delist_time > until {
+                continue;
+            }
+            for symbol in &delisting.symbols {
+                if let Some(&open_orders) = order_counts.get(symbol.as_str()) {
+                    warnings.push(SymbolWarning::Delisting {
+                        symbol: symbol.clone(),
+                        delist_time,
+                        open_orders,
+                    });
+                }
+            }
+        }
+
+        for symbol in symbols {
+            let Some(&open_orders) = order_counts.get(symbol.symbol.as_str()) else {
+                continue;
+            };
+            if let Some(previous) = self
+                .statuses
+                .insert(symbol.symbol.clone(), symbol.status.clone())
+            {
+                if previous != symbol.status {
+                    warnings.push(SymbolWarning::StatusChanged {
+                        symbol: symbol.symbol.clone(),
+                        from: previous,
+                        to: symbol.status.clone(),
+                    });
+                }
+            }
+            if symbol.status != TRADING {
+                warnings.push(SymbolWarning::NotTrading {
+                    symbol: symbol.symbol.clone(),
+                    status: symbol.status.clone(),
+                    open_orders,
+                });
+            }
+        }
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SymbolMonitor, SymbolWarning};
+    use crate::model::{DelistSchedule, Order, Symbol};
+    use anyhow::Result;
+    use serde_json::from_str;
+
+    fn symbol(name: &str, status: &str) -> Result<Symbol> {
+        let json = format!(
+            r#"{{"symbol":"{name}","status":"{status}","baseAsset":"X","baseAssetPrecision":8,"quoteAsset":"USDT","quotePrecision":8,"quoteAssetPrecision":8,"baseCommissionPrecision":8,"quoteCommissionPrecision":8,"orderTypes":["LIMIT"],"icebergAllowed":true,"ocoAllowed":true,"quoteOrderQtyMarketAllowed":true,"isSpotTradingAllowed":true,"isMarginTradingAllowed":false}}"#
+        );
+        Ok(from_str(&json)?)
+    }
+
+    #[test]
+    fn symbol_warnings() -> Result<()> {
+        let orders: Vec<Order> = from_str(
+            r#"[{"symbol":"ADAUSDT","orderId":1,"clientOrderId":"a","price":"0.4","origQty":"10","executedQty":"0","status":"NEW","timeInForce":"GTC","type":"LIMIT","side":"BUY","stopPrice":"0","icebergQty":"0","time":1686000000000},
+                {"symbol":"BNBUSDT","orderId":2,"clientOrderId":"b","price":"300","origQty":"1","executedQty":"0","status":"NEW","timeInForce":"GTC","type":"LIMIT","side":"SELL","stopPrice":"0","icebergQty":"0","time":1686000000000}]"#,
+        )?;
+        let schedule: Vec<DelistSchedule> = from_str(
+            r#"[{"delistTime":1686161202000,"symbols":["ADAUSDT","XRPUSDT"]},{"delistTime":1999999999000,"symbols":["BNBUSDT"]}]"#,
+        )?;
+
+        let mut monitor = SymbolMonitor::new();
+        let symbols = [symbol("ADAUSDT", "TRADING")?, symbol("BNBUSDT", "TRADING")?];
+        let warnings = monitor.warnings(1_686_200_000_000, &orders, &schedule, &symbols);
+        assert_eq!(
+            warnings,
+            vec![SymbolWarning::Delisting {
+                symbol: "ADAUSDT".into(),
+                delist_time: 1_686_161_202_000,
+                open_orders: 1,
+            }]
+        );
+
+        let symbols = [symbol("BNBUSDT", "BREAK")?];
+        let warnings = monitor.warnings(1_686_200_000_000, &orders, &[], &symbols);
+        assert_eq!(warnings.len(), 2);
+        assert!(matches!(&warnings[0], SymbolWarning::StatusChanged { to, .. } if to == "BREAK"));
+        Ok(())
+    }
+}
@@ -0,0 +1,293 @@
+//! One view of the spot, cross margin and USDⓈ-M futures wallets of an account.
+//!
+//! `Portfolio::snapshot` queries the three accounts and the current prices at once, merges the
+//! holdings by asset and values them in a single quote asset. Futures holdings count with their
+//! unrealized profit, margin holdings net of loans and interest. An account without margin or
+//! futures enabled gets an empty section for it.
+
+use crate::{
+    client::Binance,
+    error::Error,
+    model::{
+        futures::{FuturesAccount, FuturesPosition},
+        margin::MarginAccount,
+        AccountInformation, Prices, SymbolPrice,
+    },
+};
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
+
+// Assets without a market against the quote asset are priced through these
+const BRIDGE_ASSETS: [&str; 2] = ["USDT", "BTC"];
+// Answer of the margin endpoints for an account that never opened margin
+const MARGIN_ACCOUNT_MISSING: i64 = -3003;
+// Answer of the futures endpoints for a key without futures, the account not being opened
+const FUTURES_NOT_ENABLED: i64 = -2015;
+
+#[derive(Debug, Clone)]
+pub struct Portfolio {
+    client: Binance,
+    quote: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssetHolding {
+    pub asset: String,
+    pub spot: f64,
+    pub margin: f64,
+    pub futures: f64,
+    // Value of `total()` in the quote asset, `None` when no price was found
+    pub value: Option<f64>,
+}
+
+impl AssetHolding {
+    #[must_use]
+    pub fn total(&self) -> f64 {
+        self.spot + self.margin + self.futures
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PortfolioSnapshot {
+    pub quote: String,
+    // Sorted by asset name
+    pub assets: Vec<AssetHolding>,
+    // Open futures positions only
+    pub positions: Vec<FuturesPosition>,
+    // Value of the priced assets in `quote`
+    pub total_equity: f64,
+}
+
+impl PortfolioSnapshot {
+    #[must_use]
+    pub fn holding(&self, asset: &str) -> Option<&AssetHolding> {
+        let asset = asset.to_uppercase();
+        self.assets.iter().find(|holding| holding.asset == asset)
+    }
+
+    // Assets left out of `total_equity`
+    pub fn unpriced(&self) -> impl Iterator<Item = &str> {
+        self.assets
+            .iter()
+            .filter(|holding| holding.value.is_none())
+            .map(|holding| holding.asset.as_str())
+    }
+}
+
+impl Portfolio {
+    #[must_use]
+    pub fn new(client: Binance, quote: &str) -> Self {
+        Self {
+            client,
+            quote: quote.to_uppercase(),
+        }
+    }
+
+    pub async fn snapshot(&self) -> Result<PortfolioSnapshot> {
+        let margin = async {
+            missing_as_none(
+                self.client.get_margin_account().await,
+                MARGIN_ACCOUNT_MISSING,
+            )
+        };
+        let futures_account =
+            async { missing_as_none(self.client.get_futures_account().await, FUTURES_NOT_ENABLED) };
+        let (spot, margin, futures, prices) = futures::try_join!(
+            self.client.get_account(),
+            margin,
+            futures_account,
+            self.client.get_all_prices(),
+        )?;
+        let Prices::AllPrices(prices) = prices;
+        Ok(build_snapshot(
+            &self.quote,
+            &spot,
+            margin.as_ref(),
+            futures,
+            &prices,
+        ))
+    }
+}
+
+// `None` for an account section failing with `code`, the section not being enabled
+fn missing_as_none<T>(result: Result<T>, code: i64) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e)
+            if matches!(
+                e.downcast_ref::<Error>(),
+                Some(Error::BinanceError { code: c, .. }) if *c == code
+            ) =>
+        {
+            Ok(None)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+fn build_snapshot(
+    quote: &str,
+    spot: &AccountInformation,
+    margin: Option<&MarginAccount>,
+    futures: Option<FuturesAccount>,
+    prices: &[SymbolPrice],
+) -> PortfolioSnapshot {
+    let prices: HashMap<&str, f64> = prices
+        .iter()
+        .map(|price| (price.symbol.as_str(), price.price))
+        .collect();
+    let mut holdings: BTreeMap<String, AssetHolding> = BTreeMap::new();
+    for balance in &spot.balances {
+        let free: f64 = balance.free.parse().unwrap_or_default();
+        let locked: f64 = balance.locked.parse().unwrap_or_default();
+        holding_of(&mut holdings, &balance.asset, quote, &prices).spot += free + locked;
+    }
+    for asset in margin.iter().flat_map(|margin| &margin.user_assets) {
+        holding_of(&mut holdings, &asset.asset, quote, &prices).margin += asset.net_asset;
+    }
+    for asset in futures.iter().flat_map(|futures| &futures.assets) {
+        holding_of(&mut holdings, &asset.asset, quote, &prices).futures += asset.margin_balance;
+    }
+
+    let mut total_equity = 0.0;
+    let assets = holdings
+        .into_values()
+        .filter(|holding| holding.total() != 0.0)
+        .map(|mut holding| {
+            holding.value = price_in(&holding.asset, quote, &prices).map(|p| p * holding.total());
+            total_equity += holding.value.unwrap_or_default();
+            holding
+        })
+        .collect();
+
+    PortfolioSnapshot {
+        quote: quote.into(),
+        assets,
+        positions: futures
+            .into_iter()
+            .flat_map(|futures| futures.positions)
+            .filter(|position| position.position_amt != 0.0)
+            .collect(),
+        total_equity,
+    }
+}
+
+fn holding_of<'a>(
+    holdings: &'a mut BTreeMap<String, AssetHolding>,
+    asset: &str,
+    quote: &str,
+    prices: &HashMap<&str, f64>,
+) -> &'a mut AssetHolding {
+    let asset = normalize_asset(asset, quote, prices);
+    holdings
+        .entry(asset.clone())
+        .or_insert_with(|| AssetHolding {
+            asset,
+            spot: 0.0,
+            margin: 0.0,
+            futures: 0.0,
+            value: None,
+        })
+}
+
+// Flexible Simple Earn balances show up in the spot wallet as "LD" + asset, e.g. LDBTC. The
+// prefix is only dropped if the name is not an asset of its own, like LDO.
+fn normalize_asset(asset: &str, quote: &str, prices: &HashMap<&str, f64>) -> String {
+    let asset = asset.trim().to_uppercase();
+    match asset.strip_prefix("LD") {
+        Some(underlying)
+            if price_in(&asset, quote, prices).is_none()
+                && price_in(underlying, quote, prices).is_some() =>
+        {
+            underlying.into()
+        }
+        _ => asset,
+    }
+}
+
+// Price of ONE `asset` in `quote`, directly or through one of the bridge assets
+fn price_in(asset: &str, quote: &str, prices: &HashMap<&str, f64>) -> Option<f64> {
+    rate(asset, quote, prices).or_else(|| {
+        BRIDGE_ASSETS
+            .iter()
+            .find_map(|bridge| Some(rate(asset, bridge, prices)? * rate(bridge, quote, prices)?))
+    })
+}
+
+fn rate(from: &str, to: &str, prices: &HashMap<&str, f64>) -> Option<f64> {
+    if from == to {
+        return Some(1.0);
+    }
+    prices
+        .get(format!("{from}{to}").as_str())
+        .copied()
+        .or_else(|| {
+            prices
+                .get(format!("{to}{from}").as_str())
+                .filter(|price| **price > 0.0)
+                .map(|price| 1.0 / price)
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{build_snapshot, missing_as_none, MARGIN_ACCOUNT_MISSING};
+    use crate::{error::Error, model::SymbolPrice};
+    use anyhow::Result;
+    use serde_json::from_str;
+
+    #[test]
+    fn merged_holdings() -> Result<()> {
+        let spot = from_str(
+            r#"{"makerCommission":10,"takerCommission":10,"buyerCommission":0,"sellerCommission":0,"canTrade":true,"canWithdraw":true,"canDeposit":true,"balances":[{"asset":"BTC","free":"0.5","locked":"0.5"},{"asset":"LDBTC","free":"1.0","locked":"0"},{"asset":"LDO","free":"100","locked":"0"},{"asset":"XYZ","free":"3","locked":"0"},{"asset":"ETH","free":"0","locked":"0"}]}"#,
+        )?;
+        let margin = from_str(
+            r#"{"borrowEnabled":true,"marginLevel":"999","totalAssetOfBtc":"0","totalLiabilityOfBtc":"0","totalNetAssetOfBtc":"0","tradeEnabled":true,"transferEnabled":true,"userAssets":[{"asset":"ETH","borrowed":"1","free":"3","interest":"0","locked":"0","netAsset":"2"}]}"#,
+        )?;
+        let futures = from_str(
+            r#"{"totalWalletBalance":"1000","totalUnrealizedProfit":"-10","totalMarginBalance":"990","availableBalance":"900","assets":[{"asset":"USDT","walletBalance":"1000","unrealizedProfit":"-10","marginBalance":"990","availableBalance":"900"}],"positions":[{"symbol":"BTCUSDT","positionAmt":"0.01","entryPrice":"50000","unrealizedProfit":"-10","leverage":"10","isolated":false,"positionSide":"BOTH"},{"symbol":"ETHUSDT","positionAmt":"0","entryPrice":"0","unrealizedProfit":"0","leverage":"20","isolated":false,"positionSide":"BOTH"}]}"#,
+        )?;
+        let prices: Vec<SymbolPrice> = from_str(
+            r#"[{"symbol":"BTCUSDT","price":"50000"},{"symbol":"ETHBTC","price":"0.05"},{"symbol":"LDOUSDT","price":"2"}]"#,
+        )?;
+
+        let snapshot = build_snapshot("USDT", &spot, Some(&margin), Some(futures), &prices);
+        let btc = snapshot.holding("btc").unwrap();
+        assert!((btc.spot - 2.0).abs() < f64::EPSILON);
+        assert_eq!(btc.value, Some(100_000.0));
+        // Through the BTC bridge
+        let eth = snapshot.holding("ETH").unwrap();
+        assert_eq!(eth.value, Some(5_000.0));
+        assert_eq!(snapshot.holding("LDO").unwrap().value, Some(200.0));
+        assert_eq!(snapshot.holding("USDT").unwrap().value, Some(990.0));
+        assert_eq!(snapshot.unpriced().collect::<Vec<_>>(), vec!["XYZ"]);
+        assert!((snapshot.total_equity - 106_190.0).abs() < 1e-6);
+        assert_eq!(snapshot.positions.len(), 1);
+
+        // Spot only
+        let snapshot = build_snapshot("USDT", &spot, None, None, &prices);
+        assert_eq!(snapshot.holding("ETH"), None);
+        assert!(snapshot.positions.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn missing_sections() {
+        let error = |code| -> Result<()> {
+            Err(Error::BinanceError {
+                code,
+                msg: String::new(),
+            }
+            .into())
+        };
+        assert_eq!(
+            missing_as_none(error(MARGIN_ACCOUNT_MISSING), MARGIN_ACCOUNT_MISSING).ok(),
+            Some(None)
+        );
+        assert!(missing_as_none(error(-1021), MARGIN_ACCOUNT_MISSING).is_err());
+        assert_eq!(
+            missing_as_none(Ok(1), MARGIN_ACCOUNT_MISSING).ok(),
+            Some(Some(1))
+        );
+    }
+}
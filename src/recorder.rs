@@ -0,0 +1,238 @@
+//! Recording of raw websocket frames and their replay as a stream.
+//!
+//! A `Recorder` handed to `BinanceWebsocket::with_recorder` writes every text frame with the
+//! time it was received and the stream it came from. `ReplayWebsocket` reads such a file back
+//! and yields the same `BinanceWebsocketMessage`s as the live stream did, in recorded order, so
+//! a strategy sees exactly the same input on every run.
+//!
+//! Two formats are supported: newline-delimited JSON, one `RecordedFrame` per line, and a
+//! compact binary one, per frame the receive time as u64, the stream name with a u16 length
+//! and the frame with a u32 length, all little endian.
+
+use crate::{
+    client::websocket::parse_message,
+    model::websocket::{BinanceWebsocketMessage, Subscription},
+};
+use anyhow::Result;
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::VecDeque,
+    convert::TryFrom,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, Read, Write},
+    path::Path,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio_tungstenite::tungstenite::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordFormat {
+    Json,
+    Binary,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedFrame {
+    // Millisecond timestamp the frame was received at
+    pub received: u64,
+    // Stream name of the subscription, e.g. "btcusdt@trade"
+    pub stream: String,
+    // Text of the frame as sent by the exchange
+    pub frame: String,
+}
+
+pub struct Recorder {
+    writer: Box<dyn Write + Send>,
+    format: RecordFormat,
+}
+
+impl Recorder {
+    // Record into a new file at `path`, replacing an existing one
+    pub fn create<P: AsRef<Path>>(path: P, format: RecordFormat) -> Result<Self> {
+        Ok(Self::new(BufWriter::new(File::create(path)?), format))
+    }
+
+    #[must_use]
+    pub fn new<W: Write + Send + 'static>(writer: W, format: RecordFormat) -> Self {
+        Self {
+            writer: Box::new(writer),
+            format,
+        }
+    }
+
+    pub fn record(&mut self, frame: &RecordedFrame) -> Result<()> {
+        match self.format {
+            RecordFormat::Json => {
+                serde_json::to_writer(&mut self.writer, frame)?;
+                self.writer.write_all(b"\n")?;
+            }
+            RecordFormat::Binary => {
+                let stream_len = u16::try_from(frame.stream.len())?;
+                let frame_len = u32::try_from(frame.frame.len())?;
+                self.writer.write_all(&frame.received.to_le_bytes())?;
+                self.writer.write_all(&stream_len.to_le_bytes())?;
+                self.writer.write_all(frame.stream.as_bytes())?;
+                self.writer.write_all(&frame_len.to_le_bytes())?;
+                self.writer.write_all(frame.frame.as_bytes())?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn flush(&mut self) -> Result<()> {
+        Ok(self.writer.flush()?)
+    }
+}
+
+impl Drop for Recorder {
+    fn drop(&mut self) {
+        let _ = self.writer.flush();
+    }
+}
+
+// Yields the messages of a recording, then ends
+pub struct ReplayWebsocket {
+    frames: VecDeque<RecordedFrame>,
+    last_received: Option<u64>,
+}
+
+impl ReplayWebsocket {
+    pub fn open<P: AsRef<Path>>(path: P, format: RecordFormat) -> Result<Self> {
+        Self::from_reader(BufReader::new(File::open(path)?), format)
+    }
+
+    pub fn from_reader<R: BufRead>(reader: R, format: RecordFormat) -> Result<Self> {
+        let frames = match format {
+            RecordFormat::Json => reader
+                .lines()
+                .filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty()))
+                .map(|line| Ok(serde_json::from_str(&line?)?))
+                .collect::<Result<_>>()?,
+            RecordFormat::Binary => read_binary(reader)?,
+        };
+        Ok(Self::from_frames(frames))
+    }
+
+    #[must_use]
+    pub fn from_frames<I: IntoIterator<Item = RecordedFrame>>(frames: I) -> Self {
+        Self {
+            frames: frames.into_iter().collect(),
+            last_received: None,
+        }
+    }
+
+    // Receive time of the message yielded last, the clock of the replay
+    #[must_use]
+    pub const fn last_received(&self) -> Option<u64> {
+        self.last_received
+    }
+
+    // Frames not replayed yet
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.frames.len()
+    }
+}
+
+impl Stream for ReplayWebsocket {
+    type Item = Result<BinanceWebsocketMessage>;
+
+    fn poll_next(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let Some(frame) = this.frames.pop_front() else {
+            return Poll::Ready(None);
+        };
+        this.last_received = Some(frame.received);
+        let message = Subscription::from_stream_name(&frame.stream)
+            .map_err(Into::into)
+            .and_then(|sub| parse_message(&sub, Message::Text(frame.frame)));
+        Poll::Ready(Some(message))
+    }
+}
+
+fn read_binary<R: Read>(mut reader: R) -> Result<Vec<RecordedFrame>> {
+    let mut frames = Vec::new();
+    loop {
+        let mut received = [0; 8];
+        match reader.read_exact(&mut received) {
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(frames),
+            result => result?,
+        }
+        let mut stream_len = [0; 2];
+        reader.read_exact(&mut stream_len)?;
+        let mut stream = vec![0; usize::from(u16::from_le_bytes(stream_len))];
+        reader.read_exact(&mut stream)?;
+        let mut frame_len = [0; 4];
+        reader.read_exact(&mut frame_len)?;
+        let mut frame = vec![0; usize::try_from(u32::from_le_bytes(frame_len))?];
+        reader.read_exact(&mut frame)?;
+        frames.push(RecordedFrame {
+            received: u64::from_le_bytes(received),
+            stream: String::from_utf8(stream)?,
+            frame: String::from_utf8(frame)?,
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{RecordFormat, RecordedFrame, Recorder, ReplayWebsocket};
+    use crate::model::websocket::BinanceWebsocketMessage;
+    use anyhow::Result;
+    use futures::StreamExt;
+    use std::{
+        io::{Cursor, Write},
+        sync::{Arc, Mutex},
+    };
+
+    // Keeps what was written readable after the recorder took ownership
+    #[derive(Clone, Default)]
+    struct Shared(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Shared {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn record_and_replay() -> Result<()> {
+        let frames = vec![
+            RecordedFrame {
+                received: 1_718_100_000_125,
+                stream: "btcusdt@trade".into(),
+                frame: r#"{"e":"trade","E":1718100000123,"s":"BTCUSDT","t":3634212513,"p":"67321.01000000","q":"0.00200000","T":1718100000122,"m":false,"M":true}"#.into(),
+            },
+            RecordedFrame {
+                received: 1_718_100_000_200,
+                stream: "btcusdt@trade".into(),
+                frame: "not json".into(),
+            },
+        ];
+
+        for format in [RecordFormat::Json, RecordFormat::Binary] {
+            let buffer = Shared::default();
+            let mut recorder = Recorder::new(buffer.clone(), format);
+            for frame in &frames {
+                recorder.record(frame)?;
+            }
+            drop(recorder);
+
+            let bytes = buffer.0.lock().unwrap().clone();
+            let mut replay = ReplayWebsocket::from_reader(Cursor::new(bytes), format)?;
+            assert_eq!(replay.remaining(), 2);
+            let trade = replay.next().await.unwrap()?;
+            assert!(matches!(trade, BinanceWebsocketMessage::Trade(..)));
+            assert_eq!(replay.last_received(), Some(1_718_100_000_125));
+            assert!(replay.next().await.unwrap().is_err());
+            assert!(replay.next().await.is_none());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,277 @@
+//! Conversion of one asset into another through the spot markets.
+//!
+//! `ConversionRouter::best_path` compares the direct market between two assets with every
+//! two-hop route through a third asset, at the current best bid and ask and after the taker
+//! fee of each leg. `execute` then trades the legs one after another as market orders, every leg
+//! spending what the previous one actually received.
+
+use crate::{
+    client::Binance,
+    model::{floor_decimals, BookTickers, Side, Symbol, Ticker, Transaction},
+};
+use anyhow::Result;
+use std::collections::HashMap;
+
+// Taker fee of the default VIP 0 tier
+const DEFAULT_FEE_RATE: f64 = 0.001;
+
+#[derive(Debug, Clone)]
+pub struct ConversionRouter {
+    client: Binance,
+    fee_rate: f64,
+}
+
+// One market order of a conversion
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionLeg {
+    pub symbol: String,
+    // SELL spends the base asset, BUY spends the quote asset
+    pub side: Side,
+    pub from_asset: String,
+    pub to_asset: String,
+    // Best bid for a SELL, best ask for a BUY
+    pub price: f64,
+    // Decimals a BUY may spend, the quote asset precision of the symbol
+    pub quote_precision: u64,
+    pub amount_in: f64,
+    // Expected amount after the fee
+    pub amount_out: f64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionPath {
+    // One leg for a direct market, two through an intermediate asset
+    pub legs: Vec<ConversionLeg>,
+    pub amount_in: f64,
+    pub amount_out: f64,
+}
+
+impl ConversionPath {
+    // `to_asset` received per `from_asset` spent
+    #[must_use]
+    pub fn rate(&self) -> f64 {
+        self.amount_out / self.amount_in
+    }
+}
+
+impl ConversionRouter {
+    #[must_use]
+    pub const fn new(client: Binance) -> Self {
+        Self {
+            client,
+            fee_rate: DEFAULT_FEE_RATE,
+        }
+    }
+
+    // Taker fee rate charged on every leg, 0.001 unless the account pays less
+    #[must_use]
+    pub const fn with_fee_rate(mut self, fee_rate: f64) -> Self {
+        self.fee_rate = fee_rate;
+        self
+    }
+
+    // Route with the most `to_asset` for `amount` of `from_asset`, `None` without any. Exchange
+    // info and book tickers are served from the client's cache if `cache_ttl` is set.
+    pub async fn best_path(
+        &self,
+        from_asset: &str,
+        to_asset: &str,
+        amount: f64,
+    ) -> Result<Option<ConversionPath>> {
+        let (info, tickers) = futures::try_join!(
+            self.client.exchange_info(),
+            self.client.get_all_book_tickers()
+        )?;
+        let BookTickers::AllBookTickers(tickers) = tickers;
+        Ok(find_best_path(
+            &info.symbols,
+            &tickers,
+            &from_asset.to_uppercase(),
+            &to_asset.to_uppercase(),
+            amount,
+            self.fee_rate,
+        ))
+    }
+
+    // Trade the legs of `path` in order. A failing leg stops the conversion, the assets
+    // received so far stay in the account.
+    pub async fn execute(&self, path: &ConversionPath) -> Result<Vec<Transaction>> {
+        let mut transactions = Vec::with_capacity(path.legs.len());
+        let mut amount = path.amount_in;
+        for leg in &path.legs {
+            let transaction = match leg.side {
+                Side::Sell => self.client.market_sell(&leg.symbol, amount).await?,
+                Side::Buy => {
+                    let amount = floor_decimals(amount, leg.quote_precision);
+                    self.client
+                        .market_buy_with_quote_qty(&leg.symbol, amount)
+                        .await?
+                }
+            };
+            amount = received(leg, &transaction).unwrap_or(leg.amount_out);
+            transactions.push(transaction);
+        }
+        Ok(transactions)
+    }
+}
+
+// Market between two assets, seen from the asset spent
+struct Edge<'a> {
+    symbol: &'a str,
+    side: Side,
+    to_asset: &'a str,
+    price: f64,
+    quote_precision: u64,
+}
+
+fn find_best_path(
+    symbols: &[Symbol],
+    tickers: &[Ticker],
+    from_asset: &str,
+    to_asset: &str,
+    amount: f64,
+    fee_rate: f64,
+) -> Option<ConversionPath> {
+    let tickers: HashMap<&str, &Ticker> = tickers
+        .iter()
+        .map(|ticker| (ticker.symbol.as_str(), ticker))
+        .collect();
+    let mut edges: HashMap<&str, Vec<Edge>> = HashMap::new();
+    for symbol in symbols.iter().filter(|symbol| symbol.is_spot_tradable()) {
+        let Some(ticker) = tickers.get(symbol.symbol.as_str()) else {
+            continue;
+        };
+        if ticker.bid_price > 0.0 {
+            edges
+                .entry(symbol.base_asset.as_str())
+                .or_default()
+                .push(Edge {
+                    symbol: &symbol.symbol,
+                    side: Side::Sell,
+                    to_asset: &symbol.quote_asset,
+                    price: ticker.bid_price,
+                    quote_precision: symbol.quote_asset_precision,
+                });
+        }
+        // Spending an exact amount of the quote asset needs `quoteOrderQty`
+        if ticker.ask_price > 0.0 && symbol.quote_order_qty_market_allowed {
+            edges
+                .entry(symbol.quote_asset.as_str())
+                .or_default()
+                .push(Edge {
+                    symbol: &symbol.symbol,
+                    side: Side::Buy,
+                    to_asset: &symbol.base_asset,
+                    price: ticker.ask_price,
+                    quote_precision: symbol.quote_asset_precision,
+                });
+        }
+    }
+
+    let leg = |from: &str, edge: &Edge, amount_in: f64| {
+        let gross = match edge.side {
+            Side::Sell => amount_in * edge.price,
+            Side::Buy => amount_in / edge.price,
+        };
+        ConversionLeg {
+            symbol: edge.symbol.into(),
+            side: edge.side.clone(),
+            from_asset: from.into(),
+            to_asset: edge.to_asset.into(),
+            price: edge.price,
+            quote_precision: edge.quote_precision,
+            amount_in,
+            amount_out: gross * (1.0 - fee_rate),
+        }
+    };
+
+    let mut paths = Vec::new();
+    for first in edges.get(from_asset).into_iter().flatten() {
+        let first_leg = leg(from_asset, first, amount);
+        if first.to_asset == to_asset {
+            paths.push(vec![first_leg]);
+            continue;
+        }
+        for second in edges.get(first.to_asset).into_iter().flatten() {
+            if second.to_asset == to_asset {
+                let second_leg = leg(first.to_asset, second, first_leg.amount_out);
+                paths.push(vec![first_leg.clone(), second_leg]);
+            }
+        }
+    }
+
+    paths
+        .into_iter()
+        .map(|legs| ConversionPath {
+            amount_out: legs.last().map_or(0.0, |leg| leg.amount_out),
+            legs,
+            amount_in: amount,
+        })
+        .max_by(|a, b| a.amount_out.total_cmp(&b.amount_out))
+}
+
+// What a leg's fills brought in, net of fees paid in the received asset
+fn received(leg: &ConversionLeg, transaction: &Transaction) -> Option<f64> {
+    if transaction.fills.is_empty() {
+        return None;
+    }
+    let gross: f64 = transaction
+        .fills
+        .iter()
+        .map(|fill| match leg.side {
+            Side::Sell => fill.price * fill.qty,
+            Side::Buy => fill.qty,
+        })
+        .sum();
+    let fees: f64 = transaction
+        .fills
+        .iter()
+        .filter(|fill| fill.commission_asset == leg.to_asset)
+        .map(|fill| fill.commission)
+        .sum();
+    Some(gross - fees)
+}
+
+#[cfg(test)]
+mod test {
+    use super::find_best_path;
+    use crate::model::{Side, Symbol, Ticker};
+    use anyhow::Result;
+    use serde_json::from_str;
+
+    fn symbol(name: &str, base: &str, quote: &str) -> Result<Symbol> {
+        Ok(from_str(&format!(
+            r#"{{"symbol":"{name}","status":"TRADING","baseAsset":"{base}","baseAssetPrecision":8,"quoteAsset":"{quote}","quotePrecision":8,"quoteAssetPrecision":8,"baseCommissionPrecision":8,"quoteCommissionPrecision":8,"orderTypes":["LIMIT","MARKET"],"icebergAllowed":true,"ocoAllowed":true,"quoteOrderQtyMarketAllowed":true,"isSpotTradingAllowed":true,"isMarginTradingAllowed":false}}"#
+        ))?)
+    }
+
+    #[test]
+    fn best_path() -> Result<()> {
+        let symbols = vec![
+            symbol("ETHBTC", "ETH", "BTC")?,
+            symbol("ETHUSDT", "ETH", "USDT")?,
+            symbol("BTCUSDT", "BTC", "USDT")?,
+        ];
+        let tickers: Vec<Ticker> = from_str(
+            r#"[{"symbol":"ETHBTC","bidPrice":"0.05","bidQty":"10","askPrice":"0.0501","askQty":"10"},{"symbol":"ETHUSDT","bidPrice":"2990","bidQty":"10","askPrice":"2991","askQty":"10"},{"symbol":"BTCUSDT","bidPrice":"60000","bidQty":"1","askPrice":"60001","askQty":"1"}]"#,
+        )?;
+
+        // Direct: 1 ETH -> 2990 USDT. Through BTC: 0.05 BTC -> 3000 USDT, minus two fees.
+        let path = find_best_path(&symbols, &tickers, "ETH", "USDT", 1.0, 0.001).unwrap();
+        assert_eq!(path.legs.len(), 2);
+        assert_eq!(path.legs[0].symbol, "ETHBTC");
+        assert!((path.amount_out - 2_994.003).abs() < 1e-9);
+
+        // A higher fee makes the second hop too expensive
+        let path = find_best_path(&symbols, &tickers, "ETH", "USDT", 1.0, 0.005).unwrap();
+        assert_eq!(path.legs.len(), 1);
+
+        let path = find_best_path(&symbols, &tickers, "USDT", "ETH", 2991.0, 0.0).unwrap();
+        assert_eq!(path.legs[0].side, Side::Buy);
+        assert_eq!(path.legs[0].quote_precision, 8);
+        assert!((path.amount_out - 1.0).abs() < 1e-9);
+
+        assert!(find_best_path(&symbols, &tickers, "ETH", "DOGE", 1.0, 0.001).is_none());
+        Ok(())
+    }
+}
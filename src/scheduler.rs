@@ -0,0 +1,342 @@
+//! Request weight budget shared by all clones of a client.
+//!
+//! The exchange reports the weight used in the current minute with every response. Close to the
+//! limit, requests of a lower priority wait for the next minute so order traffic keeps the
+//! remaining budget. Every API host counts its weight in a window of its own, with its own
+//! limit.
+//!
+//! After an IP ban every request fails right away until the ban is over, as each one sent would
+//! only extend it.
+
+use crate::transport::Host;
+use chrono::Utc;
+use http::Method;
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, PoisonError,
+    },
+    time::Duration,
+};
+
+// Request weight the exchange allows per minute and IP
+pub const WEIGHT_LIMIT_1M: u32 = 6000;
+// The futures APIs allow less
+pub const FUTURES_WEIGHT_LIMIT_1M: u32 = 2400;
+
+const MINUTE_MS: u64 = 60_000;
+
+// Higher priorities may use more of the weight budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum RequestPriority {
+    // Market data, account queries and everything else, up to 80% of the budget
+    MarketData,
+    // Order cancellations, up to 95% of the budget
+    Cancel,
+    // New orders, the whole budget
+    Order,
+}
+
+impl RequestPriority {
+    // Placing anything with "order" in its path is an order action, any DELETE a cancellation
+    #[must_use]
+    pub fn of(method: &Method, path: &str) -> Self {
+        if *method == Method::DELETE {
+            Self::Cancel
+        } else if (*method == Method::POST || *method == Method::PUT)
+            && path.to_lowercase().contains("order")
+        {
+            Self::Order
+        } else {
+            Self::MarketData
+        }
+    }
+
+    const fn budget_percent(self) -> u32 {
+        match self {
+            Self::MarketData => 80,
+            Self::Cancel => 95,
+            Self::Order => 100,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct WeightWindow {
+    minute: u64,
+    used: u32,
+}
+
+#[derive(Debug)]
+pub(crate) struct Scheduler {
+    // Limit of every host but the futures ones
+    limit: u32,
+    windows: Mutex<HashMap<Host, WeightWindow>>,
+    // Millisecond timestamp the last ban ends at, 0 if there was none
+    banned_until: AtomicU64,
+}
+
+impl Scheduler {
+    pub(crate) fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            windows: Mutex::new(HashMap::new()),
+            banned_until: AtomicU64::new(0),
+        }
+    }
+
+    // Waits until a request of `priority` and `weight` to `host` fits into the budget of the
+    // current minute
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) async fn acquire(&self, host: &Host, priority: RequestPriority, weight: u32) {
+        while let Some(wait) = self.wait_time(host, priority, weight, now_ms()) {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // Without a timer to wait on, requests over budget go out right away and the exchange
+    // decides
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) async fn acquire(&self, host: &Host, priority: RequestPriority, weight: u32) {
+        if let Some(wait) = self.wait_time(host, priority, weight, now_ms()) {
+            log::debug!("{host:?} over budget for {wait:?}, sending anyway");
+        }
+    }
+
+    // Takes the `X-MBX-USED-WEIGHT-1M` header of a response from `host`
+    pub(crate) fn record(&self, host: &Host, used_weight: u32) {
+        let window = WeightWindow {
+            minute: now_ms() / MINUTE_MS,
+            used: used_weight,
+        };
+        self.windows
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(host.clone(), window);
+    }
+
+    // Takes the end of a ban reported by the exchange
+    pub(crate) fn ban(&self, until: u64) {
+        self.banned_until.fetch_max(until, Ordering::Relaxed);
+    }
+
+    // `None` unless a ban is in force
+    pub(crate) fn banned_until(&self) -> Option<u64> {
+        let until = self.banned_until.load(Ordering::Relaxed);
+        (until > now_ms()).then_some(until)
+    }
+
+    // The market data budget of `host` spread evenly over the minute, at least 1
+    pub(crate) fn weight_per_second(&self, host: &Host) -> u32 {
+        (self.limit(host) * RequestPriority::MarketData.budget_percent() / 100 / 60).max(1)
+    }
+
+    pub(crate) fn used_weight(&self, host: &Host) -> u32 {
+        self.window(host, now_ms() / MINUTE_MS)
+    }
+
+    // Weight market data may still use on `host` in the current minute, and the time until the
+    // next minute
+    pub(crate) fn budget_left(&self, host: &Host) -> (u32, Duration) {
+        let now = now_ms();
+        let minute = now / MINUTE_MS;
+        let allowed = self.limit(host) * RequestPriority::MarketData.budget_percent() / 100;
+        (
+            allowed.saturating_sub(self.window(host, minute)),
+            Duration::from_millis((minute + 1) * MINUTE_MS - now),
+        )
+    }
+
+    const fn limit(&self, host: &Host) -> u32 {
+        match host {
+            Host::Fapi | Host::Dapi => FUTURES_WEIGHT_LIMIT_1M,
+            _ => self.limit,
+        }
+    }
+
+    // Weight `host` used in `minute`
+    fn window(&self, host: &Host, minute: u64) -> u32 {
+        let windows = self.windows.lock().unwrap_or_else(PoisonError::into_inner);
+        windows
+            .get(host)
+            .filter(|window| window.minute == minute)
+            .map_or(0, |window| window.used)
+    }
+
+    // `None` if the request may go now, otherwise the time until the next minute. A request
+    // heavier than the whole budget goes alone in a fresh minute.
+    fn wait_time(
+        &self,
+        host: &Host,
+        priority: RequestPriority,
+        weight: u32,
+        now_ms: u64,
+    ) -> Option<Duration> {
+        let minute = now_ms / MINUTE_MS;
+        let allowed = self.limit(host) * priority.budget_percent() / 100;
+        if self
+            .window(host, minute)
+            .saturating_add(weight.min(allowed))
+            <= allowed
+        {
+            return None;
+        }
+        Some(Duration::from_millis((minute + 1) * MINUTE_MS - now_ms))
+    }
+}
+
+impl Default for Scheduler {
+    fn default() -> Self {
+        Self::new(WEIGHT_LIMIT_1M)
+    }
+}
+
+fn now_ms() -> u64 {
+    u64::try_from(Utc::now().timestamp_millis()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{now_ms, RequestPriority, Scheduler, WeightWindow, MINUTE_MS};
+    use crate::transport::Host;
+    use http::Method;
+    use std::{
+        collections::HashMap,
+        sync::{atomic::AtomicU64, Mutex},
+        time::Duration,
+    };
+
+    #[test]
+    fn request_priority() {
+        assert_eq!(
+            RequestPriority::of(&Method::POST, "/api/v3/order"),
+            RequestPriority::Order
+        );
+        assert_eq!(
+            RequestPriority::of(&Method::POST, "/sapi/v1/algo/spot/newOrderTwap"),
+            RequestPriority::Order
+        );
+        assert_eq!(
+            RequestPriority::of(&Method::DELETE, "/api/v3/order"),
+            RequestPriority::Cancel
+        );
+        assert_eq!(
+            RequestPriority::of(&Method::GET, "/api/v3/openOrders"),
+            RequestPriority::MarketData
+        );
+    }
+
+    #[test]
+    fn priority_lanes() {
+        let minute = 28_000_000;
+        let now = minute * MINUTE_MS + 45_000;
+        let scheduler = Scheduler {
+            limit: 1000,
+            windows: Mutex::new(HashMap::from([(
+                Host::Api,
+                WeightWindow { minute, used: 900 },
+            )])),
+            banned_until: AtomicU64::new(0),
+        };
+        let api = Host::Api;
+        assert_eq!(
+            scheduler.wait_time(&api, RequestPriority::MarketData, 1, now),
+            Some(Duration::from_secs(15))
+        );
+        assert_eq!(
+            scheduler.wait_time(&api, RequestPriority::Cancel, 1, now),
+            None
+        );
+        assert_eq!(
+            scheduler.wait_time(&api, RequestPriority::Order, 1, now),
+            None
+        );
+
+        scheduler
+            .windows
+            .lock()
+            .unwrap()
+            .get_mut(&api)
+            .unwrap()
+            .used = 960;
+        assert!(scheduler
+            .wait_time(&api, RequestPriority::Cancel, 1, now)
+            .is_some());
+        assert_eq!(
+            scheduler.wait_time(&api, RequestPriority::Order, 1, now),
+            None
+        );
+
+        // A new minute starts with a fresh budget
+        assert_eq!(
+            scheduler.wait_time(&api, RequestPriority::MarketData, 1, now + 15_000),
+            None
+        );
+    }
+
+    #[test]
+    fn request_weight() {
+        let minute = 28_000_000;
+        let now = minute * MINUTE_MS + 45_000;
+        let scheduler = Scheduler::new(1000);
+        let api = Host::Api;
+        scheduler
+            .windows
+            .lock()
+            .unwrap()
+            .insert(api.clone(), WeightWindow { minute, used: 700 });
+        // 100 of 800 left for market data
+        assert_eq!(
+            scheduler.wait_time(&api, RequestPriority::MarketData, 100, now),
+            None
+        );
+        assert_eq!(
+            scheduler.wait_time(&api, RequestPriority::MarketData, 250, now),
+            Some(Duration::from_secs(15))
+        );
+        // Heavier than the budget, waits for an unused minute
+        assert!(scheduler
+            .wait_time(&api, RequestPriority::MarketData, 5000, now)
+            .is_some());
+        assert_eq!(
+            scheduler.wait_time(&api, RequestPriority::MarketData, 5000, now + 15_000),
+            None
+        );
+    }
+
+    #[test]
+    fn windows_by_host() {
+        let scheduler = Scheduler::default();
+        scheduler.record(&Host::Fapi, 2000);
+        assert_eq!(scheduler.used_weight(&Host::Fapi), 2000);
+        assert_eq!(scheduler.used_weight(&Host::Api), 0);
+
+        // 2000 is past 80% of the futures limit, far from that of the spot API
+        let now = now_ms();
+        assert!(scheduler
+            .wait_time(&Host::Fapi, RequestPriority::MarketData, 1, now)
+            .is_some());
+        assert_eq!(
+            scheduler.wait_time(&Host::Api, RequestPriority::MarketData, 1, now),
+            None
+        );
+        assert_eq!(scheduler.weight_per_second(&Host::Api), 80);
+        assert_eq!(scheduler.weight_per_second(&Host::Fapi), 32);
+        assert_eq!(scheduler.budget_left(&Host::Fapi).0, 0);
+        assert_eq!(scheduler.budget_left(&Host::Api).0, 4800);
+    }
+
+    #[test]
+    fn ip_ban() {
+        let scheduler = Scheduler::default();
+        assert_eq!(scheduler.banned_until(), None);
+        let until = now_ms() + 60_000;
+        scheduler.ban(until);
+        scheduler.ban(until - 1000);
+        assert_eq!(scheduler.banned_until(), Some(until));
+        scheduler.ban(1);
+        assert_eq!(scheduler.banned_until(), Some(until));
+    }
+}
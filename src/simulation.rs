@@ -0,0 +1,585 @@
+//! Paper trading: a `SimulatedBinance` with the order and account methods of `Binance`, filling
+//! orders against market data fed from live streams or replayed history.
+//!
+//! Nothing is sent to the exchange. Feed `apply` the `@bookTicker`, `@trade` or `@aggTrade`
+//! messages of the traded symbols: MARKET orders fill at the best bid or ask, LIMIT orders
+//! rest until the market trades through their price. Every order waits the configured latency
+//! first and pays the fee rate in the asset it receives.
+//!
+//! Code written against the `Trading` and `MarketData` traits runs on either client unchanged.
+
+use crate::{
+    client::{MarketData, Trading},
+    error::Error,
+    model::{
+        timestamp, websocket::BinanceWebsocketMessage, Balance, Fill, Order, OrderCanceled,
+        OrderStatus, Side, SymbolPrice, Ticker, Timestamp, Transaction,
+    },
+};
+use anyhow::Result;
+use chrono::Utc;
+use std::{
+    collections::HashMap,
+    convert::TryFrom,
+    sync::{Arc, Mutex, PoisonError},
+    time::Duration,
+};
+
+const INSUFFICIENT_BALANCE: i64 = -2010;
+const ORDER_DOES_NOT_EXIST: i64 = -2013;
+
+#[derive(Debug, Clone)]
+pub struct SimulationConfig {
+    // Commission per fill, 0.001 is the spot default of 0.1%
+    pub fee_rate: f64,
+    // Delay before an order reaches the simulated matching engine
+    pub latency: Duration,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            fee_rate: 0.001,
+            latency: Duration::ZERO,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Quote {
+    bid: f64,
+    ask: f64,
+    last: f64,
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct AssetBalance {
+    free: f64,
+    locked: f64,
+}
+
+#[derive(Debug, Clone)]
+struct SimulatedOrder {
+    symbol: String,
+    order_id: u64,
+    client_order_id: String,
+    side: Side,
+    price: f64,
+    qty: f64,
+    executed_qty: f64,
+    status: OrderStatus,
+    time: Timestamp,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    // Base and quote asset of every symbol
+    symbols: HashMap<String, (String, String)>,
+    quotes: HashMap<String, Quote>,
+    balances: HashMap<String, AssetBalance>,
+    orders: HashMap<u64, SimulatedOrder>,
+    next_order_id: u64,
+    next_trade_id: u64,
+}
+
+// Clones share the simulated account, like clones of `Binance` share the real one
+#[derive(Debug, Clone, Default)]
+pub struct SimulatedBinance {
+    config: SimulationConfig,
+    state: Arc<Mutex<State>>,
+}
+
+impl SimulatedBinance {
+    #[must_use]
+    pub fn new(config: SimulationConfig) -> Self {
+        Self {
+            config,
+            state: Arc::default(),
+        }
+    }
+
+    // Make `symbol` tradable, e.g. `add_symbol("BTCUSDT", "BTC", "USDT")`
+    pub fn add_symbol(&self, symbol: &str, base_asset: &str, quote_asset: &str) {
+        self.lock().symbols.insert(
+            symbol.to_uppercase(),
+            (base_asset.to_uppercase(), quote_asset.to_uppercase()),
+        );
+    }
+
+    pub fn deposit(&self, asset: &str, amount: f64) {
+        self.lock()
+            .balances
+            .entry(asset.to_uppercase())
+            .or_default()
+            .free += amount;
+    }
+
+    // Takes book tickers, trades and aggregate trades, fills the LIMIT orders the market traded
+    // through and returns them
+    #[must_use]
+    pub fn apply(&self, message: &BinanceWebsocketMessage) -> Vec<Order> {
+        let (symbol, bid, ask, last) = match message {
+            BinanceWebsocketMessage::BookTicker(ticker) => (
+                &ticker.symbol,
+                ticker.best_bid,
+                ticker.best_ask,
+                f64::midpoint(ticker.best_bid, ticker.best_ask),
+            ),
+            BinanceWebsocketMessage::Trade(trade) => {
+                (&trade.symbol, trade.price, trade.price, trade.price)
+            }
+            BinanceWebsocketMessage::AggregateTrade(trade) => {
+                (&trade.symbol, trade.price, trade.price, trade.price)
+            }
+            _ => return Vec::new(),
+        };
+        let mut state = self.lock();
+        state
+            .quotes
+            .insert(symbol.clone(), Quote { bid, ask, last });
+        let fee_rate = self.config.fee_rate;
+        // Collected first, filling needs `orders` mutably
+        #[allow(clippy::needless_collect)]
+        let crossed: Vec<u64> = state
+            .orders
+            .values()
+            .filter(|order| order.symbol == *symbol && order.status == OrderStatus::New)
+            .filter(|order| match order.side {
+                Side::Buy => ask <= order.price,
+                Side::Sell => bid >= order.price,
+            })
+            .map(|order| order.order_id)
+            .collect();
+        let filled = crossed
+            .into_iter()
+            .filter_map(|order_id| {
+                state
+                    .fill_resting(order_id, fee_rate)
+                    .map(SimulatedOrder::to_order)
+            })
+            .collect();
+        drop(state);
+        filled
+    }
+
+    // Place a LIMIT order - BUY
+    pub async fn limit_buy(&self, symbol: &str, qty: f64, price: f64) -> Result<Transaction> {
+        self.place_order(symbol, Side::Buy, qty, Some(price)).await
+    }
+
+    // Place a LIMIT order - SELL
+    pub async fn limit_sell(&self, symbol: &str, qty: f64, price: f64) -> Result<Transaction> {
+        self.place_order(symbol, Side::Sell, qty, Some(price)).await
+    }
+
+    // Place a MARKET order - BUY
+    pub async fn market_buy(&self, symbol: &str, qty: f64) -> Result<Transaction> {
+        self.place_order(symbol, Side::Buy, qty, None).await
+    }
+
+    // Place a MARKET order - SELL
+    pub async fn market_sell(&self, symbol: &str, qty: f64) -> Result<Transaction> {
+        self.place_order(symbol, Side::Sell, qty, None).await
+    }
+
+    pub async fn cancel_order(&self, symbol: &str, order_id: u64) -> Result<OrderCanceled> {
+        self.delay().await;
+        let mut state = self.lock();
+        let order = state
+            .orders
+            .get_mut(&order_id)
+            .filter(|order| order.symbol == symbol.to_uppercase())
+            .filter(|order| order.status == OrderStatus::New)
+            .ok_or_else(unknown_order)?;
+        order.status = OrderStatus::Canceled;
+        let canceled = OrderCanceled {
+            symbol: order.symbol.clone(),
+            orig_client_order_id: order.client_order_id.clone(),
+            order_id,
+            client_order_id: order.client_order_id.clone(),
+        };
+        state.unlock_remaining(order_id);
+        drop(state);
+        Ok(canceled)
+    }
+
+    pub async fn get_open_orders(&self, symbol: &str) -> Result<Vec<Order>> {
+        self.delay().await;
+        let symbol = symbol.to_uppercase();
+        Ok(self
+            .lock()
+            .orders
+            .values()
+            .filter(|order| order.symbol == symbol && order.status == OrderStatus::New)
+            .map(SimulatedOrder::to_order)
+            .collect())
+    }
+
+    pub async fn order_status(&self, symbol: &str, order_id: u64) -> Result<Order> {
+        self.delay().await;
+        let symbol = symbol.to_uppercase();
+        self.lock()
+            .orders
+            .get(&order_id)
+            .filter(|order| order.symbol == symbol)
+            .map(SimulatedOrder::to_order)
+            .ok_or_else(unknown_order)
+    }
+
+    pub async fn get_balance(&self, asset: &str) -> Result<Balance> {
+        self.delay().await;
+        let asset = asset.to_uppercase();
+        let balance = self
+            .lock()
+            .balances
+            .get(&asset)
+            .copied()
+            .unwrap_or_default();
+        Ok(Balance {
+            asset,
+            free: balance.free.to_string(),
+            locked: balance.locked.to_string(),
+        })
+    }
+
+    // Last price fed for `symbol`
+    pub async fn get_price(&self, symbol: &str) -> Result<SymbolPrice> {
+        self.delay().await;
+        let symbol = symbol.to_uppercase();
+        let quote = self.lock().quotes.get(&symbol).copied();
+        let quote = quote.ok_or_else(|| Error::NoMarketData(symbol.clone()))?;
+        Ok(SymbolPrice {
+            symbol,
+            price: quote.last,
+        })
+    }
+
+    // Best bid and ask fed for `symbol`, the quantities are not simulated
+    pub async fn get_book_ticker(&self, symbol: &str) -> Result<Ticker> {
+        self.delay().await;
+        let symbol = symbol.to_uppercase();
+        let quote = self.lock().quotes.get(&symbol).copied();
+        let quote = quote.ok_or_else(|| Error::NoMarketData(symbol.clone()))?;
+        Ok(Ticker {
+            symbol,
+            bid_price: quote.bid,
+            bid_qty: 0.0,
+            ask_price: quote.ask,
+            ask_qty: 0.0,
+        })
+    }
+
+    async fn place_order(
+        &self,
+        symbol: &str,
+        side: Side,
+        qty: f64,
+        price: Option<f64>,
+    ) -> Result<Transaction> {
+        self.delay().await;
+        let symbol = symbol.to_uppercase();
+        let time = timestamp::from_millis(now_ms())?;
+        let mut state = self.lock();
+        let (base, quote_asset) = state
+            .symbols
+            .get(&symbol)
+            .cloned()
+            .ok_or_else(|| Error::InvalidSymbol(symbol.clone()))?;
+        let quote = state.quotes.get(&symbol).copied();
+
+        // Marketable orders fill right away at the best price, LIMIT orders at theirs or better
+        let fill_price = match (&side, price, quote) {
+            (Side::Buy, Some(limit), Some(quote)) if quote.ask <= limit => Some(quote.ask),
+            (Side::Sell, Some(limit), Some(quote)) if quote.bid >= limit => Some(quote.bid),
+            (_, Some(_), _) => None,
+            (Side::Buy, None, Some(quote)) => Some(quote.ask),
+            (Side::Sell, None, Some(quote)) => Some(quote.bid),
+            (_, None, None) => return Err(Error::NoMarketData(symbol).into()),
+        };
+        let (asset, needed) = match side {
+            Side::Buy => (quote_asset, qty * price.or(fill_price).unwrap_or_default()),
+            Side::Sell => (base, qty),
+        };
+        let balance = state.balances.entry(asset).or_default();
+        if balance.free < needed {
+            return Err(Error::BinanceError {
+                code: INSUFFICIENT_BALANCE,
+                msg: "Account has insufficient balance for requested action.".into(),
+            }
+            .into());
+        }
+        balance.free -= needed;
+        balance.locked += needed;
+
+        state.next_order_id += 1;
+        let order_id = state.next_order_id;
+        state.orders.insert(
+            order_id,
+            SimulatedOrder {
+                symbol: symbol.clone(),
+                order_id,
+                client_order_id: format!("simulated-{order_id}"),
+                side,
+                price: price.unwrap_or_default(),
+                qty,
+                executed_qty: 0.0,
+                status: OrderStatus::New,
+                time,
+            },
+        );
+        let fills = fill_price
+            .and_then(|fill_price| state.fill(order_id, fill_price, self.config.fee_rate))
+            .into_iter()
+            .collect();
+        drop(state);
+        Ok(Transaction {
+            symbol,
+            order_id,
+            client_order_id: format!("simulated-{order_id}"),
+            transact_time: time,
+            fills,
+            extra: HashMap::new(),
+        })
+    }
+
+    async fn delay(&self) {
+        tokio::time::sleep(self.config.latency).await;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl MarketData for SimulatedBinance {
+    async fn get_price(&self, symbol: &str) -> Result<SymbolPrice> {
+        Self::get_price(self, symbol).await
+    }
+
+    async fn get_book_ticker(&self, symbol: &str) -> Result<Ticker> {
+        Self::get_book_ticker(self, symbol).await
+    }
+}
+
+impl Trading for SimulatedBinance {
+    async fn limit_buy(&self, symbol: &str, qty: f64, price: f64) -> Result<Transaction> {
+        Self::limit_buy(self, symbol, qty, price).await
+    }
+
+    async fn limit_sell(&self, symbol: &str, qty: f64, price: f64) -> Result<Transaction> {
+        Self::limit_sell(self, symbol, qty, price).await
+    }
+
+    async fn market_buy(&self, symbol: &str, qty: f64) -> Result<Transaction> {
+        Self::market_buy(self, symbol, qty).await
+    }
+
+    async fn market_sell(&self, symbol: &str, qty: f64) -> Result<Transaction> {
+        Self::market_sell(self, symbol, qty).await
+    }
+
+    async fn cancel_order(&self, symbol: &str, order_id: u64) -> Result<OrderCanceled> {
+        Self::cancel_order(self, symbol, order_id).await
+    }
+
+    async fn order_status(&self, symbol: &str, order_id: u64) -> Result<Order> {
+        Self::order_status(self, symbol, order_id).await
+    }
+
+    async fn get_open_orders(&self, symbol: &str) -> Result<Vec<Order>> {
+        Self::get_open_orders(self, symbol).await
+    }
+
+    async fn get_balance(&self, asset: &str) -> Result<Balance> {
+        Self::get_balance(self, asset).await
+    }
+}
+
+impl State {
+    fn fill_resting(&mut self, order_id: u64, fee_rate: f64) -> Option<&SimulatedOrder> {
+        let price = self.orders.get(&order_id)?.price;
+        self.fill(order_id, price, fee_rate)?;
+        self.orders.get(&order_id)
+    }
+
+    // Fills the whole order at `price`, settles the balances and charges the fee in the asset
+    // received
+    fn fill(&mut self, order_id: u64, price: f64, fee_rate: f64) -> Option<Fill> {
+        let order = self.orders.get_mut(&order_id)?;
+        let (base, quote) = self.symbols.get(&order.symbol)?.clone();
+        order.executed_qty = order.qty;
+        order.status = OrderStatus::Filled;
+        let (side, qty, limit) = (order.side.clone(), order.qty, order.price);
+
+        let (paid, locked, paid_amount, received, received_amount) = match side {
+            // A LIMIT buy locked `qty * limit`, filling below it releases the difference
+            Side::Buy => {
+                let locked = if limit > 0.0 {
+                    qty * limit
+                } else {
+                    qty * price
+                };
+                (quote, locked, qty * price, base, qty)
+            }
+            Side::Sell => (base, qty, qty, quote, qty * price),
+        };
+        let paid_balance = self.balances.entry(paid).or_default();
+        paid_balance.locked -= locked;
+        paid_balance.free += locked - paid_amount;
+        let commission = received_amount * fee_rate;
+        self.balances.entry(received.clone()).or_default().free += received_amount - commission;
+
+        self.next_trade_id += 1;
+        Some(Fill {
+            price,
+            qty,
+            commission,
+            commission_asset: received,
+            trade_id: self.next_trade_id,
+        })
+    }
+
+    fn unlock_remaining(&mut self, order_id: u64) {
+        let Some(order) = self.orders.get(&order_id) else {
+            return;
+        };
+        let Some((base, quote)) = self.symbols.get(&order.symbol) else {
+            return;
+        };
+        let remaining = order.qty - order.executed_qty;
+        let (asset, amount) = match order.side {
+            Side::Buy => (quote.clone(), remaining * order.price),
+            Side::Sell => (base.clone(), remaining),
+        };
+        let balance = self.balances.entry(asset).or_default();
+        balance.locked -= amount;
+        balance.free += amount;
+    }
+}
+
+impl SimulatedOrder {
+    fn to_order(&self) -> Order {
+        let status = match self.status {
+            OrderStatus::Filled => "FILLED",
+            OrderStatus::Canceled => "CANCELED",
+            _ => "NEW",
+        };
+        let type_name = if self.price > 0.0 { "LIMIT" } else { "MARKET" };
+        Order {
+            symbol: self.symbol.clone(),
+            order_id: self.order_id,
+            client_order_id: self.client_order_id.clone(),
+            price: self.price,
+            orig_qty: self.qty.to_string(),
+            executed_qty: self.executed_qty.to_string(),
+            status: status.into(),
+            time_in_force: "GTC".into(),
+            type_name: type_name.into(),
+            side: match self.side {
+                Side::Buy => "BUY".into(),
+                Side::Sell => "SELL".into(),
+            },
+            stop_price: 0.0,
+            iceberg_qty: "0".into(),
+            time: self.time,
+            extra: HashMap::new(),
+        }
+    }
+}
+
+fn unknown_order() -> anyhow::Error {
+    Error::BinanceError {
+        code: ORDER_DOES_NOT_EXIST,
+        msg: "Order does not exist.".into(),
+    }
+    .into()
+}
+
+fn now_ms() -> u64 {
+    u64::try_from(Utc::now().timestamp_millis()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SimulatedBinance, SimulationConfig};
+    use crate::{
+        client::{MarketData, Trading},
+        model::websocket::parse_event,
+    };
+    use anyhow::Result;
+
+    // A strategy that only knows the traits
+    async fn buy_below_ask<C: MarketData + Trading + Sync>(client: &C) -> Result<u64> {
+        let ticker = client.get_book_ticker("BTCUSDT").await?;
+        let order = client.limit_buy("BTCUSDT", 0.1, ticker.bid_price).await?;
+        Ok(order.order_id)
+    }
+
+    fn simulation() -> Result<SimulatedBinance> {
+        let simulation = SimulatedBinance::new(SimulationConfig::default());
+        simulation.add_symbol("BTCUSDT", "BTC", "USDT");
+        simulation.deposit("USDT", 10_000.0);
+        let ticker = parse_event(
+            r#"{"u":400900217,"s":"BTCUSDT","b":"25000.00","B":"1.0","a":"25010.00","A":"1.0"}"#,
+        )?;
+        assert!(simulation.apply(&ticker).is_empty());
+        Ok(simulation)
+    }
+
+    #[tokio::test]
+    async fn market_order_fills_at_best_price() -> Result<()> {
+        let simulation = simulation()?;
+        let transaction = simulation.market_buy("btcusdt", 0.2).await?;
+        assert_eq!(transaction.fills.len(), 1);
+        assert!((transaction.fills[0].price - 25_010.0).abs() < f64::EPSILON);
+
+        let usdt = simulation.get_balance("USDT").await?;
+        // 0.2 at 25010
+        assert!((usdt.free.parse::<f64>()? - 4_998.0).abs() < 1e-9);
+        let btc = simulation.get_balance("BTC").await?;
+        // Less the 0.1% fee
+        assert!((btc.free.parse::<f64>()? - 0.1998).abs() < 1e-12);
+
+        assert!(simulation.market_buy("BTCUSDT", 1.0).await.is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn runs_trait_code() -> Result<()> {
+        let simulation = simulation()?;
+        let order_id = buy_below_ask(&simulation).await?;
+        let order = Trading::order_status(&simulation, "BTCUSDT", order_id).await?;
+        assert_eq!(order.status, "NEW");
+        assert!((order.price - 25_000.0).abs() < f64::EPSILON);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn limit_order_rests_until_crossed() -> Result<()> {
+        let simulation = simulation()?;
+        let transaction = simulation.limit_buy("BTCUSDT", 0.1, 24_000.0).await?;
+        assert!(transaction.fills.is_empty());
+        assert_eq!(simulation.get_open_orders("BTCUSDT").await?.len(), 1);
+        let usdt = simulation.get_balance("USDT").await?;
+        assert!((usdt.locked.parse::<f64>()? - 2400.0).abs() < f64::EPSILON);
+
+        let filled = simulation.apply(&parse_event(
+            r#"{"e":"trade","E":1672515782136,"s":"BTCUSDT","t":12345,"p":"23990.00","q":"1","T":1672515782136,"m":true,"M":true}"#,
+        )?);
+        assert_eq!(filled.len(), 1);
+        assert_eq!(filled[0].status, "FILLED");
+        assert!(simulation.get_open_orders("BTCUSDT").await?.is_empty());
+        let usdt = simulation.get_balance("USDT").await?;
+        assert!(usdt.locked.parse::<f64>()?.abs() < f64::EPSILON);
+
+        let order = simulation.limit_sell("BTCUSDT", 0.05, 30_000.0).await?;
+        simulation.cancel_order("BTCUSDT", order.order_id).await?;
+        let btc = simulation.get_balance("BTC").await?;
+        assert!(btc.locked.parse::<f64>()?.abs() < f64::EPSILON);
+        assert!(simulation
+            .cancel_order("BTCUSDT", order.order_id)
+            .await
+            .is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,266 @@
+//! Scripted websocket streams for testing code that consumes `BinanceWebsocket`.
+//!
+//! A `TestStream` yields hand-crafted messages in the order they were scripted, each at a
+//! virtual timestamp, as the same `Stream<Item = Result<BinanceWebsocketMessage>>` the live
+//! stream is. Components like `OrderTracker` or a strategy can so be driven through depth gaps,
+//! partial fills, parse errors and reconnects without a connection.
+//!
+//! The virtual clock starts at the first item and only moves as items are yielded,
+//! `TestStream::clock` hands it to the code under test. A `paced` stream in addition sleeps the
+//! gaps between items on the tokio clock, which a test with a paused runtime skips through
+//! instantly.
+
+use crate::{
+    client::websocket::parse_message,
+    model::websocket::{parse_event, BinanceWebsocketMessage, Subscription},
+};
+use anyhow::{anyhow, Result};
+use futures::{FutureExt, Stream};
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    task::{Context, Poll},
+    time::Duration,
+};
+use tokio::time::{sleep, Sleep};
+use tokio_tungstenite::tungstenite::Message;
+
+// Virtual time of a `TestStream`, the timestamp of the item yielded last
+#[derive(Debug, Clone, Default)]
+pub struct TestClock(Arc<AtomicU64>);
+
+impl TestClock {
+    // Milliseconds, like `signing::timestamp`
+    #[must_use]
+    pub fn now(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, now: u64) {
+        self.0.store(now, Ordering::Relaxed);
+    }
+}
+
+#[derive(Debug)]
+enum Scripted {
+    Message(Box<BinanceWebsocketMessage>),
+    // Parsed when yielded, so malformed frames fail like on the live stream
+    Frame(Subscription, String),
+    Event(String),
+    Error(String),
+}
+
+#[derive(Debug, Default)]
+pub struct TestStreamBuilder {
+    items: Vec<(u64, Scripted)>,
+    now: u64,
+    paced: bool,
+}
+
+impl TestStreamBuilder {
+    // Virtual time of the following items, in milliseconds. Moving backwards is allowed to
+    // script out of order delivery.
+    #[must_use]
+    pub const fn at(mut self, timestamp: u64) -> Self {
+        self.now = timestamp;
+        self
+    }
+
+    // Advance the virtual time of the following items
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub const fn after(mut self, delay: Duration) -> Self {
+        self.now += delay.as_millis() as u64;
+        self
+    }
+
+    #[must_use]
+    pub fn message(self, message: BinanceWebsocketMessage) -> Self {
+        self.push(Scripted::Message(Box::new(message)))
+    }
+
+    // Raw text of a frame of `subscription`, e.g. copied from the API docs
+    #[must_use]
+    pub fn frame(self, subscription: &Subscription, text: &str) -> Self {
+        self.push(Scripted::Frame(subscription.clone(), text.into()))
+    }
+
+    // Raw user data event, as received on a user data stream or the websocket API
+    #[must_use]
+    pub fn event(self, text: &str) -> Self {
+        self.push(Scripted::Event(text.into()))
+    }
+
+    // `BinanceWebsocketMessage::StaleStream`, as the stale timeout reports it
+    #[must_use]
+    pub fn stale(self, subscription: &Subscription) -> Self {
+        self.message(BinanceWebsocketMessage::StaleStream(subscription.clone()))
+    }
+
+    // The error the live stream yields when a connection is closed. Script a reconnect by
+    // following it with the messages of the new connection, e.g. a fresh depth snapshot.
+    #[must_use]
+    pub fn disconnect(self) -> Self {
+        self.error("Socket closed")
+    }
+
+    #[must_use]
+    pub fn error(self, error: &str) -> Self {
+        self.push(Scripted::Error(error.into()))
+    }
+
+    // Sleep the virtual gap before each item on the tokio clock instead of yielding at once
+    #[must_use]
+    pub const fn paced(mut self) -> Self {
+        self.paced = true;
+        self
+    }
+
+    #[must_use]
+    pub fn build(self) -> TestStream {
+        // The first item is due right away
+        let clock = TestClock::default();
+        clock.set(
+            self.items
+                .first()
+                .map_or(self.now, |(timestamp, _)| *timestamp),
+        );
+        TestStream {
+            items: self.items.into(),
+            clock,
+            paced: self.paced,
+            delay: None,
+        }
+    }
+
+    fn push(mut self, scripted: Scripted) -> Self {
+        self.items.push((self.now, scripted));
+        self
+    }
+}
+
+// Yields the scripted items, then ends
+#[derive(Debug)]
+pub struct TestStream {
+    items: VecDeque<(u64, Scripted)>,
+    clock: TestClock,
+    paced: bool,
+    delay: Option<Pin<Box<Sleep>>>,
+}
+
+impl TestStream {
+    #[must_use]
+    pub fn builder() -> TestStreamBuilder {
+        TestStreamBuilder::default()
+    }
+
+    #[must_use]
+    pub fn clock(&self) -> TestClock {
+        self.clock.clone()
+    }
+
+    // Items not yielded yet
+    #[must_use]
+    pub fn remaining(&self) -> usize {
+        self.items.len()
+    }
+}
+
+impl Stream for TestStream {
+    type Item = Result<BinanceWebsocketMessage>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let Some(&(timestamp, _)) = this.items.front() else {
+            return Poll::Ready(None);
+        };
+
+        if this.paced {
+            let gap = timestamp.saturating_sub(this.clock.now());
+            let delay = this
+                .delay
+                .get_or_insert_with(|| Box::pin(sleep(Duration::from_millis(gap))));
+            if delay.poll_unpin(cx).is_pending() {
+                return Poll::Pending;
+            }
+            this.delay = None;
+        }
+
+        let (timestamp, scripted) = this.items.pop_front().unwrap();
+        this.clock.set(timestamp);
+        let item = match scripted {
+            Scripted::Message(message) => Ok(*message),
+            Scripted::Frame(sub, text) => parse_message(&sub, Message::Text(text)),
+            Scripted::Event(text) => parse_event(&text),
+            Scripted::Error(error) => Err(anyhow!(error)),
+        };
+        Poll::Ready(Some(item))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::TestStream;
+    use crate::{
+        model::{
+            websocket::{BinanceWebsocketMessage, Subscription},
+            OrderStatus,
+        },
+        tracker::OrderTracker,
+    };
+    use futures::StreamExt;
+    use std::time::{Duration, Instant};
+
+    const PARTIAL: &str = r#"{"e":"executionReport","E":1718100001001,"s":"BTCUSDT","c":"web_5a3b0c1f","S":"BUY","o":"LIMIT","f":"GTC","q":"0.01000000","p":"67000.00000000","P":"0.00000000","F":"0.00000000","g":-1,"C":"","x":"TRADE","X":"PARTIALLY_FILLED","r":"NONE","i":28457312211,"l":"0.00400000","z":"0.00400000","L":"67000.00000000","n":"0","N":null,"T":1718100001000,"t":3634215000,"I":60839122041,"w":false,"m":true,"M":true,"O":1718100000000,"Z":"268.00000000"}"#;
+
+    #[tokio::test]
+    async fn scripted_stream() {
+        let trades = Subscription::Trade("BTCUSDT".into());
+        let mut stream = TestStream::builder()
+            .at(1_718_100_001_001)
+            .event(PARTIAL)
+            .after(Duration::from_secs(5))
+            .stale(&trades)
+            .disconnect()
+            .after(Duration::from_millis(250))
+            .frame(&trades, "{}")
+            .build();
+        let clock = stream.clock();
+        let mut tracker = OrderTracker::new();
+
+        let change = tracker
+            .apply(&stream.next().await.unwrap().unwrap())
+            .unwrap();
+        assert_eq!(change.order.status, OrderStatus::PartiallyFilled);
+        assert_eq!(clock.now(), 1_718_100_001_001);
+
+        assert!(matches!(
+            stream.next().await.unwrap().unwrap(),
+            BinanceWebsocketMessage::StaleStream(..)
+        ));
+        assert_eq!(clock.now(), 1_718_100_006_001);
+        assert!(stream.next().await.unwrap().is_err());
+        assert!(stream.next().await.unwrap().is_err());
+        assert_eq!(clock.now(), 1_718_100_006_251);
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn paced_stream() {
+        let mut stream = TestStream::builder()
+            .message(BinanceWebsocketMessage::Ping)
+            .after(Duration::from_millis(20))
+            .message(BinanceWebsocketMessage::Pong)
+            .paced()
+            .build();
+        let start = Instant::now();
+        stream.next().await.unwrap().unwrap();
+        stream.next().await.unwrap().unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(20));
+        assert_eq!(stream.remaining(), 0);
+    }
+}
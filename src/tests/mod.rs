@@ -4,11 +4,7 @@ pub mod test {
     use anyhow::Result;
 
     pub fn setup() -> Result<Binance> {
-        dotenv::dotenv().ok();
         let _ = env_logger::builder().is_test(true).try_init();
-        Ok(Binance::with_credential(
-            &std::env::var("BINANCE_KEY")?,
-            &std::env::var("BINANCE_SECRET")?,
-        ))
+        Binance::from_env()
     }
 }
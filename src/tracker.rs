@@ -0,0 +1,394 @@
+//! Local order and balance state kept in sync with the user data stream.
+//!
+//! Feed the trackers every message of the user data stream, and the `OrderTracker` the orders
+//! placed through the client. After a reconnect, `OrderTracker::reconcile` catches up on what
+//! the stream missed in between, a `BalanceTracker` is simply seeded again.
+
+use crate::{
+    client::Binance,
+    model::{
+        timestamp,
+        websocket::{AccountUpdate, BalanceUpdate, BinanceWebsocketMessage, UserOrderUpdate},
+        AccountInformation, Order, OrderStatus, Transaction,
+    },
+};
+use anyhow::Result;
+use futures::{
+    channel::mpsc::{unbounded, UnboundedReceiver, UnboundedSender},
+    Stream,
+};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrackedOrder {
+    pub symbol: String,
+    pub order_id: u64,
+    pub client_order_id: String,
+    pub status: OrderStatus,
+    pub price: f64,
+    pub orig_qty: f64,
+    pub executed_qty: f64,
+    pub update_time: u64,
+}
+
+impl TrackedOrder {
+    #[must_use]
+    pub const fn is_open(&self) -> bool {
+        matches!(
+            self.status,
+            OrderStatus::New
+                | OrderStatus::PendingNew
+                | OrderStatus::PartiallyFilled
+                | OrderStatus::PendingCancel
+        )
+    }
+}
+
+// A tracked order after a status or fill change, with its status before the change
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderChange {
+    pub order: TrackedOrder,
+    pub previous_status: Option<OrderStatus>,
+}
+
+#[derive(Debug, Default)]
+pub struct OrderTracker {
+    orders: HashMap<u64, TrackedOrder>,
+    subscribers: Vec<UnboundedSender<OrderChange>>,
+}
+
+impl OrderTracker {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Start tracking an order right after it was placed
+    pub fn track(&mut self, transaction: &Transaction) -> Option<OrderChange> {
+        let extra = |key: &str| transaction.extra.get(key);
+        let order = TrackedOrder {
+            symbol: transaction.symbol.clone(),
+            order_id: transaction.order_id,
+            client_order_id: transaction.client_order_id.clone(),
+            // ACK responses carry no status, the order is accepted at this point
+            status: extra("status")
+                .and_then(parse_status)
+                .unwrap_or(OrderStatus::New),
+            price: extra("price").map_or(0.0, parse_float),
+            orig_qty: extra("origQty").map_or(0.0, parse_float),
+            executed_qty: extra("executedQty").map_or(0.0, parse_float),
+            update_time: timestamp::to_millis(&transaction.transact_time),
+        };
+        self.update(order)
+    }
+
+    // Take over an order as returned by the REST API, e.g. from `get_open_orders`
+    pub fn track_order(&mut self, order: &Order) -> Option<OrderChange> {
+        let update_time = order
+            .extra
+            .get("updateTime")
+            .and_then(Value::as_u64)
+            .unwrap_or_else(|| timestamp::to_millis(&order.time));
+        let order = TrackedOrder {
+            symbol: order.symbol.clone(),
+            order_id: order.order_id,
+            client_order_id: order.client_order_id.clone(),
+            status: parse_status(&Value::from(order.status.as_str())).unwrap_or(OrderStatus::New),
+            price: order.price,
+            orig_qty: order.orig_qty.parse().unwrap_or_default(),
+            executed_qty: order.executed_qty.parse().unwrap_or_default(),
+            update_time,
+        };
+        self.update(order)
+    }
+
+    // Applies an `executionReport` of the user data stream, other messages are ignored
+    pub fn apply(&mut self, message: &BinanceWebsocketMessage) -> Option<OrderChange> {
+        match message {
+            BinanceWebsocketMessage::UserOrderUpdate(update) => self.apply_update(update),
+            _ => None,
+        }
+    }
+
+    pub fn apply_update(&mut self, update: &UserOrderUpdate) -> Option<OrderChange> {
+        // Cancellations report the canceled order's id in `C`
+        let client_order_id = match update.c_ignore.as_deref() {
+            Some(id) if !id.is_empty() => id.to_string(),
+            _ => update.new_client_order_id.clone(),
+        };
+        self.update(TrackedOrder {
+            symbol: update.symbol.clone(),
+            order_id: update.order_id,
+            client_order_id,
+            status: update.order_status,
+            price: update.price,
+            orig_qty: update.qty,
+            executed_qty: update.accumulated_qty_filled_trades,
+            update_time: update.trade_order_time,
+        })
+    }
+
+    // Catches up with the exchange after a reconnect of the user data stream. Open orders
+    // unknown to the tracker are added, tracked orders which are no longer open are queried
+    // for their final state.
+    pub async fn reconcile(&mut self, client: &Binance, symbol: &str) -> Result<Vec<OrderChange>> {
+        let symbol = symbol.to_uppercase();
+        let open_orders = client.get_open_orders(&symbol).await?;
+
+        let mut changes = Vec::new();
+        for order in &open_orders {
+            changes.extend(self.track_order(order));
+        }
+
+        let closed: Vec<u64> = self
+            .orders
+            .values()
+            .filter(|o| o.symbol == symbol && o.is_open())
+            .filter(|o| !open_orders.iter().any(|open| open.order_id == o.order_id))
+            .map(|o| o.order_id)
+            .collect();
+        for order_id in closed {
+            let order = client.order_status(&symbol, order_id).await?;
+            changes.extend(self.track_order(&order));
+        }
+        Ok(changes)
+    }
+
+    fn update(&mut self, order: TrackedOrder) -> Option<OrderChange> {
+        let previous = self.orders.get(&order.order_id);
+        // Events may arrive out of order, never go back to an older state
+        if previous.is_some_and(|p| p.update_time > order.update_time) {
+            return None;
+        }
+        if previous == Some(&order) {
+            return None;
+        }
+        let change = OrderChange {
+            previous_status: previous.map(|p| p.status),
+            order: order.clone(),
+        };
+        self.orders.insert(order.order_id, order);
+        self.subscribers
+            .retain(|subscriber| subscriber.unbounded_send(change.clone()).is_ok());
+        Some(change)
+    }
+
+    #[must_use]
+    pub fn get(&self, order_id: u64) -> Option<&TrackedOrder> {
+        self.orders.get(&order_id)
+    }
+
+    #[must_use]
+    pub fn by_client_order_id(&self, client_order_id: &str) -> Option<&TrackedOrder> {
+        self.orders
+            .values()
+            .find(|o| o.client_order_id == client_order_id)
+    }
+
+    pub fn orders(&self) -> impl Iterator<Item = &TrackedOrder> {
+        self.orders.values()
+    }
+
+    pub fn open_orders<'a>(&'a self, symbol: &'a str) -> impl Iterator<Item = &'a TrackedOrder> {
+        self.orders
+            .values()
+            .filter(move |o| o.is_open() && o.symbol.eq_ignore_ascii_case(symbol))
+    }
+
+    // Forget filled, canceled, rejected and expired orders
+    pub fn prune_closed(&mut self) {
+        self.orders.retain(|_, o| o.is_open());
+    }
+
+    // Every change applied from now on, until the stream is dropped
+    pub fn changes(&mut self) -> impl Stream<Item = OrderChange> {
+        let (sender, receiver): (_, UnboundedReceiver<OrderChange>) = unbounded();
+        self.subscribers.push(sender);
+        receiver
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct AssetBalance {
+    pub free: f64,
+    pub locked: f64,
+    // Event time of the last update applied, 0 for balances taken from the account snapshot
+    pub update_time: u64,
+}
+
+impl AssetBalance {
+    #[must_use]
+    pub fn total(&self) -> f64 {
+        self.free + self.locked
+    }
+}
+
+// Free and locked balance per asset, seeded once from `/account` and kept current by
+// `outboundAccountPosition` and `balanceUpdate` events
+#[derive(Debug, Clone, Default)]
+pub struct BalanceTracker {
+    balances: HashMap<String, AssetBalance>,
+}
+
+impl BalanceTracker {
+    pub async fn seed(client: &Binance) -> Result<Self> {
+        Ok(Self::from_account(&client.get_account().await?))
+    }
+
+    #[must_use]
+    pub fn from_account(account: &AccountInformation) -> Self {
+        let balances = account
+            .balances
+            .iter()
+            .map(|balance| {
+                let asset_balance = AssetBalance {
+                    free: balance.free.parse().unwrap_or_default(),
+                    locked: balance.locked.parse().unwrap_or_default(),
+                    update_time: 0,
+                };
+                (balance.asset.clone(), asset_balance)
+            })
+            .collect();
+        Self { balances }
+    }
+
+    // Applies account and balance events of the user data stream, returns whether a balance
+    // changed. Other messages are ignored.
+    pub fn apply(&mut self, message: &BinanceWebsocketMessage) -> bool {
+        match message {
+            BinanceWebsocketMessage::UserAccountUpdate(update) => self.apply_position(update),
+            BinanceWebsocketMessage::UserBalanceUpdate(update) => self.apply_delta(update),
+            _ => false,
+        }
+    }
+
+    // The position event carries the absolute balances of the assets that changed
+    pub fn apply_position(&mut self, update: &AccountUpdate) -> bool {
+        let mut changed = false;
+        for balance in &update.balance {
+            let current = self.balances.entry(balance.asset.clone()).or_default();
+            if current.update_time > update.last_account_update {
+                continue;
+            }
+            *current = AssetBalance {
+                free: balance.free,
+                locked: balance.locked,
+                update_time: update.last_account_update,
+            };
+            changed = true;
+        }
+        changed
+    }
+
+    pub fn apply_delta(&mut self, update: &BalanceUpdate) -> bool {
+        let current = self.balances.entry(update.asset.clone()).or_default();
+        if current.update_time > update.event_time {
+            return false;
+        }
+        current.free += update.balance_delta;
+        current.update_time = update.event_time;
+        true
+    }
+
+    #[must_use]
+    pub fn get(&self, asset: &str) -> Option<AssetBalance> {
+        self.balances.get(&asset.to_uppercase()).copied()
+    }
+
+    // Assets with a non-zero balance
+    pub fn non_zero(&self) -> impl Iterator<Item = (&str, AssetBalance)> {
+        self.balances
+            .iter()
+            .filter(|(_, balance)| balance.total() != 0.0)
+            .map(|(asset, balance)| (asset.as_str(), *balance))
+    }
+}
+
+fn parse_status(status: &Value) -> Option<OrderStatus> {
+    serde_json::from_value(status.clone()).ok()
+}
+
+fn parse_float(value: &Value) -> f64 {
+    match value {
+        Value::String(s) => s.parse().unwrap_or_default(),
+        _ => value.as_f64().unwrap_or_default(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{BalanceTracker, OrderTracker};
+    use crate::model::{websocket::parse_event, AccountInformation, OrderStatus, Transaction};
+    use anyhow::Result;
+    use futures::StreamExt;
+    use serde_json::from_str;
+
+    const NEW: &str = r#"{"e":"executionReport","E":1718100001001,"s":"BTCUSDT","c":"web_5a3b0c1f","S":"BUY","o":"LIMIT","f":"GTC","q":"0.01000000","p":"67000.00000000","P":"0.00000000","F":"0.00000000","g":-1,"C":"","x":"NEW","X":"NEW","r":"NONE","i":28457312211,"l":"0.00000000","z":"0.00000000","L":"0.00000000","n":"0","N":null,"T":1718100001000,"t":-1,"I":60839122041,"w":true,"m":false,"M":false,"O":1718100001000,"Z":"0.00000000"}"#;
+    const FILLED: &str = r#"{"e":"executionReport","E":1718100061001,"s":"BTCUSDT","c":"web_5a3b0c1f","S":"BUY","o":"LIMIT","f":"GTC","q":"0.01000000","p":"67000.00000000","P":"0.00000000","F":"0.00000000","g":-1,"C":"","x":"TRADE","X":"FILLED","r":"NONE","i":28457312211,"l":"0.01000000","z":"0.01000000","L":"67000.00000000","n":"0.00001000","N":"BTC","T":1718100061000,"t":3634215001,"I":60839188210,"w":false,"m":true,"M":true,"O":1718100001000,"Z":"670.00000000"}"#;
+
+    #[tokio::test]
+    async fn order_tracker() -> Result<()> {
+        let mut tracker = OrderTracker::new();
+        let mut changes = tracker.changes();
+
+        let placed: Transaction = from_str(
+            r#"{"symbol":"BTCUSDT","orderId":28457312211,"orderListId":-1,"clientOrderId":"web_5a3b0c1f","transactTime":1718100001000}"#,
+        )?;
+        tracker.track(&placed);
+        assert_eq!(tracker.open_orders("btcusdt").count(), 1);
+
+        // The stream fills in what the ACK response left out
+        let change = tracker.apply(&parse_event(NEW)?).unwrap();
+        assert_eq!(change.previous_status, Some(OrderStatus::New));
+        assert!((change.order.orig_qty - 0.01).abs() < f64::EPSILON);
+        assert!(tracker.apply(&parse_event(NEW)?).is_none());
+
+        let change = tracker.apply(&parse_event(FILLED)?).unwrap();
+        assert_eq!(change.previous_status, Some(OrderStatus::New));
+        assert_eq!(change.order.status, OrderStatus::Filled);
+        assert!((change.order.executed_qty - 0.01).abs() < f64::EPSILON);
+
+        // A late NEW report does not reopen the order
+        assert!(tracker.apply(&parse_event(NEW)?).is_none());
+        assert_eq!(tracker.open_orders("BTCUSDT").count(), 0);
+
+        assert_eq!(changes.next().await.unwrap().previous_status, None);
+        assert_eq!(
+            changes.next().await.unwrap().order.update_time,
+            1_718_100_001_000
+        );
+        assert_eq!(
+            changes.next().await.unwrap().order.status,
+            OrderStatus::Filled
+        );
+
+        tracker.prune_closed();
+        assert!(tracker.by_client_order_id("web_5a3b0c1f").is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn balance_tracker() -> Result<()> {
+        let account: AccountInformation = from_str(
+            r#"{"makerCommission":15,"takerCommission":15,"buyerCommission":0,"sellerCommission":0,"canTrade":true,"canWithdraw":true,"canDeposit":true,"balances":[{"asset":"BTC","free":"1.00000000","locked":"0.50000000"},{"asset":"LTC","free":"0.00000000","locked":"0.00000000"}]}"#,
+        )?;
+        let mut tracker = BalanceTracker::from_account(&account);
+        assert_eq!(tracker.non_zero().count(), 1);
+
+        let deposit = r#"{"e":"balanceUpdate","E":1718100061001,"a":"LTC","d":"2.50000000","T":1718100061000}"#;
+        assert!(tracker.apply(&parse_event(deposit)?));
+        assert!((tracker.get("ltc").unwrap().free - 2.5).abs() < f64::EPSILON);
+
+        let position = r#"{"e":"outboundAccountPosition","E":1718100062001,"u":1718100062000,"B":[{"a":"BTC","f":"1.50000000","l":"0.00000000"}]}"#;
+        assert!(tracker.apply(&parse_event(position)?));
+        let btc = tracker.get("BTC").unwrap();
+        assert!((btc.total() - 1.5).abs() < f64::EPSILON);
+        assert!(btc.locked.abs() < f64::EPSILON);
+
+        // Older events do not overwrite newer balances
+        let stale = r#"{"e":"outboundAccountPosition","E":1718100050001,"u":1718100050000,"B":[{"a":"BTC","f":"1.00000000","l":"0.50000000"}]}"#;
+        assert!(!tracker.apply(&parse_event(stale)?));
+        Ok(())
+    }
+}
@@ -1,38 +1,262 @@
-use crate::error::{BinanceResponse, Error};
+use crate::error::{BinanceErrorData, BinanceResponse, Error};
+use crate::model::request::query_number;
+use crate::proxy::Proxy;
+use crate::scheduler::{RequestPriority, Scheduler, WEIGHT_LIMIT_1M};
+use crate::signing::{self, sign_query};
 use anyhow::Result;
-use chrono::Utc;
 use headers::*;
-use hex::encode as hexify;
-use hmac::{Hmac, Mac};
-use http::Method;
+use http::{Method, StatusCode};
 use log::debug;
 use once_cell::sync::OnceCell;
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{to_string, to_value, Value};
-use sha2::Sha256;
 use std::fmt;
 use std::str::FromStr;
-use url::Url;
+use std::sync::{Arc, PoisonError, RwLock};
+use std::time::Duration;
+use subtle::ConstantTimeEq;
+use tracing::{field, info_span, Instrument, Span};
+use url::{form_urlencoded, Url};
+use uuid::Uuid;
+use zeroize::Zeroizing;
 
-const BASE: &str = "https://www.binance.com/api";
+// Base url of every REST API family. `Custom` points e.g. at a mock server or a relay.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Host {
+    Api,
+    Sapi,
+    Papi,
+    Fapi,
+    Dapi,
+    Eapi,
+    Testnet,
+    Custom(Url),
+}
+
+impl Host {
+    #[must_use]
+    pub fn base_url(&self) -> &str {
+        match self {
+            Self::Api | Self::Sapi => "https://api.binance.com",
+            Self::Papi => "https://papi.binance.com",
+            Self::Fapi => "https://fapi.binance.com",
+            Self::Dapi => "https://dapi.binance.com",
+            Self::Eapi => "https://eapi.binance.com",
+            Self::Testnet => "https://testnet.binance.vision",
+            Self::Custom(url) => url.as_str().trim_end_matches('/'),
+        }
+    }
+}
+
+// Binance exchange the client talks to. The regional exchanges run the spot API on their own
+// host and offer no derivatives.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum Region {
+    #[default]
+    Com,
+    // Binance.US, with a subset of the SAPI routes
+    Us,
+    // Another exchange serving the spot API below `Url`, e.g. Binance TR or Japan
+    Custom(Url),
+}
+
+// SAPI routes Binance.US serves
+const US_SAPI_PREFIXES: [&str; 10] = [
+    "/sapi/v1/account/",
+    "/sapi/v1/asset/",
+    "/sapi/v1/capital/",
+    "/sapi/v1/fiatpayment/",
+    "/sapi/v1/otc/",
+    "/sapi/v1/staking/",
+    "/sapi/v1/sub-account/",
+    "/sapi/v1/system/",
+    "/sapi/v1/userDataStream",
+    "/sapi/v3/sub-account/",
+];
 
+impl Region {
+    #[must_use]
+    pub fn base_url<'a>(&'a self, host: &'a Host) -> &'a str {
+        match (self, host) {
+            (Self::Us, Host::Api | Host::Sapi) => "https://api.binance.us",
+            (Self::Custom(url), Host::Api | Host::Sapi) => url.as_str().trim_end_matches('/'),
+            _ => host.base_url(),
+        }
+    }
+
+    // Whether the exchange of this region serves `endpoint`. Custom hosts are always assumed to.
+    #[must_use]
+    pub fn supports(&self, endpoint: &Endpoint) -> bool {
+        match (self, &endpoint.host) {
+            (Self::Us, Host::Sapi) => US_SAPI_PREFIXES
+                .iter()
+                .any(|prefix| endpoint.path.starts_with(prefix)),
+            (Self::Com, _)
+            | (_, Host::Api | Host::Testnet | Host::Custom(_))
+            | (Self::Custom(_), Host::Sapi) => true,
+            _ => false,
+        }
+    }
+
+    // Full url of `endpoint` on this region's exchange, `Error::UnsupportedEndpoint` if it is
+    // not served there
+    pub fn url(&self, endpoint: &Endpoint) -> Result<String, Error> {
+        if !self.supports(endpoint) {
+            return Err(Error::UnsupportedEndpoint(endpoint.path.clone()));
+        }
+        Ok(format!(
+            "{}{}",
+            self.base_url(&endpoint.host),
+            endpoint.path
+        ))
+    }
+}
+
+// A REST endpoint, `path` includes the API prefix, e.g. "/fapi/v1/time"
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Endpoint {
+    pub host: Host,
+    pub path: String,
+}
+
+impl Endpoint {
+    #[must_use]
+    pub fn new(host: Host, path: &str) -> Self {
+        Self {
+            host,
+            path: path.into(),
+        }
+    }
+
+    #[must_use]
+    pub fn url(&self) -> String {
+        format!("{}{}", self.host.base_url(), self.path)
+    }
+}
+
+// API family and version, the prefix of every REST path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Version {
     V1,
     V2,
     V3,
+    SapiV1,
+    SapiV3,
+    PapiV1,
+    FapiV1,
+    FapiV2,
+}
+
+impl Version {
+    #[must_use]
+    pub const fn host(self) -> Host {
+        match self {
+            Self::V1 | Self::V2 | Self::V3 => Host::Api,
+            Self::SapiV1 | Self::SapiV3 => Host::Sapi,
+            Self::PapiV1 => Host::Papi,
+            Self::FapiV1 | Self::FapiV2 => Host::Fapi,
+        }
+    }
+
+    // `path` below this version, e.g. "/time" becomes "/api/v3/time"
+    #[must_use]
+    pub fn endpoint(self, path: &str) -> Endpoint {
+        Endpoint {
+            host: self.host(),
+            path: format!("{self}{path}"),
+        }
+    }
 }
 
 impl fmt::Display for Version {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match &self {
-            Version::V1 => write!(f, "/v1"),
-            Version::V2 => write!(f, "/v2"),
-            Version::V3 => write!(f, "/v3"),
+            Self::V1 => write!(f, "/api/v1"),
+            Self::V2 => write!(f, "/api/v2"),
+            Self::V3 => write!(f, "/api/v3"),
+            Self::SapiV1 => write!(f, "/sapi/v1"),
+            Self::SapiV3 => write!(f, "/sapi/v3"),
+            Self::PapiV1 => write!(f, "/papi/v1"),
+            Self::FapiV1 => write!(f, "/fapi/v1"),
+            Self::FapiV2 => write!(f, "/fapi/v2"),
         }
     }
 }
 
 const RECV_WINDOW: usize = 5000;
+const USED_WEIGHT_HEADER: &str = "x-mbx-used-weight-1m";
+const DEFAULT_CLIENT: &str = "the default HTTP config builds a client";
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+const POOL_IDLE_TIMEOUT: Duration = Duration::from_secs(90);
+const TCP_KEEPALIVE: Duration = Duration::from_mins(1);
+
+// Settings of the underlying HTTP client. Unlike the reqwest defaults, requests time out
+// so a hung connection cannot stall the caller forever.
+#[derive(Clone, Debug)]
+pub struct HttpConfig {
+    pub connect_timeout: Option<Duration>,
+    pub timeout: Option<Duration>,
+    pub pool_idle_timeout: Option<Duration>,
+    pub pool_max_idle_per_host: usize,
+    pub tcp_nodelay: bool,
+    pub tcp_keepalive: Option<Duration>,
+    pub http2_prior_knowledge: bool,
+    pub proxy: Option<Proxy>,
+    // Request weight per minute shared out by `RequestPriority`
+    pub weight_limit: u32,
+    pub region: Region,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Some(CONNECT_TIMEOUT),
+            timeout: Some(REQUEST_TIMEOUT),
+            pool_idle_timeout: Some(POOL_IDLE_TIMEOUT),
+            pool_max_idle_per_host: usize::MAX,
+            tcp_nodelay: true,
+            tcp_keepalive: Some(TCP_KEEPALIVE),
+            http2_prior_knowledge: false,
+            proxy: None,
+            weight_limit: WEIGHT_LIMIT_1M,
+            region: Region::Com,
+        }
+    }
+}
+
+impl HttpConfig {
+    // Browsers own the connection, so only the native client honours the socket options
+    #[cfg_attr(target_arch = "wasm32", allow(clippy::unused_self))]
+    pub fn client(&self) -> Result<reqwest::Client> {
+        let builder = reqwest::Client::builder();
+        #[cfg(not(target_arch = "wasm32"))]
+        let builder = self.native_options(builder)?;
+        Ok(builder.build()?)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn native_options(&self, builder: reqwest::ClientBuilder) -> Result<reqwest::ClientBuilder> {
+        let mut builder = builder
+            .pool_idle_timeout(self.pool_idle_timeout)
+            .pool_max_idle_per_host(self.pool_max_idle_per_host)
+            .tcp_nodelay(self.tcp_nodelay)
+            .tcp_keepalive(self.tcp_keepalive);
+        if let Some(timeout) = self.connect_timeout {
+            builder = builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.timeout {
+            builder = builder.timeout(timeout);
+        }
+        if self.http2_prior_knowledge {
+            builder = builder.http2_prior_knowledge();
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(proxy.reqwest()?);
+        }
+        Ok(builder)
+    }
+}
 
 pub struct BinanceApiKey(pub String);
 
@@ -60,10 +284,58 @@ impl headers::Header for BinanceApiKey {
     }
 }
 
+// API key and secret. The secret is wiped from memory on drop, never printed by `Debug` and
+// compared in constant time.
+#[derive(Clone)]
+pub struct Credential {
+    api_key: String,
+    api_secret: Zeroizing<String>,
+}
+
+impl Credential {
+    pub fn new(api_key: &str, api_secret: &str) -> Self {
+        Self {
+            api_key: api_key.into(),
+            api_secret: Zeroizing::new(api_secret.into()),
+        }
+    }
+
+    pub fn api_key(&self) -> &str {
+        &self.api_key
+    }
+}
+
+impl fmt::Debug for Credential {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Credential")
+            .field("api_key", &self.api_key)
+            .field("api_secret", &"<redacted>")
+            .finish()
+    }
+}
+
+impl PartialEq for Credential {
+    fn eq(&self, other: &Self) -> bool {
+        self.api_key == other.api_key
+            && bool::from(
+                self.api_secret
+                    .as_bytes()
+                    .ct_eq(other.api_secret.as_bytes()),
+            )
+    }
+}
+
+impl Eq for Credential {}
+
 #[derive(Clone, Debug)]
 pub struct Transport {
-    credential: Option<(String, String)>,
+    // Shared by all clones, so a rotated credential is picked up everywhere
+    credential: Arc<RwLock<Option<Credential>>>,
     client: reqwest::Client,
+    scheduler: Arc<Scheduler>,
+    region: Arc<RwLock<Region>>,
+    // Shared by all clones as well, `Binance::warm_up` may pin a faster mirror of the spot API
+    spot_host: Arc<RwLock<Option<Url>>>,
     pub recv_window: usize,
 }
 
@@ -74,20 +346,31 @@ impl Default for Transport {
 }
 
 impl Transport {
+    // Panics like `reqwest::Client::new` if the TLS backend cannot be initialized, the default
+    // config has no proxy or other setting that could fail
+    #[must_use]
     pub fn new() -> Self {
-        Self {
-            credential: None,
-            client: reqwest::Client::builder().build().unwrap(),
-            recv_window: RECV_WINDOW,
-        }
+        Self::with_config(None, &HttpConfig::default()).expect(DEFAULT_CLIENT)
     }
 
+    #[must_use]
     pub fn with_credential(api_key: &str, api_secret: &str) -> Self {
-        Self {
-            client: reqwest::Client::builder().build().unwrap(),
-            credential: Some((api_key.into(), api_secret.into())),
+        Self::with_config(
+            Some(Credential::new(api_key, api_secret)),
+            &HttpConfig::default(),
+        )
+        .expect(DEFAULT_CLIENT)
+    }
+
+    pub fn with_config(credential: Option<Credential>, config: &HttpConfig) -> Result<Self> {
+        Ok(Self {
+            credential: Arc::new(RwLock::new(credential)),
+            client: config.client()?,
+            scheduler: Arc::new(Scheduler::new(config.weight_limit)),
+            region: Arc::new(RwLock::new(config.region.clone())),
+            spot_host: Arc::new(RwLock::new(None)),
             recv_window: RECV_WINDOW,
-        }
+        })
     }
 
     pub async fn get<O, Q>(
@@ -100,10 +383,33 @@ impl Transport {
         O: DeserializeOwned,
         Q: Serialize,
     {
-        self.request::<_, _, ()>(Method::GET, api_version, endpoint, params, None)
+        self.request::<_, _, ()>(Method::GET, api_version.endpoint(endpoint), params, None)
             .await
     }
 
+    // Like `get`, for endpoints whose request weight depends on the parameters. The request
+    // waits until `weight` fits into the budget of the current minute.
+    pub async fn weighted_get<O, Q>(
+        &self,
+        api_version: Version,
+        endpoint: &str,
+        params: Option<Q>,
+        weight: u32,
+    ) -> Result<O>
+    where
+        O: DeserializeOwned,
+        Q: Serialize,
+    {
+        self.weighted_request::<_, _, ()>(
+            Method::GET,
+            api_version.endpoint(endpoint),
+            params,
+            None,
+            weight,
+        )
+        .await
+    }
+
     pub async fn post<O, D>(
         &self,
         api_version: Version,
@@ -114,7 +420,7 @@ impl Transport {
         O: DeserializeOwned,
         D: Serialize,
     {
-        self.request::<_, (), _>(Method::POST, api_version, endpoint, None, data)
+        self.request::<_, (), _>(Method::POST, api_version.endpoint(endpoint), None, data)
             .await
     }
 
@@ -128,7 +434,7 @@ impl Transport {
         O: DeserializeOwned,
         D: Serialize,
     {
-        self.request::<_, (), _>(Method::PUT, api_version, endpoint, None, data)
+        self.request::<_, (), _>(Method::PUT, api_version.endpoint(endpoint), None, data)
             .await
     }
 
@@ -142,7 +448,7 @@ impl Transport {
         O: DeserializeOwned,
         Q: Serialize,
     {
-        self.request::<_, _, ()>(Method::DELETE, api_version, endpoint, params, None)
+        self.request::<_, _, ()>(Method::DELETE, api_version.endpoint(endpoint), params, None)
             .await
     }
 
@@ -156,7 +462,7 @@ impl Transport {
         O: DeserializeOwned,
         Q: Serialize,
     {
-        self.signed_request::<_, _, ()>(Method::GET, api_version, endpoint, params, None)
+        self.signed_request::<_, _, ()>(Method::GET, api_version.endpoint(endpoint), params, None)
             .await
     }
 
@@ -170,7 +476,7 @@ impl Transport {
         O: DeserializeOwned,
         D: Serialize,
     {
-        self.signed_request::<_, (), _>(Method::POST, api_version, endpoint, None, data)
+        self.signed_request::<_, (), _>(Method::POST, api_version.endpoint(endpoint), None, data)
             .await
     }
 
@@ -184,7 +490,7 @@ impl Transport {
         O: DeserializeOwned,
         Q: Serialize,
     {
-        self.signed_request::<_, _, ()>(Method::PUT, api_version, endpoint, params, None)
+        self.signed_request::<_, _, ()>(Method::PUT, api_version.endpoint(endpoint), params, None)
             .await
     }
 
@@ -198,24 +504,46 @@ impl Transport {
         O: DeserializeOwned,
         Q: Serialize,
     {
-        self.signed_request::<_, _, ()>(Method::DELETE, api_version, endpoint, params, None)
-            .await
+        self.signed_request::<_, _, ()>(
+            Method::DELETE,
+            api_version.endpoint(endpoint),
+            params,
+            None,
+        )
+        .await
     }
 
     pub async fn request<O, Q, D>(
         &self,
         method: Method,
-        api_version: Version,
-        endpoint: &str,
+        endpoint: Endpoint,
+        params: Option<Q>,
+        data: Option<D>,
+    ) -> Result<O>
+    where
+        O: DeserializeOwned,
+        Q: Serialize,
+        D: Serialize,
+    {
+        self.weighted_request(method, endpoint, params, data, 1)
+            .await
+    }
+
+    async fn weighted_request<O, Q, D>(
+        &self,
+        method: Method,
+        endpoint: Endpoint,
         params: Option<Q>,
         data: Option<D>,
+        weight: u32,
     ) -> Result<O>
     where
         O: DeserializeOwned,
         Q: Serialize,
         D: Serialize,
     {
-        let url = format!("{}{}{}", BASE, api_version, endpoint);
+        let priority = RequestPriority::of(&method, &endpoint.path);
+        let url = self.url(&endpoint)?;
         debug!("url: {}", url);
         let url = match params {
             Some(p) => Url::parse_with_params(&url, p.to_url_query())?,
@@ -226,32 +554,27 @@ impl Transport {
             Some(data) => data.to_url_query_string(),
             None => "".to_string(),
         };
+        let span = request_span(&method, &endpoint, &url, &body);
 
         let mut req = self
             .client
             .request(method, url.as_str())
             .header("Content-Type", "application/x-www-form-urlencoded");
 
-        if let Ok((key, _)) = self.check_key() {
+        if let Ok(credential) = self.check_key() {
             // This is for user stream: user stream requests need api key in the header but no signature. WEIRD
-            req = req.header("X-MBX-APIKEY", key);
+            req = req.header("X-MBX-APIKEY", credential.api_key());
         }
 
-        let req = req.body(body);
-
-        Ok(req
-            .send()
-            .await?
-            .json::<BinanceResponse<_>>()
-            .await?
-            .into_result()?)
+        self.send(req.body(body), &endpoint.host, priority, weight)
+            .instrument(span)
+            .await
     }
 
     pub async fn signed_request<O, Q, D>(
         &self,
         method: Method,
-        api_version: Version,
-        endpoint: &str,
+        endpoint: Endpoint,
         params: Option<Q>,
         data: Option<D>,
     ) -> Result<O>
@@ -260,15 +583,17 @@ impl Transport {
         Q: Serialize,
         D: Serialize,
     {
+        let priority = RequestPriority::of(&method, &endpoint.path);
         let query = params.map_or_else(Vec::new, |q| q.to_url_query());
-        let url = format!("{}{}{}", BASE, api_version, endpoint);
+        let url = self.url(&endpoint)?;
         let mut url = Url::parse_with_params(&url, &query)?;
         url.query_pairs_mut()
-            .append_pair("timestamp", &Utc::now().timestamp_millis().to_string());
+            .append_pair("timestamp", &signing::timestamp().to_string());
         url.query_pairs_mut()
             .append_pair("recvWindow", &self.recv_window.to_string());
 
         let body = data.map_or_else(String::new, |data| data.to_url_query_string());
+        let span = request_span(&method, &endpoint, &url, &body);
 
         let (key, signature) = self.signature(&url, &body)?;
         url.query_pairs_mut().append_pair("signature", &signature);
@@ -282,40 +607,232 @@ impl Transport {
             .header("X-MBX-APIKEY", key)
             .body(body);
 
-        Ok(req
-            .send()
-            .await?
-            .json::<BinanceResponse<_>>()
-            .await?
-            .into_result()?)
+        self.send(req, &endpoint.host, priority, 1)
+            .instrument(span)
+            .await
     }
 
-    fn check_key(&self) -> Result<(&str, &str)> {
-        match self.credential.as_ref() {
-            None => Err(Error::NoApiKeySet.into()),
-            Some((k, s)) => Ok((k, s)),
+    // Runs inside the span of `request_span`, which gets the used weight and the outcome
+    async fn send<O: DeserializeOwned>(
+        &self,
+        req: reqwest::RequestBuilder,
+        host: &Host,
+        priority: RequestPriority,
+        weight: u32,
+    ) -> Result<O> {
+        let span = Span::current();
+        // Requests during a ban would only extend it
+        if let Some(until) = self.scheduler.banned_until() {
+            span.record("outcome", "banned");
+            return Err(Error::IpBanned { until: Some(until) }.into());
+        }
+        self.scheduler.acquire(host, priority, weight).await;
+        let response = req.send().await.inspect_err(|e| {
+            span.record("outcome", field::display(e));
+        })?;
+        if let Some(used) = response
+            .headers()
+            .get(USED_WEIGHT_HEADER)
+            .and_then(|used| used.to_str().ok()?.parse().ok())
+        {
+            self.scheduler.record(host, used);
+            span.record("weight", used);
         }
+        let status = response.status();
+        span.record("status", status.as_u16());
+        let result = parse_response(status, &response.text().await?);
+        if let Err(Some(Error::IpBanned { until: Some(until) })) =
+            result.as_ref().map_err(anyhow::Error::downcast_ref)
+        {
+            self.scheduler.ban(*until);
+        }
+        match &result {
+            Ok(_) => span.record("outcome", "ok"),
+            Err(e) => span.record("outcome", field::display(e)),
+        };
+        result
     }
 
-    pub(self) fn signature(&self, url: &Url, body: &str) -> Result<(&str, String)> {
-        let (key, secret) = self.check_key()?;
-        // Signature: hex(HMAC_SHA256(queries + data))
-        let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).unwrap();
-        let sign_message = format!("{}{}", url.query().unwrap_or(""), body);
-        mac.update(sign_message.as_bytes());
-        let signature = hexify(mac.finalize().into_bytes());
-        Ok((key, signature))
+    // Request weight used on `host` in the current minute, as last reported by the exchange
+    pub fn used_weight(&self, host: &Host) -> u32 {
+        self.scheduler.used_weight(host)
     }
+
+    pub fn weight_per_second(&self, host: &Host) -> u32 {
+        self.scheduler.weight_per_second(host)
+    }
+
+    // Market data weight left on `host` in the current minute, and the time until the next one
+    pub fn budget_left(&self, host: &Host) -> (u32, Duration) {
+        self.scheduler.budget_left(host)
+    }
+
+    #[must_use]
+    pub fn region(&self) -> Region {
+        self.region
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    // Switches this transport and all its clones to `region`, dropping a pinned spot host
+    pub fn set_region(&self, region: Region) {
+        *self.region.write().unwrap_or_else(PoisonError::into_inner) = region;
+        self.set_spot_host(None);
+    }
+
+    #[must_use]
+    pub fn spot_host(&self) -> Option<Url> {
+        self.spot_host
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    // Sends the spot API requests of this transport and all its clones to `host`, e.g. a mirror
+    // like "https://api3.binance.com". The other APIs stay on the hosts of the region.
+    pub fn set_spot_host(&self, host: Option<Url>) {
+        *self
+            .spot_host
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = host;
+    }
+
+    // Full url of `endpoint` in the current region, on the pinned spot host for the spot API
+    pub fn url(&self, endpoint: &Endpoint) -> Result<String, Error> {
+        let url = self.region().url(endpoint)?;
+        match (&endpoint.host, self.spot_host()) {
+            (Host::Api, Some(host)) => Ok(format!(
+                "{}{}",
+                host.as_str().trim_end_matches('/'),
+                endpoint.path
+            )),
+            _ => Ok(url),
+        }
+    }
+
+    // Time until the response headers of an unsigned GET of `url` arrived, over a pooled
+    // connection if there is one. The connection stays in the pool for later requests.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub async fn round_trip(&self, url: &str) -> Result<Duration> {
+        let started = std::time::Instant::now();
+        self.client.get(url).send().await?.error_for_status()?;
+        Ok(started.elapsed())
+    }
+
+    // Replaces the credential of this transport and all its clones, `None` removes it
+    pub fn set_credential(&self, credential: Option<Credential>) {
+        *self
+            .credential
+            .write()
+            .unwrap_or_else(PoisonError::into_inner) = credential;
+    }
+
+    fn check_key(&self) -> Result<Credential> {
+        let credential = self
+            .credential
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        Ok(credential.ok_or(Error::NoApiKeySet)?)
+    }
+
+    pub(self) fn signature(&self, url: &Url, body: &str) -> Result<(String, String)> {
+        let credential = self.check_key()?;
+        let signature = sign_query(&credential.api_secret, url.query().unwrap_or(""), body);
+        Ok((credential.api_key, signature))
+    }
+}
+
+// One span per REST call, `weight`, `status` and `outcome` are recorded by `send`
+fn request_span(method: &Method, endpoint: &Endpoint, url: &Url, body: &str) -> Span {
+    let (symbol, correlation_id) = span_params(url, body);
+    info_span!(
+        "binance_request",
+        %method,
+        endpoint = %endpoint.path,
+        symbol = symbol.as_deref(),
+        %correlation_id,
+        weight = field::Empty,
+        status = field::Empty,
+        outcome = field::Empty,
+    )
+}
+
+// Symbol and correlation id of a request. The correlation id is the client order id of order
+// requests, so all calls about one order share it, and a fresh id otherwise.
+fn span_params(url: &Url, body: &str) -> (Option<String>, String) {
+    let params: Vec<(String, String)> = url
+        .query_pairs()
+        .chain(form_urlencoded::parse(body.as_bytes()))
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    };
+    let correlation_id = param("newClientOrderId")
+        .or_else(|| param("origClientOrderId"))
+        .unwrap_or_else(|| Uuid::new_v4().simple().to_string());
+    (param("symbol"), correlation_id)
+}
+
+// Maintenance is reported as 503 with a non-JSON body, or as an error mentioning it. Both end
+// up as `Error::ExchangeMaintenance` so callers can back off instead of retrying blindly.
+// Bans come as 418, with JSON or an HTML page, and end up as `Error::IpBanned`. An HTML 403 is
+// the CDN's firewall rejecting the request, `Error::Cloudflare`. Error bodies of other statuses
+// are never taken for `O`, which may be e.g. `Success` and accept any object.
+fn parse_response<O: DeserializeOwned>(status: StatusCode, body: &str) -> Result<O> {
+    let response = if status.is_success() {
+        serde_json::from_str::<BinanceResponse<O>>(body)
+    } else {
+        serde_json::from_str::<BinanceErrorData>(body).map(BinanceResponse::Error)
+    };
+    match response {
+        Ok(response) => match response.into_result() {
+            Err(Error::BinanceError { msg, .. }) if msg.to_lowercase().contains("maintenance") => {
+                Err(Error::ExchangeMaintenance(msg).into())
+            }
+            Err(Error::BinanceError { msg, .. })
+                if status == StatusCode::IM_A_TEAPOT || msg.contains("banned until") =>
+            {
+                Err(Error::IpBanned {
+                    until: banned_until(&msg),
+                }
+                .into())
+            }
+            result => Ok(result?),
+        },
+        Err(_) if status == StatusCode::SERVICE_UNAVAILABLE => {
+            Err(Error::ExchangeMaintenance(status.to_string()).into())
+        }
+        Err(_) if status == StatusCode::IM_A_TEAPOT => Err(Error::IpBanned {
+            until: banned_until(body),
+        }
+        .into()),
+        Err(_) if status == StatusCode::FORBIDDEN => Err(Error::Cloudflare(status.as_u16()).into()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Millisecond timestamp of e.g. "Way too many requests; IP(10.0.0.1) banned until 1664544000000."
+fn banned_until(msg: &str) -> Option<u64> {
+    let (_, rest) = msg.split_once("banned until ")?;
+    let digits = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    rest[..digits].parse().ok()
 }
 
 trait ToUrlQuery: Serialize {
+    // Percent-encoded exactly like `Url::parse_with_params` encodes the query, so the signed
+    // payload always matches the bytes on the wire
     fn to_url_query_string(&self) -> String {
-        let vec = self.to_url_query();
-
-        vec.into_iter()
-            .map(|(k, v)| format!("{}={}", k, v))
-            .collect::<Vec<_>>()
-            .join("&")
+        form_urlencoded::Serializer::new(String::new())
+            .extend_pairs(self.to_url_query())
+            .finish()
     }
 
     fn to_url_query(&self) -> Vec<(String, String)> {
@@ -327,6 +844,7 @@ trait ToUrlQuery: Serialize {
             match value {
                 Value::Null => continue,
                 Value::String(s) => vec.push((key.clone(), s.clone())),
+                Value::Number(number) => vec.push((key.clone(), query_number(number))),
                 other => vec.push((key.clone(), to_string(other).unwrap())),
             }
         }
@@ -339,8 +857,14 @@ impl<S: Serialize> ToUrlQuery for S {}
 
 #[cfg(test)]
 mod test {
-    use super::Transport;
+    use super::{
+        parse_response, span_params, Credential, Endpoint, Host, Region, ToUrlQuery, Transport,
+        Version,
+    };
+    use crate::{error::Error, model::Success};
     use anyhow::Result;
+    use http::StatusCode;
+    use serde_json::json;
     use url::{form_urlencoded::Serializer, Url};
 
     #[test]
@@ -459,4 +983,228 @@ mod test {
         );
         Ok(())
     }
+
+    #[test]
+    fn url_query_percent_encoding() -> Result<()> {
+        let params = json! {{"email": "sub+1@example.com", "newClientOrderId": "my#order 1"}};
+        let body = params.to_url_query_string();
+        assert_eq!(
+            body,
+            "email=sub%2B1%40example.com&newClientOrderId=my%23order+1"
+        );
+
+        let url = Url::parse_with_params("http://a.com/api/v3/order", params.to_url_query())?;
+        assert_eq!(url.query(), Some(body.as_str()));
+        Ok(())
+    }
+
+    #[test]
+    fn url_query_numbers() {
+        let params = json! {{"amount": 0.000_008_12, "limit": 1000, "total": 1e16}};
+        assert_eq!(
+            params.to_url_query_string(),
+            "amount=0.00000812&limit=1000&total=10000000000000000"
+        );
+    }
+
+    #[test]
+    fn request_span_params() -> Result<()> {
+        let url = Url::parse("https://api.binance.com/api/v3/order?symbol=BTCUSDT&timestamp=1")?;
+        let (symbol, id) = span_params(&url, "side=BUY&newClientOrderId=my-order");
+        assert_eq!(symbol.as_deref(), Some("BTCUSDT"));
+        assert_eq!(id, "my-order");
+
+        let url = Url::parse("https://api.binance.com/api/v3/time")?;
+        let (symbol, id) = span_params(&url, "");
+        assert!(symbol.is_none());
+        assert_eq!(id.len(), 32);
+        Ok(())
+    }
+
+    #[test]
+    fn maintenance_response() {
+        let maintenance = |result: Result<u64>| {
+            matches!(
+                result.unwrap_err().downcast_ref(),
+                Some(Error::ExchangeMaintenance(_))
+            )
+        };
+        assert!(maintenance(parse_response(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "<html>Service Unavailable</html>"
+        )));
+        assert!(maintenance(parse_response(
+            StatusCode::OK,
+            r#"{"code":-1,"msg":"System is under maintenance."}"#
+        )));
+        assert!(!maintenance(parse_response(
+            StatusCode::BAD_REQUEST,
+            r#"{"code":-1121,"msg":"Invalid symbol."}"#
+        )));
+        assert_eq!(parse_response::<u64>(StatusCode::OK, "42").unwrap(), 42);
+    }
+
+    #[test]
+    fn error_response() {
+        let error = parse_response::<Success>(
+            StatusCode::BAD_REQUEST,
+            r#"{"code":-4046,"msg":"No need to change margin type."}"#,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref(),
+            Some(Error::BinanceError { code: -4046, .. })
+        ));
+        assert!(
+            parse_response::<Success>(StatusCode::OK, r#"{"code":200,"msg":"success"}"#).is_ok()
+        );
+        assert!(parse_response::<Success>(StatusCode::BAD_REQUEST, "{}").is_err());
+    }
+
+    #[test]
+    fn banned_response() {
+        let error = |status, body| {
+            parse_response::<u64>(status, body)
+                .unwrap_err()
+                .downcast::<Error>()
+                .unwrap()
+        };
+        assert!(matches!(
+            error(
+                StatusCode::IM_A_TEAPOT,
+                r#"{"code":-1003,"msg":"Way too many requests; IP(10.0.0.1) banned until 1664544000000. Please use the websocket for live updates to avoid bans."}"#
+            ),
+            Error::IpBanned {
+                until: Some(1_664_544_000_000)
+            }
+        ));
+        assert!(matches!(
+            error(StatusCode::IM_A_TEAPOT, "<html><body>Banned</body></html>"),
+            Error::IpBanned { until: None }
+        ));
+        assert!(matches!(
+            error(
+                StatusCode::FORBIDDEN,
+                "<HTML><HEAD><TITLE>ERROR: The request could not be satisfied</TITLE></HEAD></HTML>"
+            ),
+            Error::Cloudflare(403)
+        ));
+        assert!(matches!(
+            error(
+                StatusCode::TOO_MANY_REQUESTS,
+                r#"{"code":-1003,"msg":"Too many requests."}"#
+            ),
+            Error::BinanceError { code: -1003, .. }
+        ));
+    }
+
+    #[test]
+    fn credential_debug() {
+        let tr = Transport::with_credential("api-key", "very-secret");
+        let debug = format!("{tr:?}");
+        assert!(debug.contains("api-key"));
+        assert!(!debug.contains("very-secret"));
+        assert_eq!(
+            Credential::new("api-key", "very-secret"),
+            Credential::new("api-key", "very-secret")
+        );
+        assert_ne!(
+            Credential::new("api-key", "very-secret"),
+            Credential::new("api-key", "other-secret")
+        );
+    }
+
+    #[test]
+    fn credential_rotation() -> Result<()> {
+        let tr = Transport::with_credential("old-key", "old-secret");
+        let clone = tr.clone();
+        let url = Url::parse("http://a.com/api/v3/account?timestamp=1")?;
+        let (_, old) = tr.signature(&url, "")?;
+
+        tr.set_credential(Some(Credential::new("new-key", "new-secret")));
+        let (key, new) = clone.signature(&url, "")?;
+        assert_eq!(key, "new-key");
+        assert_ne!(old, new);
+
+        clone.set_credential(None);
+        assert!(tr.signature(&url, "").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn endpoint_url() -> Result<()> {
+        assert_eq!(
+            Version::SapiV3.endpoint("/asset/getUserAsset").url(),
+            "https://api.binance.com/sapi/v3/asset/getUserAsset"
+        );
+        assert_eq!(
+            Version::FapiV2.endpoint("/account").url(),
+            "https://fapi.binance.com/fapi/v2/account"
+        );
+        assert_eq!(
+            Endpoint::new(Host::Fapi, "/fapi/v1/time").url(),
+            "https://fapi.binance.com/fapi/v1/time"
+        );
+        let local = Host::Custom(Url::parse("http://127.0.0.1:8080")?);
+        assert_eq!(
+            Endpoint::new(local, "/api/v3/time").url(),
+            "http://127.0.0.1:8080/api/v3/time"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn region_urls() -> Result<()> {
+        let us = Region::Us;
+        assert_eq!(
+            us.url(&Version::V3.endpoint("/time"))?,
+            "https://api.binance.us/api/v3/time"
+        );
+        assert_eq!(
+            us.url(&Version::SapiV1.endpoint("/capital/config/getall"))?,
+            "https://api.binance.us/sapi/v1/capital/config/getall"
+        );
+        assert!(us
+            .url(&Version::SapiV1.endpoint("/margin/account"))
+            .is_err());
+        assert!(us.url(&Version::FapiV2.endpoint("/account")).is_err());
+
+        let tr = Region::Custom(Url::parse("https://api.example.tr/")?);
+        assert_eq!(
+            tr.url(&Version::V3.endpoint("/time"))?,
+            "https://api.example.tr/api/v3/time"
+        );
+        assert_eq!(
+            Region::Com.url(&Version::FapiV1.endpoint("/time"))?,
+            "https://fapi.binance.com/fapi/v1/time"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn pinned_spot_host() -> Result<()> {
+        let tr = Transport::new();
+        // Clones share the override
+        let clone = tr.clone();
+        clone.set_spot_host(Some(Url::parse("https://api3.binance.com")?));
+        assert_eq!(
+            tr.url(&Version::V3.endpoint("/time"))?,
+            "https://api3.binance.com/api/v3/time"
+        );
+        assert_eq!(
+            tr.url(&Version::FapiV1.endpoint("/time"))?,
+            "https://fapi.binance.com/fapi/v1/time"
+        );
+        assert_eq!(
+            tr.url(&Version::SapiV1.endpoint("/system/status"))?,
+            "https://api.binance.com/sapi/v1/system/status"
+        );
+
+        clone.set_region(Region::Us);
+        assert_eq!(
+            tr.url(&Version::V3.endpoint("/time"))?,
+            "https://api.binance.us/api/v3/time"
+        );
+        Ok(())
+    }
 }
@@ -0,0 +1,123 @@
+//! User data stream that keeps its listen key alive and replaces it once it expired.
+//!
+//! `UserDataManager` extends the listen key every 30 minutes. When the exchange reports
+//! `listenKeyExpired`, or the connection fails or goes silent, it obtains a new key, reconnects
+//! and yields `BinanceWebsocketMessage::UserStreamRestarted`. Events sent in between are lost,
+//! so resync open orders and balances on it, e.g. with `OrderTracker::reconcile`.
+
+use crate::{
+    client::{websocket::BinanceWebsocket, Binance},
+    error::Error,
+    model::websocket::{BinanceWebsocketMessage, Subscription},
+};
+use anyhow::Result;
+use futures::StreamExt;
+use std::time::Duration;
+use tokio::time::{interval_at, Instant, Interval, MissedTickBehavior};
+
+// Listen keys expire 60 minutes after they were last extended
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_mins(30);
+// The exchange pings every 20 seconds and pings count as activity, a minute without any means
+// the connection is dead
+const STALE_TIMEOUT: Duration = Duration::from_mins(1);
+
+pub struct UserDataManager {
+    client: Binance,
+    websocket: BinanceWebsocket,
+    listen_key: String,
+    keep_alive: Interval,
+}
+
+impl UserDataManager {
+    // Open the user data stream of `client`'s account on `websocket`
+    pub async fn start(client: &Binance, mut websocket: BinanceWebsocket) -> Result<Self> {
+        let listen_key = client.user_stream_start().await?.listen_key;
+        subscribe(&mut websocket, &listen_key).await?;
+        let mut keep_alive = interval_at(Instant::now() + KEEP_ALIVE_INTERVAL, KEEP_ALIVE_INTERVAL);
+        keep_alive.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        Ok(Self {
+            client: client.clone(),
+            websocket,
+            listen_key,
+            keep_alive,
+        })
+    }
+
+    #[must_use]
+    pub fn listen_key(&self) -> &str {
+        &self.listen_key
+    }
+
+    // The next event of the stream. Failing to restart is returned as an error, the next call
+    // tries again.
+    pub async fn next(&mut self) -> Option<Result<BinanceWebsocketMessage>> {
+        loop {
+            tokio::select! {
+                message = self.websocket.next() => return match message? {
+                    Ok(BinanceWebsocketMessage::ListenKeyExpired(_)) => Some(self.restart().await),
+                    Ok(BinanceWebsocketMessage::StaleStream(Subscription::UserData(key)))
+                        if key == self.listen_key =>
+                    {
+                        log::warn!("user data stream went silent, restarting");
+                        Some(self.restart().await)
+                    }
+                    Err(e) if is_connection_error(&e) => {
+                        log::warn!("user data stream: {e}, restarting");
+                        Some(self.restart().await)
+                    }
+                    message => Some(message),
+                },
+                _ = self.keep_alive.tick() => {
+                    if let Err(e) = self.client.user_stream_keep_alive(&self.listen_key).await {
+                        log::warn!("user data stream keep alive: {e}, restarting");
+                        return Some(self.restart().await);
+                    }
+                }
+            }
+        }
+    }
+
+    async fn restart(&mut self) -> Result<BinanceWebsocketMessage> {
+        self.websocket
+            .unsubscribe(&Subscription::UserData(self.listen_key.clone()));
+        self.listen_key = self.client.user_stream_start().await?.listen_key;
+        subscribe(&mut self.websocket, &self.listen_key).await?;
+        self.keep_alive.reset();
+        Ok(BinanceWebsocketMessage::UserStreamRestarted(
+            self.listen_key.clone(),
+        ))
+    }
+}
+
+// Watched for silence, the websocket's stale timeout skips user data streams unless set per
+// subscription
+async fn subscribe(websocket: &mut BinanceWebsocket, listen_key: &str) -> Result<()> {
+    let subscription = Subscription::UserData(listen_key.into());
+    websocket.set_stale_timeout(&subscription, Some(STALE_TIMEOUT));
+    websocket.subscribe(&subscription).await
+}
+
+// Events that fail to parse leave the connection intact
+fn is_connection_error(e: &anyhow::Error) -> bool {
+    !matches!(e.downcast_ref(), Some(Error::UnknownEvent(_))) && !e.is::<serde_json::Error>()
+}
+
+#[cfg(test)]
+mod test {
+    use super::is_connection_error;
+    use crate::{error::Error, model::websocket::parse_event};
+    use anyhow::anyhow;
+
+    #[test]
+    fn connection_errors() {
+        assert!(is_connection_error(&anyhow!("error: ConnectionClosed")));
+        assert!(is_connection_error(&anyhow!(
+            "Socket closed without a close frame"
+        )));
+        assert!(is_connection_error(&Error::NoStreamSubscribed.into()));
+        assert!(!is_connection_error(
+            &parse_event(r#"{"e":"new"}"#).unwrap_err()
+        ));
+        assert!(!is_connection_error(&parse_event("{").unwrap_err()));
+    }
+}
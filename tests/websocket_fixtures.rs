@@ -0,0 +1,46 @@
+use anyhow::{Context, Result};
+use binance_async::model::websocket::parse_event;
+use std::{fs, path::Path};
+
+// Every payload in `tests/fixtures/websocket` must parse into the variant its file is named after
+#[test]
+fn recorded_payloads() -> Result<()> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/websocket");
+    let mut failures = Vec::new();
+    let mut checked = 0;
+
+    for entry in fs::read_dir(&dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "json") {
+            continue;
+        }
+        let name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .unwrap_or_default();
+        let variant = name.split('-').next().unwrap_or_default();
+        let payload = fs::read_to_string(&path).with_context(|| path.display().to_string())?;
+
+        checked += 1;
+        match parse_event(&payload) {
+            Ok(message) => {
+                let parsed = format!("{message:?}");
+                if !parsed.starts_with(&format!("{variant}(")) {
+                    failures.push(format!("{name}: parsed as {parsed:.60}"));
+                }
+            }
+            Err(e) => failures.push(format!("{name}: {e:#}")),
+        }
+    }
+
+    assert!(checked > 0, "no fixtures found in {}", dir.display());
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+    Ok(())
+}
+
+#[test]
+fn unknown_payloads() {
+    assert!(parse_event(r#"{"e":"somethingNew","E":1}"#).is_err());
+    assert!(parse_event(r#"{"result":null,"id":1}"#).is_err());
+    assert!(parse_event("not json").is_err());
+}